@@ -12,10 +12,14 @@ const HELP_TEMPLATE: &str = "{about}\n\n{usage-heading} {usage}\n\n{all-args}{af
     disable_help_subcommand = true
 )]
 pub struct Cli {
-    /// Increase diagnostic output. Repeat for full error chains.
+    /// Increase diagnostic output: -v info, -vv debug, -vvv trace. Also prints full error chains.
     #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     pub verbose: u8,
 
+    /// Disable progress spinners and bars, even when stderr is a terminal.
+    #[arg(long, global = true)]
+    pub no_progress: bool,
+
     #[command(subcommand)]
     pub(crate) command: Command,
 }
@@ -31,7 +35,7 @@ impl Cli {
     }
 
     pub async fn run(self) -> eyre::Result<()> {
-        let mut context = Context::new(self.verbose);
+        let mut context = Context::new(self.verbose, !self.no_progress);
         self.command.run(&mut context).await
     }
 }
@@ -85,6 +89,17 @@ mod tests {
         assert!(Cli::try_parse_from(["bento", "list", "--json"]).is_err());
     }
 
+    #[test]
+    fn no_progress_is_a_global_flag() {
+        let cli = Cli::try_parse_from(["bento", "image", "pull", "alpine", "--no-progress"])
+            .expect("no-progress after subcommand should parse");
+        assert!(cli.no_progress);
+
+        let cli =
+            Cli::try_parse_from(["bento", "list"]).expect("list without no-progress should parse");
+        assert!(!cli.no_progress);
+    }
+
     #[test]
     fn edit_command_is_not_available() {
         assert!(Cli::try_parse_from(["bento", "edit"]).is_err());
@@ -96,5 +111,6 @@ mod tests {
 
         assert!(!help.contains("cleanup"));
         assert!(!help.contains("shell-proxy"));
+        assert!(!help.contains("completions"));
     }
 }
@@ -0,0 +1,54 @@
+use std::io::Write as _;
+
+use clap::Args;
+use clap_complete::Shell;
+use eyre::Context as _;
+
+use crate::app::Cli;
+use crate::context::Context;
+
+#[derive(Debug, Args)]
+#[command(about = "Print a shell completion script", hide = true)]
+pub struct Cmd {
+    /// Shell to generate completions for.
+    #[arg(value_enum, value_name = "SHELL")]
+    shell: Shell,
+}
+
+impl Cmd {
+    pub async fn run(self, _context: &mut Context) -> eyre::Result<()> {
+        let mut script = Vec::new();
+        write_completions(self.shell, &mut script);
+        std::io::stdout()
+            .write_all(&script)
+            .context("write completion script")
+    }
+}
+
+/// Completions are registered under the `bento` binary name, not the name it was invoked with.
+///
+/// clap_complete panics on write errors, so callers render into a buffer first.
+fn write_completions(shell: Shell, out: &mut Vec<u8>) {
+    let mut command = Cli::command();
+    let bin_name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, bin_name, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use clap_complete::Shell;
+
+    use crate::commands::completions::write_completions;
+
+    #[test]
+    fn completions_cover_subcommands_for_bento() {
+        let mut out = Vec::new();
+
+        write_completions(Shell::Bash, &mut out);
+        let script = String::from_utf8(out).expect("utf8 script");
+
+        assert!(script.contains("_bento()"));
+        assert!(script.contains("create"));
+        assert!(script.contains("image"));
+    }
+}
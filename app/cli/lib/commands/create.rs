@@ -1,20 +1,25 @@
 use std::collections::BTreeMap;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
 use clap::Args;
 use eyre::Context as _;
-use libvm::{MachineNetworkConfig, Memory};
+use libvm::{MachineNetworkConfig, Memory, RootDiskClone};
 use utils::HumanSize;
 use vm_spec::Mount;
 
+use crate::commands::kernel::{find_kernel_bundle, DEFAULT_KERNEL_BUNDLE};
 use crate::commands::profile::{parse_label, parse_machine_network_config, parse_profile_mount};
-use crate::commands::rootfs_image::{get_base_rootfs_image, record_base_rootfs_metadata};
+use crate::commands::rootfs_image::{
+    get_base_rootfs_image, get_cached_base_rootfs_image, get_overlay_template,
+    overlay_template_path, record_base_rootfs_metadata, verify_base_rootfs_arch,
+};
 use crate::commands::start_options::machine_start_options;
 use crate::config::GlobalConfig;
-use crate::constants::{DEFAULT_PROFILE_NAME, PROFILE_METADATA_KEY};
+use crate::constants::{CONFIG_METADATA_KEY, DEFAULT_PROFILE_NAME, PROFILE_METADATA_KEY};
 use crate::context::Context;
-use crate::profile::{resolve_host_path, MountMode, ProfileMount, ProfileStore};
-use crate::ui::{watch_image_progress, Spinner};
+use crate::profile::{resolve_host_path, MountMode, NamedProfile, ProfileMount, ProfileStore};
+use crate::ui::{self, watch_image_progress, Spinner};
 
 const EXAMPLES: &[&str] = &[
     "bento create dev --start --default",
@@ -22,6 +27,10 @@ const EXAMPLES: &[&str] = &[
     "bento create dev --profile rust-dev",
     "bento create ubuntu --image ubuntu:24.04",
     "bento create dev rust-dev --image disk:./target/rootfs.img",
+    "cat user-data.yaml | bento create dev --userdata -",
+    "bento create dev --kernel-bundle lts",
+    "bento create dev --mount ./src:/src --dry-run",
+    "bento create dev --config ./dev.yaml --cpus 4",
 ];
 
 #[derive(Debug, Args)]
@@ -39,6 +48,10 @@ pub struct Cmd {
     /// Profile name. Alternative to the positional profile argument.
     #[arg(long = "profile")]
     pub profile_name: Option<String>,
+    /// Profile file to create the VM from instead of a named profile.
+    /// Flags override the matching fields from the file.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["profile", "profile_name"])]
+    pub config: Option<PathBuf>,
     /// Image reference to create from. Overrides the profile image when both are set.
     #[arg(long)]
     pub image: Option<String>,
@@ -48,6 +61,17 @@ pub struct Cmd {
     /// Make the created VM the default for commands that omit VM.
     #[arg(long)]
     pub default: bool,
+    /// Print the VM spec that would be written and exit without creating anything.
+    /// The image must already be cached; nothing is pulled.
+    #[arg(long, conflicts_with_all = ["start", "default"])]
+    pub dry_run: bool,
+    /// Copy the base rootfs in full instead of cloning it copy-on-write.
+    #[arg(long)]
+    pub copy: bool,
+    /// Attach the cached base rootfs read-only with a writable overlay disk
+    /// instead of cloning it.
+    #[arg(long, conflicts_with = "copy")]
+    pub overlay: bool,
     #[command(flatten)]
     pub(crate) overrides: VmOverrideArgs,
 }
@@ -60,9 +84,15 @@ pub(crate) struct VmOverrideArgs {
     /// Virtual machine RAM size, for example 512mb or 4gb.
     #[arg(long, value_name = "SIZE")]
     pub memory: Option<HumanSize>,
+    /// Memory the balloon device never reclaims below, for example 1gb.
+    #[arg(long, value_name = "SIZE")]
+    pub min_memory: Option<HumanSize>,
     /// Path to a custom kernel. Only works for Linux.
     #[arg(long)]
     pub kernel: Option<PathBuf>,
+    /// Kernel bundle installed with `bento kernel install`. `--kernel` takes precedence.
+    #[arg(long, value_name = "NAME")]
+    pub kernel_bundle: Option<String>,
     /// Path to a custom initramfs image. Only works for Linux.
     #[arg(long = "initramfs", visible_alias = "initrd")]
     pub initramfs: Option<PathBuf>,
@@ -70,12 +100,24 @@ pub(crate) struct VmOverrideArgs {
     #[arg(long, value_name = "SIZE")]
     pub disk_size: Option<HumanSize>,
     /// Enable nested virtualization for supported VZ guests.
-    #[arg(long)]
+    #[arg(long, visible_alias = "nested")]
     pub nested_virtualization: bool,
     /// Enable Rosetta for x86_64 Linux binaries in supported VZ guests.
     #[arg(long)]
     pub rosetta: bool,
-    /// Path to userdata file.
+    /// Attach a graphics device and pointer for graphical VZ guests.
+    #[arg(long)]
+    pub graphics: bool,
+    /// Attach a USB keyboard for graphical VZ guests.
+    #[arg(long)]
+    pub keyboard: bool,
+    /// Do not attach the virtio entropy device.
+    #[arg(long)]
+    pub no_entropy: bool,
+    /// Do not attach the memory balloon, for example for predictable benchmarks.
+    #[arg(long)]
+    pub no_memory_balloon: bool,
+    /// Path to a cloud-init userdata file, or `-` to read it from stdin.
     #[arg(long, value_name = "PATH")]
     pub userdata: Option<PathBuf>,
     /// Path to an existing disk image.
@@ -85,11 +127,15 @@ pub(crate) struct VmOverrideArgs {
     #[arg(long = "mount", value_name = "SRC:DST[:MODE]", value_parser = parse_profile_mount)]
     pub mounts: Vec<ProfileMount>,
     /// Override the profile network target. Allowed: private, none, NAME, or name:NAME.
+    /// `none` attaches no network device, so the VM runs fully offline.
     #[arg(long, value_parser = parse_machine_network_config)]
     pub network: Option<MachineNetworkConfig>,
     /// Add or override a label. Format: KEY=VALUE.
     #[arg(long = "label", value_name = "KEY=VALUE", value_parser = parse_label)]
     pub labels: Vec<(String, String)>,
+    /// Use the image even when its architecture does not match the host.
+    #[arg(long)]
+    pub allow_arch_mismatch: bool,
 }
 
 impl VmOverrideArgs {
@@ -100,6 +146,13 @@ impl VmOverrideArgs {
             .map_err(eyre::Report::msg)
     }
 
+    pub(crate) fn min_memory_mib(&self) -> eyre::Result<Option<u32>> {
+        self.min_memory
+            .map(HumanSize::memory_mib)
+            .transpose()
+            .map_err(eyre::Report::msg)
+    }
+
     pub(crate) fn disk_size_bytes(&self) -> eyre::Result<Option<u64>> {
         self.disk_size
             .map(HumanSize::storage_bytes)
@@ -110,28 +163,45 @@ impl VmOverrideArgs {
 
 impl Cmd {
     pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
-        let mut progress = Spinner::start("Reading", "VM recipe");
+        let show_progress = context.progress();
+        let mut progress = Spinner::start(show_progress, "Reading", "VM recipe");
         let mut resolved = self.resolve()?;
         let runtime = context.runtime().await?;
         progress.step("Finding", "boot assets");
+        let kernel_configured = resolved.kernel.is_some() || resolved.kernel_bundle.is_some();
         let boot_assets = resolve_boot_assets(
             runtime.local_data_dir(),
             resolved.kernel.take(),
             resolved.initramfs.take(),
-        );
+            resolved.kernel_bundle.as_deref(),
+        )?;
+        if !kernel_configured {
+            require_default_kernel(&boot_assets.kernel)?;
+        }
+        if resolved.nested_virtualization {
+            require_nested_virtualization()?;
+        }
         progress.finish_clear();
-        let base_rootfs = {
+        // A dry run must not pull, convert or write anything, so it only uses the local cache.
+        let base_rootfs = if self.dry_run {
+            get_cached_base_rootfs_image(runtime, &resolved.image_ref)?
+        } else {
             let (image_progress, image_events) = ocidisk::ImageProgressSender::default_channel();
             let image_progress_task =
-                watch_image_progress(resolved.image_ref.clone(), image_events);
+                watch_image_progress(resolved.image_ref.clone(), image_events, !show_progress);
             let image =
                 get_base_rootfs_image(runtime, &resolved.image_ref, Some(image_progress)).await;
             let _ = image_progress_task.await;
             image?
         };
+        verify_base_rootfs_arch(&base_rootfs, self.overrides.allow_arch_mismatch)?;
         record_base_rootfs_metadata(&mut resolved.metadata, &base_rootfs);
-        let progress = Spinner::start("Creating", &self.name);
-        let machine = runtime
+        let overlay_template = match (self.overlay, self.dry_run) {
+            (false, _) => None,
+            (true, true) => Some(overlay_template_path(runtime)?),
+            (true, false) => Some(get_overlay_template(runtime)?),
+        };
+        let builder = runtime
             .machine(resolved.image_ref.clone(), base_rootfs.path)
             .name(self.name.clone())
             .labels(resolved.labels)
@@ -142,18 +212,34 @@ impl Cmd {
                     .memory_mib
                     .map(|memory| Memory::mebibytes(u64::from(memory))),
             )
+            .maybe_min_memory(
+                resolved
+                    .min_memory_mib
+                    .map(|memory| Memory::mebibytes(u64::from(memory))),
+            )
             .kernel(boot_assets.kernel)
             .maybe_initramfs(boot_assets.initramfs)
             .maybe_root_disk_size(resolved.disk_size_bytes)
+            .copy_root_disk(self.copy)
+            .maybe_overlay_root_disk(overlay_template)
             .nested_virtualization(resolved.nested_virtualization)
             .rosetta(resolved.rosetta)
+            .graphics(resolved.graphics)
+            .keyboard(resolved.keyboard)
+            .entropy(resolved.entropy)
+            .memory_balloon(resolved.memory_balloon)
             .maybe_userdata(resolved.userdata)
             .disks(resolved.disks)
             .mounts(resolved.mounts)
-            .network(resolved.network)
-            .create()
-            .await?;
+            .network(resolved.network);
+        if self.dry_run {
+            return ui::print_json(&builder.dry_run().await?);
+        }
+
+        let progress = Spinner::start(show_progress, "Creating", &self.name);
+        let (machine, root_disk) = builder.create_with_root_disk().await?;
         progress.finish_success("Created");
+        eprintln!("root disk: {}", root_disk_note(root_disk));
 
         if self.default {
             GlobalConfig::write_default_machine(Some(&self.name))?;
@@ -161,7 +247,7 @@ impl Cmd {
         }
 
         if self.start {
-            let progress = Spinner::start("Starting", &self.name);
+            let progress = Spinner::start(show_progress, "Starting", &self.name);
             machine
                 .start_with(machine_start_options(runtime, &machine)?)
                 .await?;
@@ -177,7 +263,7 @@ impl Cmd {
         }
         let profile_name = self.profile.clone().or_else(|| self.profile_name.clone());
         let profile_name = profile_name.or_else(|| {
-            if self.image.is_none() {
+            if self.image.is_none() && self.config.is_none() {
                 Some(DEFAULT_PROFILE_NAME.to_string())
             } else {
                 None
@@ -192,16 +278,28 @@ impl Cmd {
         let mut cpus = None;
         let mut memory_mib = None;
         let mut disk_size_bytes = None;
-        let mut resolved_image_ref = if let Some(profile_name) = profile_name {
-            let store = ProfileStore::from_env()?;
-            let named = store.resolve(&profile_name)?;
+        let named = match (&self.config, profile_name) {
+            (Some(path), _) => Some(NamedProfile::from_path(path)?),
+            (None, Some(profile_name)) => Some(ProfileStore::from_env()?.resolve(&profile_name)?),
+            (None, None) => None,
+        };
+        let mut resolved_image_ref = if let Some(named) = named {
             network = named.profile.machine_network();
             userdata = named.profile.userdata.clone();
             cpus = named.profile.cpus();
             memory_mib = named.profile.memory_mib()?;
             disk_size_bytes = named.profile.disk_size_bytes()?;
             labels = named.profile.labels.clone();
-            metadata.insert(PROFILE_METADATA_KEY.to_string(), named.name.clone());
+            match &self.config {
+                Some(path) => {
+                    let path = std::path::absolute(path)
+                        .with_context(|| format!("resolve config path {}", path.display()))?;
+                    metadata.insert(CONFIG_METADATA_KEY.to_string(), path.display().to_string());
+                }
+                None => {
+                    metadata.insert(PROFILE_METADATA_KEY.to_string(), named.name.clone());
+                }
+            }
             mounts = named.profile.resolved_mounts()?;
             for (key, value) in &self.overrides.labels {
                 labels.insert(key.clone(), value.clone());
@@ -242,16 +340,23 @@ impl Cmd {
             userdata,
             cpus: self.overrides.cpus.or(cpus),
             memory_mib: self.overrides.memory_mib()?.or(memory_mib),
+            min_memory_mib: self.overrides.min_memory_mib()?,
             kernel: self.overrides.kernel.clone(),
             initramfs: self.overrides.initramfs.clone(),
+            kernel_bundle: self.overrides.kernel_bundle.clone(),
             disk_size_bytes: self.overrides.disk_size_bytes()?.or(disk_size_bytes),
             nested_virtualization: self.overrides.nested_virtualization,
             rosetta: self.overrides.rosetta,
+            graphics: self.overrides.graphics,
+            keyboard: self.overrides.keyboard,
+            entropy: !self.overrides.no_entropy,
+            memory_balloon: !self.overrides.no_memory_balloon,
             disks: self.overrides.disks.clone(),
         })
     }
 }
 
+#[derive(Debug)]
 pub(crate) struct BootAssets {
     pub(crate) kernel: PathBuf,
     pub(crate) initramfs: Option<PathBuf>,
@@ -261,12 +366,72 @@ pub(crate) fn resolve_boot_assets(
     data_dir: &Path,
     kernel: Option<PathBuf>,
     initramfs: Option<PathBuf>,
-) -> BootAssets {
-    let assets_dir = data_dir.join("assets");
-    BootAssets {
-        kernel: kernel.unwrap_or_else(|| assets_dir.join("default")),
-        initramfs,
+    kernel_bundle: Option<&str>,
+) -> eyre::Result<BootAssets> {
+    if let Some(kernel) = kernel {
+        return Ok(BootAssets { kernel, initramfs });
     }
+
+    if let Some(name) = kernel_bundle {
+        let bundle = find_kernel_bundle(data_dir, name)?.ok_or_else(|| {
+            eyre::eyre!(
+                "kernel bundle {name} is not installed; install it with `bento kernel install <KERNEL> --name {name}`"
+            )
+        })?;
+        return Ok(BootAssets {
+            kernel: bundle.kernel,
+            initramfs: initramfs.or(bundle.initramfs),
+        });
+    }
+
+    Ok(match find_kernel_bundle(data_dir, DEFAULT_KERNEL_BUNDLE)? {
+        Some(bundle) => BootAssets {
+            kernel: bundle.kernel,
+            initramfs: initramfs.or(bundle.initramfs),
+        },
+        None => BootAssets {
+            kernel: data_dir.join("assets").join("default"),
+            initramfs,
+        },
+    })
+}
+
+/// Describes how the root disk was created for the line printed after create.
+pub(crate) fn root_disk_note(root_disk: RootDiskClone) -> String {
+    match root_disk {
+        RootDiskClone::CopyOnWrite => "instant clone (CoW)".to_string(),
+        RootDiskClone::Copied { size_bytes } => {
+            format!("copied {}", ui::human_bytes(Some(size_bytes)))
+        }
+        RootDiskClone::Overlay { size_bytes } => format!(
+            "read-only base with {} overlay",
+            ui::human_bytes(Some(size_bytes))
+        ),
+    }
+}
+
+/// Fails before any work is done when the host cannot run nested guests.
+pub(crate) fn require_nested_virtualization() -> eyre::Result<()> {
+    if libvm::host_capabilities()?.nested_virtualization {
+        return Ok(());
+    }
+
+    eyre::bail!(
+        "nested virtualization is not supported on this host; run `bento doctor` to see host capabilities"
+    )
+}
+
+/// Fails with install guidance when no kernel was configured and the default
+/// kernel is missing. Configured kernel paths are validated by vmmon at start.
+pub(crate) fn require_default_kernel(kernel: &Path) -> eyre::Result<()> {
+    if kernel.is_file() {
+        return Ok(());
+    }
+
+    eyre::bail!(
+        "no default kernel found at {}; install one with `bento kernel install <KERNEL>` or pass --kernel <PATH>",
+        kernel.display()
+    )
 }
 
 struct ResolvedCreate {
@@ -278,11 +443,17 @@ struct ResolvedCreate {
     userdata: Option<String>,
     cpus: Option<u8>,
     memory_mib: Option<u32>,
+    min_memory_mib: Option<u32>,
     kernel: Option<PathBuf>,
     initramfs: Option<PathBuf>,
+    kernel_bundle: Option<String>,
     disk_size_bytes: Option<u64>,
     nested_virtualization: bool,
     rosetta: bool,
+    graphics: bool,
+    keyboard: bool,
+    entropy: bool,
+    memory_balloon: bool,
     disks: Vec<PathBuf>,
 }
 
@@ -294,8 +465,39 @@ pub(crate) fn profile_mount_to_mount(mount: &ProfileMount) -> eyre::Result<Mount
     })
 }
 
+/// Header lines cloud-init recognizes at the start of a userdata document.
+const USERDATA_HEADERS: &[&str] = &["#cloud-config", "#!", "## template", "#include"];
+
 pub(crate) fn read_userdata_path(path: &Path) -> eyre::Result<String> {
-    std::fs::read_to_string(path).with_context(|| format!("read userdata {}", path.display()))
+    let (userdata, source) = if path == Path::new("-") {
+        let mut userdata = String::new();
+        std::io::stdin()
+            .read_to_string(&mut userdata)
+            .context("read userdata from stdin")?;
+        (userdata, "stdin".to_string())
+    } else {
+        let userdata = std::fs::read_to_string(path)
+            .with_context(|| format!("read userdata {}", path.display()))?;
+        (userdata, path.display().to_string())
+    };
+    validate_userdata(&userdata, &source)?;
+    Ok(userdata)
+}
+
+fn validate_userdata(userdata: &str, source: &str) -> eyre::Result<()> {
+    let content = userdata.trim_start();
+    if content.is_empty() {
+        eyre::bail!("userdata from {source} is empty");
+    }
+    if !USERDATA_HEADERS
+        .iter()
+        .any(|header| content.starts_with(header))
+    {
+        ui::warn(format!(
+            "userdata from {source} does not start with #cloud-config, #!, ## template or #include; cloud-init may ignore it"
+        ));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -305,12 +507,39 @@ mod tests {
     use clap::Parser;
 
     use crate::app::Cli;
-    use crate::commands::create::resolve_boot_assets;
+    use libvm::RootDiskClone;
+
+    use crate::commands::create::{
+        require_default_kernel, resolve_boot_assets, root_disk_note, validate_userdata,
+    };
     use crate::commands::Command;
+    use crate::constants::{CONFIG_METADATA_KEY, PROFILE_METADATA_KEY};
+
+    #[test]
+    fn root_disk_note_distinguishes_clone_from_copy() {
+        assert_eq!(
+            root_disk_note(RootDiskClone::CopyOnWrite),
+            "instant clone (CoW)"
+        );
+        assert!(root_disk_note(RootDiskClone::Copied {
+            size_bytes: 4 * 1024 * 1024 * 1024
+        })
+        .starts_with("copied 4"));
+    }
+
+    #[test]
+    fn create_overlay_conflicts_with_copy() {
+        let cli = Cli::try_parse_from(["bento", "create", "dev", "--overlay"])
+            .expect("overlay should parse");
+        assert!(matches!(cli.command, Command::Create(create) if create.overlay));
+
+        assert!(Cli::try_parse_from(["bento", "create", "dev", "--overlay", "--copy"]).is_err());
+    }
 
     #[test]
     fn default_boot_assets_use_flat_data_assets_dir() {
-        let assets = resolve_boot_assets(Path::new("/data/bento"), None, None);
+        let assets = resolve_boot_assets(Path::new("/data/bento"), None, None, None)
+            .expect("resolve boot assets");
 
         assert_eq!(assets.kernel, PathBuf::from("/data/bento/assets/default"));
         assert_eq!(assets.initramfs, None);
@@ -322,7 +551,9 @@ mod tests {
             Path::new("/data/bento"),
             Some(PathBuf::from("./kernel")),
             None,
-        );
+            None,
+        )
+        .expect("resolve boot assets");
 
         assert_eq!(assets.kernel, PathBuf::from("./kernel"));
         assert_eq!(assets.initramfs, None);
@@ -334,7 +565,9 @@ mod tests {
             Path::new("/data/bento"),
             None,
             Some(PathBuf::from("./initrd.img")),
-        );
+            None,
+        )
+        .expect("resolve boot assets");
 
         assert_eq!(assets.kernel, PathBuf::from("/data/bento/assets/default"));
         assert_eq!(assets.initramfs, Some(PathBuf::from("./initrd.img")));
@@ -365,6 +598,68 @@ mod tests {
         assert!(create.default);
     }
 
+    #[test]
+    fn create_dry_run_conflicts_with_start_and_default() {
+        let cli = Cli::try_parse_from(["bento", "create", "dev", "--dry-run"])
+            .expect("create command should parse");
+
+        assert!(matches!(cli.command, Command::Create(create) if create.dry_run));
+        assert!(Cli::try_parse_from(["bento", "create", "dev", "--dry-run", "--start"]).is_err());
+        assert!(Cli::try_parse_from(["bento", "create", "dev", "--dry-run", "--default"]).is_err());
+    }
+
+    #[test]
+    fn create_and_run_accept_nested_shorthand() {
+        let create = Cli::try_parse_from(["bento", "create", "dev", "--nested"])
+            .expect("create command should parse");
+        let run = Cli::try_parse_from(["bento", "run", "dev", "--nested"])
+            .expect("run command should parse");
+
+        assert!(matches!(
+            create.command,
+            Command::Create(create) if create.overrides.nested_virtualization
+        ));
+        assert!(matches!(
+            run.command,
+            Command::Run(run) if run.overrides.nested_virtualization
+        ));
+    }
+
+    #[test]
+    fn create_config_file_is_overridden_by_flags() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = dir.path().join("dev.yaml");
+        std::fs::write(
+            &config,
+            "version: \"1\"\nimage: \"ubuntu:24.04\"\nresources:\n  cpus: 2\n  memory: 1gb\n",
+        )
+        .expect("write config");
+        let config = config.to_str().expect("utf8 path");
+        let cli =
+            Cli::try_parse_from(["bento", "create", "dev", "--config", config, "--cpus", "4"])
+                .expect("create command should parse");
+        let Command::Create(create) = cli.command else {
+            panic!("expected create command");
+        };
+
+        let resolved = create.resolve().expect("resolve create");
+
+        assert_eq!(resolved.image_ref, "ubuntu:24.04");
+        assert_eq!(resolved.cpus, Some(4));
+        assert_eq!(resolved.memory_mib, Some(1024));
+        assert_eq!(resolved.metadata.get(PROFILE_METADATA_KEY), None);
+        assert_eq!(
+            resolved
+                .metadata
+                .get(CONFIG_METADATA_KEY)
+                .map(String::as_str),
+            Some(config)
+        );
+        assert!(
+            Cli::try_parse_from(["bento", "create", "dev", "base", "--config", config]).is_err()
+        );
+    }
+
     #[test]
     fn create_command_parses_vm_overrides() {
         let cli = Cli::try_parse_from([
@@ -376,6 +671,8 @@ mod tests {
             "4",
             "--memory",
             "4gb",
+            "--min-memory",
+            "1gb",
             "--kernel",
             "./vmlinuz",
             "--initrd",
@@ -384,6 +681,8 @@ mod tests {
             "40gb",
             "--nested-virtualization",
             "--rosetta",
+            "--graphics",
+            "--no-memory-balloon",
             "--userdata",
             "./user-data.yaml",
             "--disk",
@@ -405,12 +704,20 @@ mod tests {
             create.overrides.memory_mib().expect("memory mib"),
             Some(4096)
         );
+        assert_eq!(
+            create.overrides.min_memory_mib().expect("min memory mib"),
+            Some(1024)
+        );
         assert_eq!(
             create.overrides.disk_size_bytes().expect("disk size bytes"),
             Some(40 * 1024 * 1024 * 1024)
         );
         assert!(create.overrides.nested_virtualization);
         assert!(create.overrides.rosetta);
+        assert!(create.overrides.graphics);
+        assert!(!create.overrides.keyboard);
+        assert!(!create.overrides.no_entropy);
+        assert!(create.overrides.no_memory_balloon);
         assert_eq!(create.overrides.disks.len(), 1);
         assert_eq!(create.overrides.mounts.len(), 1);
         assert_eq!(
@@ -430,4 +737,74 @@ mod tests {
                 .is_err()
         );
     }
+
+    #[test]
+    fn validate_userdata_rejects_empty_content() {
+        let error = validate_userdata(" \n\t", "stdin").expect_err("empty userdata");
+
+        assert_eq!(error.to_string(), "userdata from stdin is empty");
+        assert!(validate_userdata("#cloud-config\npackages: [git]\n", "stdin").is_ok());
+        assert!(validate_userdata("packages: [git]\n", "stdin").is_ok());
+    }
+
+    #[test]
+    fn missing_default_kernel_explains_how_to_install_one() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let assets =
+            resolve_boot_assets(dir.path(), None, None, None).expect("resolve boot assets");
+
+        let error = require_default_kernel(&assets.kernel).expect_err("missing default kernel");
+        assert!(error.to_string().contains("no default kernel found at"));
+        assert!(error.to_string().contains("--kernel <PATH>"));
+
+        std::fs::create_dir_all(dir.path().join("assets")).expect("create assets dir");
+        std::fs::write(&assets.kernel, b"kernel").expect("write kernel");
+        assert!(require_default_kernel(&assets.kernel).is_ok());
+    }
+
+    #[test]
+    fn default_kernel_bundle_is_preferred_when_installed() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let bundle_dir = dir.path().join("assets/kernels/default");
+        std::fs::create_dir_all(&bundle_dir).expect("create bundle dir");
+        std::fs::write(bundle_dir.join("kernel"), b"kernel").expect("write kernel");
+        std::fs::write(bundle_dir.join("initramfs"), b"initramfs").expect("write initramfs");
+
+        let assets =
+            resolve_boot_assets(dir.path(), None, None, None).expect("resolve boot assets");
+        let overridden =
+            resolve_boot_assets(dir.path(), None, Some(PathBuf::from("./initrd")), None)
+                .expect("resolve boot assets");
+
+        assert_eq!(assets.kernel, bundle_dir.join("kernel"));
+        assert_eq!(assets.initramfs, Some(bundle_dir.join("initramfs")));
+        assert_eq!(overridden.initramfs, Some(PathBuf::from("./initrd")));
+    }
+
+    #[test]
+    fn named_kernel_bundle_is_used_and_must_be_installed() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let bundle_dir = dir.path().join("assets/kernels/lts");
+        std::fs::create_dir_all(&bundle_dir).expect("create bundle dir");
+        std::fs::write(bundle_dir.join("kernel"), b"kernel").expect("write kernel");
+
+        let assets =
+            resolve_boot_assets(dir.path(), None, None, Some("lts")).expect("resolve boot assets");
+        let explicit = resolve_boot_assets(
+            dir.path(),
+            Some(PathBuf::from("./vmlinuz")),
+            None,
+            Some("lts"),
+        )
+        .expect("resolve boot assets");
+        let error = resolve_boot_assets(dir.path(), None, None, Some("edge"))
+            .expect_err("missing kernel bundle");
+
+        assert_eq!(assets.kernel, bundle_dir.join("kernel"));
+        assert_eq!(assets.initramfs, None);
+        assert_eq!(explicit.kernel, PathBuf::from("./vmlinuz"));
+        assert!(error
+            .to_string()
+            .contains("bento kernel install <KERNEL> --name edge"));
+    }
 }
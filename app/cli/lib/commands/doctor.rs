@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use clap::Args;
+use console::{style, Color};
+
+use crate::commands::kernel::{find_kernel_bundle, DEFAULT_KERNEL_BUNDLE};
+use crate::context::Context;
+use crate::ui;
+
+#[derive(Debug, Args)]
+#[command(about = "Check that this host can run BentoBox VMs")]
+pub struct Cmd {}
+
+/// Result of a single host check.
+#[derive(Debug)]
+struct Check {
+    name: &'static str,
+    outcome: Result<String, String>,
+    /// A failed critical check means VMs cannot start at all.
+    critical: bool,
+}
+
+impl Cmd {
+    pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
+        let mut checks = vec![Check {
+            name: "vmmon",
+            outcome: libvm::vmmon_executable()
+                .map(|path| path.display().to_string())
+                .map_err(|err| err.to_string()),
+            critical: true,
+        }];
+        #[cfg(target_os = "linux")]
+        checks.push(Check {
+            name: "kvm",
+            outcome: nix::unistd::access(
+                "/dev/kvm",
+                nix::unistd::AccessFlags::R_OK | nix::unistd::AccessFlags::W_OK,
+            )
+            .map(|()| "/dev/kvm is accessible".to_string())
+            .map_err(|err| format!("/dev/kvm: {err}")),
+            critical: true,
+        });
+        checks.push(Check {
+            name: "nested virtualization",
+            outcome: match libvm::host_capabilities() {
+                Ok(capabilities) if capabilities.nested_virtualization => {
+                    Ok("supported".to_string())
+                }
+                Ok(_) => Err("not supported on this host".to_string()),
+                Err(err) => Err(err.to_string()),
+            },
+            critical: false,
+        });
+
+        match context.runtime().await {
+            Ok(runtime) => {
+                let data_dir = runtime.local_data_dir().to_path_buf();
+                checks.push(Check {
+                    name: "data directory",
+                    outcome: writable_dir(&data_dir),
+                    critical: true,
+                });
+                checks.push(Check {
+                    name: "image store",
+                    outcome: writable_dir(runtime.local_images_dir()),
+                    critical: true,
+                });
+                checks.push(Check {
+                    name: "default kernel",
+                    outcome: match find_kernel_bundle(&data_dir, DEFAULT_KERNEL_BUNDLE) {
+                        Ok(Some(bundle)) => Ok(bundle.kernel.display().to_string()),
+                        Ok(None) => Err(
+                            "not installed, run `bento kernel install <KERNEL>` or pass --kernel"
+                                .to_string(),
+                        ),
+                        Err(err) => Err(err.to_string()),
+                    },
+                    critical: false,
+                });
+            }
+            Err(err) => checks.push(Check {
+                name: "runtime",
+                outcome: Err(format!("{err:#}")),
+                critical: true,
+            }),
+        }
+
+        print_checks(&checks);
+        let failed = failed_critical_checks(&checks);
+        if failed > 0 {
+            eyre::bail!("{failed} critical check(s) failed");
+        }
+        Ok(())
+    }
+}
+
+/// Confirms a directory accepts new files.
+///
+/// A missing directory passes when its closest existing ancestor is writable,
+/// since bento creates it on first use.
+fn writable_dir(dir: &Path) -> Result<String, String> {
+    let Some(existing) = dir.ancestors().find(|path| path.exists()) else {
+        return Err(format!("{} has no existing parent", dir.display()));
+    };
+    if !existing.is_dir() {
+        return Err(format!("{} is not a directory", existing.display()));
+    }
+
+    let probe = existing.join(format!(".bento-doctor-{}", std::process::id()));
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .and_then(|_| std::fs::remove_file(&probe))
+        .map_err(|err| format!("{} is not writable: {err}", existing.display()))?;
+
+    if existing == dir {
+        Ok(dir.display().to_string())
+    } else {
+        Ok(format!("{} (created on first use)", dir.display()))
+    }
+}
+
+fn failed_critical_checks(checks: &[Check]) -> usize {
+    checks
+        .iter()
+        .filter(|check| check.critical && check.outcome.is_err())
+        .count()
+}
+
+fn print_checks(checks: &[Check]) {
+    for check in checks {
+        let (mark, detail) = match &check.outcome {
+            Ok(detail) => (styled_mark("✓", Color::Green), detail),
+            Err(detail) if check.critical => (styled_mark("✗", Color::Red), detail),
+            Err(detail) => (styled_mark("!", Color::Yellow), detail),
+        };
+        println!("{mark} {}: {detail}", check.name);
+    }
+}
+
+fn styled_mark(mark: &str, color: Color) -> String {
+    if ui::should_style_stdout() {
+        style(mark).fg(color).to_string()
+    } else {
+        mark.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::doctor::{failed_critical_checks, writable_dir, Check};
+
+    #[test]
+    fn only_critical_failures_count() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("file");
+        std::fs::write(&file, b"").expect("write file");
+        let checks = [
+            Check {
+                name: "data directory",
+                outcome: writable_dir(&dir.path().join("bento")),
+                critical: true,
+            },
+            Check {
+                name: "image store",
+                outcome: writable_dir(&file.join("images")),
+                critical: true,
+            },
+            Check {
+                name: "default kernel",
+                outcome: Err("not installed".to_string()),
+                critical: false,
+            },
+        ];
+
+        assert!(checks[0].outcome.is_ok());
+        assert_eq!(failed_critical_checks(&checks), 1);
+        assert_eq!(std::fs::read_dir(dir.path()).expect("read dir").count(), 1);
+    }
+}
@@ -3,7 +3,7 @@ use eyre::bail;
 use libvm::MachineData;
 
 use crate::context::Context;
-use crate::ssh;
+use crate::ssh::{self, HostKeyPolicy};
 
 #[derive(Debug, Args)]
 #[command(about = "Execute a command in a running VM")]
@@ -16,6 +16,10 @@ pub struct Cmd {
     #[arg(long, short = 'u')]
     pub user: Option<String>,
 
+    /// Skip guest host key verification instead of pinning the key seen on first connect.
+    #[arg(long)]
+    pub insecure: bool,
+
     /// Guest command and arguments to execute after `--`.
     #[arg(required = true, last = true, allow_hyphen_values = true)]
     pub command: Vec<String>,
@@ -39,6 +43,7 @@ impl Cmd {
             &data_dir,
             &machine_name,
             self.user.as_deref(),
+            &HostKeyPolicy::for_machine(&inspect_data, self.insecure),
             &self.command,
         )?;
         std::process::exit(status.code().unwrap_or(1));
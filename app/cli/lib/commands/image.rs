@@ -0,0 +1,535 @@
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+use eyre::Context as _;
+use ocidisk::{
+    ImageProgressSender, ImageRecord, ImageRemoval, ImageStore, Platform, RootfsOptions,
+    StoreDiskUsage,
+};
+use serde::Serialize;
+
+use crate::context::Context;
+use crate::ui::{self, OutputFormat, Table};
+
+const EXAMPLES: &[&str] = &[
+    "bento image pull ghcr.io/vandycknick/archlinux:latest",
+    "bento image pull alpine --platform linux/amd64",
+    "bento image import ./rootfs.img.zst --name local/base",
+    "bento image list",
+    "bento image inspect alpine",
+    "bento image inspect sha256:0123abcd --format json",
+    "bento image tag alpine dev",
+    "bento image untag dev",
+    "bento image rm dev",
+    "bento image rm 0123abcd --force",
+    "bento image df",
+    "bento image gc",
+];
+
+#[derive(Debug, Args)]
+#[command(
+    about = "Manage cached base images",
+    after_help = crate::help::examples(EXAMPLES)
+)]
+pub struct Cmd {
+    #[command(subcommand)]
+    command: ImageSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum ImageSubcommand {
+    #[command(about = "Pull an image into the cache without creating a VM")]
+    Pull(PullCmd),
+    #[command(about = "Copy a raw ext4 disk image into the cache under a tag")]
+    Import(ImportCmd),
+    #[command(about = "List cached base images", visible_alias = "ls")]
+    List(ListCmd),
+    #[command(about = "Show cached base image details")]
+    Inspect(InspectCmd),
+    #[command(about = "Add a tag that points at a cached image")]
+    Tag(TagCmd),
+    #[command(about = "Remove a tag without deleting the image")]
+    Untag(UntagCmd),
+    #[command(
+        about = "Remove a tag, or the cached image once no other tag points at it",
+        visible_alias = "remove"
+    )]
+    Rm(RmCmd),
+    #[command(about = "Show disk space used by the image cache")]
+    Df(DfCmd),
+    #[command(about = "Remove leftover cache directories from interrupted pulls")]
+    Gc,
+}
+
+#[derive(Debug, Args)]
+struct PullCmd {
+    /// Image reference to pull.
+    #[arg(value_name = "IMAGE")]
+    image: String,
+
+    /// Platform to select from a multi-platform image. Defaults to the host platform.
+    #[arg(long, value_name = "OS/ARCH[/VARIANT]")]
+    platform: Option<Platform>,
+}
+
+#[derive(Debug, Args)]
+struct ImportCmd {
+    /// Raw ext4 disk image to import, optionally zstd-compressed.
+    #[arg(value_name = "DISK")]
+    disk: PathBuf,
+
+    /// Tag to give the imported image.
+    #[arg(long, value_name = "NAME")]
+    name: String,
+
+    /// Platform the disk was built for. Defaults to the host platform.
+    #[arg(long, value_name = "OS/ARCH[/VARIANT]")]
+    platform: Option<Platform>,
+}
+
+#[derive(Debug, Args)]
+struct ListCmd {
+    /// Output format.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = OutputFormat::Plain)]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Args)]
+struct DfCmd {
+    /// Output format.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = OutputFormat::Plain)]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Args)]
+struct InspectCmd {
+    /// Image reference, tag, or image ID.
+    #[arg(value_name = "IMAGE")]
+    image: String,
+
+    /// Output format.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = OutputFormat::Plain)]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Args)]
+struct TagCmd {
+    /// Existing image reference, tag, or image ID.
+    #[arg(value_name = "SOURCE")]
+    source: String,
+
+    /// New tag to point at the image.
+    #[arg(value_name = "TAG")]
+    tag: String,
+}
+
+#[derive(Debug, Args)]
+struct UntagCmd {
+    /// Tag to remove.
+    #[arg(value_name = "TAG")]
+    tag: String,
+}
+
+#[derive(Debug, Args)]
+struct RmCmd {
+    /// Tag, reference, or image id to remove.
+    #[arg(value_name = "IMAGE")]
+    image: String,
+
+    /// Delete the image even when other tags still point at it.
+    #[arg(long, short)]
+    force: bool,
+}
+
+impl Cmd {
+    pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
+        let show_progress = context.progress();
+        let store = image_store(context).await?;
+        match self.command {
+            ImageSubcommand::Pull(command) => pull_image(&store, command, show_progress).await,
+            ImageSubcommand::Import(command) => import_image(&store, command),
+            ImageSubcommand::List(command) => list_images(&store, command),
+            ImageSubcommand::Inspect(command) => inspect_image(&store, command),
+            ImageSubcommand::Tag(command) => tag_image(&store, command),
+            ImageSubcommand::Untag(command) => untag_image(&store, command),
+            ImageSubcommand::Rm(command) => remove_image(context, &store, command).await,
+            ImageSubcommand::Df(command) => image_disk_usage(&store, command),
+            ImageSubcommand::Gc => gc_images(&store),
+        }
+    }
+}
+
+pub(crate) async fn image_store(context: &mut Context) -> eyre::Result<ImageStore> {
+    let runtime = context.runtime().await?;
+    ImageStore::open(runtime.local_images_dir()).wrap_err("failed to open Bento image cache")
+}
+
+async fn pull_image(store: &ImageStore, command: PullCmd, show_progress: bool) -> eyre::Result<()> {
+    let platform = match command.platform {
+        Some(platform) => platform,
+        None => Platform::host().wrap_err("failed to select host OCI platform")?,
+    };
+    let (progress, events) = ImageProgressSender::default_channel();
+    let progress_task = ui::watch_image_progress(command.image.clone(), events, !show_progress);
+    let image = store
+        .get_or_create(&command.image, RootfsOptions::new(platform), Some(progress))
+        .await;
+    let _ = progress_task.await;
+    let image = image.wrap_err_with(|| format!("failed to pull {}", command.image))?;
+
+    ui::success(format!(
+        "pulled {} for {} ({})",
+        image.image_ref,
+        image.platform,
+        short_image_id(&image.image_id)
+    ));
+    Ok(())
+}
+
+fn import_image(store: &ImageStore, command: ImportCmd) -> eyre::Result<()> {
+    let platform = match command.platform {
+        Some(platform) => platform,
+        None => Platform::host().wrap_err("failed to select host OCI platform")?,
+    };
+    let record = store
+        .import_disk(&command.disk, &command.name, &platform)
+        .wrap_err_with(|| format!("failed to import {}", command.disk.display()))?;
+
+    ui::success(format!(
+        "imported {} as {} for {} ({})",
+        command.disk.display(),
+        record.tags.first().unwrap_or(&record.image_ref),
+        record.platform,
+        short_image_id(&record.image_id)
+    ));
+    Ok(())
+}
+
+fn list_images(store: &ImageStore, command: ListCmd) -> eyre::Result<()> {
+    let records = store.list().wrap_err("failed to list cached images")?;
+    match command.format {
+        OutputFormat::Json => ui::print_json(&records),
+        OutputFormat::Plain => {
+            let now = ui::now_unix();
+            let mut table = Table::new(["IMAGE ID", "REFERENCE", "PLATFORM", "SIZE", "CREATED"]);
+            for record in records {
+                table.add_row([
+                    short_image_id(&record.image_id).to_string(),
+                    record.image_ref,
+                    record.platform.to_string(),
+                    ui::human_bytes(Some(record.disk_usage_bytes)),
+                    ui::relative_time(record.created_at_unix, now),
+                ]);
+            }
+            table.print()
+        }
+    }
+}
+
+fn inspect_image(store: &ImageStore, command: InspectCmd) -> eyre::Result<()> {
+    let record = store
+        .inspect(&command.image)
+        .wrap_err_with(|| format!("failed to inspect image {}", command.image))?
+        .ok_or_else(|| eyre::eyre!("image `{}` not found", command.image))?;
+
+    match command.format {
+        OutputFormat::Json => ui::print_json(&record),
+        OutputFormat::Plain => print_human(&record),
+    }
+}
+
+fn tag_image(store: &ImageStore, command: TagCmd) -> eyre::Result<()> {
+    let record = store
+        .tag(&command.source, &command.tag)
+        .wrap_err_with(|| format!("failed to tag {} as {}", command.source, command.tag))?;
+    ui::success(format!(
+        "tagged {} as {}",
+        short_image_id(&record.image_id),
+        command.tag
+    ));
+    Ok(())
+}
+
+fn untag_image(store: &ImageStore, command: UntagCmd) -> eyre::Result<()> {
+    store
+        .untag(&command.tag)
+        .wrap_err_with(|| format!("failed to untag {}", command.tag))?;
+    ui::success(format!("untagged {}", command.tag));
+    Ok(())
+}
+
+async fn remove_image(
+    context: &mut Context,
+    store: &ImageStore,
+    command: RmCmd,
+) -> eyre::Result<()> {
+    let machines = attached_machines(context, store, &command.image).await?;
+    let removal = store
+        .remove_unless_attached(&command.image, command.force, &machines)
+        .wrap_err_with(|| format!("failed to remove image {}", command.image))?;
+    match removal {
+        ImageRemoval::Untagged {
+            tag,
+            image_id,
+            remaining_tags,
+        } => ui::success(format!(
+            "removed tag {tag}; image {} kept, still tagged as {}",
+            short_image_id(&image_id),
+            remaining_tags.join(", ")
+        )),
+        ImageRemoval::Removed { image_id, tags } if tags.is_empty() => {
+            ui::success(format!("removed image {}", short_image_id(&image_id)))
+        }
+        ImageRemoval::Removed { image_id, tags } => ui::success(format!(
+            "removed image {} and tags {}",
+            short_image_id(&image_id),
+            tags.join(", ")
+        )),
+    }
+    Ok(())
+}
+
+/// Machines that attach the rootfs of the image `reference` resolves to, such
+/// as overlay VMs. Unknown references resolve to none and removal reports them.
+///
+/// A store that cannot be read is an error, so removal never mistakes a broken
+/// index for an image nothing boots from.
+pub(crate) async fn attached_machines(
+    context: &mut Context,
+    store: &ImageStore,
+    reference: &str,
+) -> eyre::Result<Vec<String>> {
+    let Some(record) = store
+        .inspect(reference)
+        .wrap_err_with(|| format!("failed to resolve image {reference}"))?
+    else {
+        return Ok(Vec::new());
+    };
+    Ok(context
+        .runtime()
+        .await?
+        .machines_attaching_disk(&record.rootfs_path)
+        .await?)
+}
+
+#[derive(Serialize)]
+struct DiskUsageReport<'a> {
+    #[serde(flatten)]
+    usage: &'a StoreDiskUsage,
+    total_bytes: u64,
+}
+
+fn image_disk_usage(store: &ImageStore, command: DfCmd) -> eyre::Result<()> {
+    let usage = store
+        .disk_usage()
+        .wrap_err("failed to measure the image cache")?;
+    match command.format {
+        OutputFormat::Json => ui::print_json(&DiskUsageReport {
+            usage: &usage,
+            total_bytes: usage.total_bytes(),
+        }),
+        OutputFormat::Plain => {
+            let mut table = Table::new(["TYPE", "NAME", "SIZE"]);
+            for image in &usage.images {
+                let shared = if image.shared { ", shared rootfs" } else { "" };
+                table.add_row([
+                    "image".to_string(),
+                    format!("{} ({}{shared})", image.image_ref, image.platform),
+                    ui::human_bytes(Some(image.bytes)),
+                ]);
+            }
+            table.add_row([
+                "layers".to_string(),
+                "shared layer cache".to_string(),
+                ui::human_bytes(Some(usage.layer_cache_bytes)),
+            ]);
+            table.add_row([
+                "index".to_string(),
+                "tag index".to_string(),
+                ui::human_bytes(Some(usage.index_bytes)),
+            ]);
+            for orphan in &usage.orphans {
+                table.add_row([
+                    "orphan".to_string(),
+                    orphan.path.display().to_string(),
+                    ui::human_bytes(Some(orphan.bytes)),
+                ]);
+            }
+            table.add_row([
+                "total".to_string(),
+                "-".to_string(),
+                ui::human_bytes(Some(usage.total_bytes())),
+            ]);
+            table.print()?;
+            if !usage.orphans.is_empty() {
+                ui::warn(format!(
+                    "{} orphaned cache entries can be removed with `bento image gc`",
+                    usage.orphans.len()
+                ));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn gc_images(store: &ImageStore) -> eyre::Result<()> {
+    let removed = store
+        .gc_orphans()
+        .wrap_err("failed to clean up the image cache")?;
+    for path in &removed {
+        println!("{}", path.display());
+    }
+    ui::success(format!("removed {} orphaned cache entries", removed.len()));
+    Ok(())
+}
+
+fn print_human(record: &ImageRecord) -> eyre::Result<()> {
+    let mut rows = vec![
+        ("ID", record.image_id.clone()),
+        ("Reference", record.image_ref.clone()),
+    ];
+    if !record.tags.is_empty() {
+        rows.push(("Tags", record.tags.join(", ")));
+    }
+    rows.push(("Source", record.source.to_string()));
+    rows.push(("Platform", record.platform.to_string()));
+    if let Some(manifest_digest) = &record.manifest_digest {
+        rows.push(("Manifest", manifest_digest.clone()));
+    }
+    if let Some(config_digest) = &record.config_digest {
+        rows.push(("Config", config_digest.clone()));
+    }
+    if !record.layers.is_empty() {
+        rows.push(("Layers", record.layers.len().to_string()));
+    }
+    if let Some(created) = &record.provenance.created {
+        rows.push(("Built", created.clone()));
+    }
+    if let Some(source) = &record.provenance.source {
+        rows.push(("Built from", source.clone()));
+    }
+    if let Some(revision) = &record.provenance.revision {
+        rows.push(("Revision", revision.clone()));
+    }
+    rows.push(("Filesystem", record.filesystem.clone()));
+    rows.push(("Rootfs", record.rootfs_path.display().to_string()));
+    rows.push(("Size", ui::human_bytes(Some(record.size_bytes))));
+    rows.push(("On disk", ui::human_bytes(Some(record.disk_usage_bytes))));
+    rows.push(("Created", ui::format_unix(record.created_at_unix)));
+    ui::print_detail_rows(&rows)
+}
+
+pub(crate) fn short_image_id(image_id: &str) -> &str {
+    let encoded = image_id
+        .rsplit_once(':')
+        .map(|(_, encoded)| encoded)
+        .unwrap_or(image_id);
+    encoded.get(..12).unwrap_or(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::app::Cli;
+    use crate::commands::image::{short_image_id, ImageSubcommand};
+    use crate::commands::Command;
+    use crate::ui::OutputFormat;
+
+    #[test]
+    fn images_alias_parses_inspect() {
+        let cli = Cli::try_parse_from(["bento", "images", "inspect", "alpine", "--format", "json"])
+            .expect("images inspect should parse");
+
+        let Command::Image(image) = cli.command else {
+            panic!("expected image command");
+        };
+        let ImageSubcommand::Inspect(inspect) = image.command else {
+            panic!("expected image inspect command");
+        };
+
+        assert_eq!(inspect.image, "alpine");
+        assert_eq!(inspect.format, OutputFormat::Json);
+    }
+
+    #[test]
+    fn image_pull_parses_platform() {
+        let cli = Cli::try_parse_from([
+            "bento",
+            "image",
+            "pull",
+            "alpine",
+            "--platform",
+            "linux/arm64/v8",
+        ])
+        .expect("image pull should parse");
+
+        let Command::Image(image) = cli.command else {
+            panic!("expected image command");
+        };
+        let ImageSubcommand::Pull(pull) = image.command else {
+            panic!("expected image pull command");
+        };
+
+        assert_eq!(pull.image, "alpine");
+        assert_eq!(
+            pull.platform
+                .map(|platform| platform.to_string())
+                .as_deref(),
+            Some("linux/arm64/v8")
+        );
+        assert!(
+            Cli::try_parse_from(["bento", "image", "pull", "alpine", "--platform", "linux"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn image_import_parses_disk_and_name() {
+        let cli = Cli::try_parse_from([
+            "bento",
+            "image",
+            "import",
+            "./rootfs.img",
+            "--name",
+            "local/base",
+        ])
+        .expect("image import should parse");
+
+        let Command::Image(image) = cli.command else {
+            panic!("expected image command");
+        };
+        let ImageSubcommand::Import(import) = image.command else {
+            panic!("expected image import command");
+        };
+
+        assert_eq!(import.disk, std::path::PathBuf::from("./rootfs.img"));
+        assert_eq!(import.name, "local/base");
+        assert!(Cli::try_parse_from(["bento", "image", "import", "./rootfs.img"]).is_err());
+    }
+
+    #[test]
+    fn image_tag_parses_source_and_tag() {
+        let cli = Cli::try_parse_from(["bento", "image", "tag", "alpine", "dev"])
+            .expect("image tag should parse");
+
+        let Command::Image(image) = cli.command else {
+            panic!("expected image command");
+        };
+        let ImageSubcommand::Tag(tag) = image.command else {
+            panic!("expected image tag command");
+        };
+
+        assert_eq!(tag.source, "alpine");
+        assert_eq!(tag.tag, "dev");
+    }
+
+    #[test]
+    fn short_image_id_drops_algorithm() {
+        assert_eq!(
+            short_image_id("sha256:0123456789abcdef0123"),
+            "0123456789ab"
+        );
+        assert_eq!(short_image_id("tar-sha256:abc"), "abc");
+    }
+}
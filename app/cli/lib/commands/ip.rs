@@ -0,0 +1,119 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use clap::Args;
+use libvm::{MachineData, MachineNetworkConfig, MachineStatus};
+
+use crate::commands::start_options::parse_duration;
+use crate::context::Context;
+
+/// How often `--wait` checks whether the guest reported an address.
+const ADDRESS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+const EXAMPLES: &[&str] = &[
+    "bento ip dev",
+    "bento ip dev --wait --timeout 2m",
+    "ssh user@$(bento ip dev --wait)",
+];
+
+#[derive(Debug, Args)]
+#[command(
+    about = "Print the IP address of a running VM",
+    after_help = crate::help::examples(EXAMPLES)
+)]
+pub struct Cmd {
+    /// Name or ID of the VM. Defaults to the configured default VM.
+    #[arg(value_name = "VM")]
+    name: Option<String>,
+
+    /// Wait until the guest reports an address.
+    #[arg(long)]
+    wait: bool,
+
+    /// Give up waiting after this long, for example 30s or 2m.
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "wait")]
+    timeout: Option<Duration>,
+}
+
+impl Cmd {
+    pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
+        let (_name, machine) = context.machine(self.name.as_deref()).await?;
+        let deadline = self
+            .timeout
+            .map(|timeout| tokio::time::Instant::now() + timeout);
+
+        loop {
+            let data = machine.inspect().await?;
+            if let Some(address) = guest_address(&data)? {
+                println!("{address}");
+                return Ok(());
+            }
+            if !self.wait {
+                eyre::bail!(
+                    "VM {} has not reported an IP address yet; pass --wait to block until it does",
+                    data.name
+                );
+            }
+            if deadline.is_some_and(|deadline| tokio::time::Instant::now() >= deadline) {
+                eyre::bail!(
+                    "timed out waiting for VM {} to report an IP address",
+                    data.name
+                );
+            }
+            tokio::time::sleep(ADDRESS_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Returns the guest's primary address, or `None` while it has not reported one.
+fn guest_address(data: &MachineData) -> eyre::Result<Option<IpAddr>> {
+    if matches!(data.network, MachineNetworkConfig::None) {
+        eyre::bail!("VM {} has networking disabled", data.name);
+    }
+    if !matches!(
+        data.status,
+        MachineStatus::Starting { .. } | MachineStatus::Running { .. }
+    ) {
+        eyre::bail!("VM {} is not running", data.name);
+    }
+    Ok(primary_address(&data.guest_addresses))
+}
+
+/// Prefers the first IPv4 address, falling back to the first IPv6 address.
+fn primary_address(addresses: &[String]) -> Option<IpAddr> {
+    let addresses = addresses
+        .iter()
+        .filter_map(|address| address.parse::<IpAddr>().ok())
+        .collect::<Vec<_>>();
+    addresses
+        .iter()
+        .find(|address| address.is_ipv4())
+        .or_else(|| addresses.first())
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use crate::commands::ip::primary_address;
+
+    #[test]
+    fn primary_address_prefers_ipv4() {
+        let addresses = [
+            "fd00::5".to_string(),
+            "not-an-ip".to_string(),
+            "192.168.64.5".to_string(),
+        ];
+
+        assert_eq!(
+            primary_address(&addresses),
+            Some("192.168.64.5".parse::<IpAddr>().expect("ip"))
+        );
+        assert_eq!(
+            primary_address(&addresses[..1]),
+            Some("fd00::5".parse::<IpAddr>().expect("ip"))
+        );
+        assert_eq!(primary_address(&[]), None);
+    }
+}
@@ -0,0 +1,266 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Subcommand};
+use eyre::Context as _;
+
+use crate::context::Context;
+use crate::ui::{self, Table};
+
+pub(crate) const DEFAULT_KERNEL_BUNDLE: &str = "default";
+
+const KERNEL_FILE_NAME: &str = "kernel";
+const INITRAMFS_FILE_NAME: &str = "initramfs";
+
+const EXAMPLES: &[&str] = &[
+    "bento kernel install ./vmlinuz",
+    "bento kernel install ./vmlinuz --initramfs ./initrd.img --name lts",
+    "bento kernel list",
+];
+
+#[derive(Debug, Args)]
+#[command(
+    about = "Manage kernel bundles used to boot VMs",
+    after_help = crate::help::examples(EXAMPLES)
+)]
+pub struct Cmd {
+    #[command(subcommand)]
+    command: KernelSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum KernelSubcommand {
+    #[command(about = "Copy a kernel and optional initramfs into a bundle")]
+    Install(InstallCmd),
+    #[command(about = "List installed kernel bundles", visible_alias = "ls")]
+    List,
+}
+
+#[derive(Debug, Args)]
+struct InstallCmd {
+    /// Path to the kernel image.
+    #[arg(value_name = "KERNEL")]
+    kernel: PathBuf,
+
+    /// Path to an initramfs to install next to the kernel.
+    #[arg(long, value_name = "PATH")]
+    initramfs: Option<PathBuf>,
+
+    /// Bundle name. VMs created without `--kernel` boot the `default` bundle.
+    #[arg(long, value_name = "NAME", default_value = DEFAULT_KERNEL_BUNDLE)]
+    name: String,
+}
+
+/// Files in an installed kernel bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KernelBundle {
+    pub(crate) name: String,
+    pub(crate) kernel: PathBuf,
+    pub(crate) initramfs: Option<PathBuf>,
+}
+
+impl Cmd {
+    pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
+        let data_dir = context.runtime().await?.local_data_dir().to_path_buf();
+        match self.command {
+            KernelSubcommand::Install(command) => {
+                let bundle = install_kernel_bundle(
+                    &data_dir,
+                    &command.name,
+                    &command.kernel,
+                    command.initramfs.as_deref(),
+                )?;
+                ui::success(format!("Installed kernel bundle {}", bundle.name));
+                Ok(())
+            }
+            KernelSubcommand::List => print_bundles(&list_kernel_bundles(&data_dir)?),
+        }
+    }
+}
+
+pub(crate) fn kernel_bundles_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("assets").join("kernels")
+}
+
+/// Looks up an installed bundle by name. Returns `None` when its kernel is missing.
+pub(crate) fn find_kernel_bundle(
+    data_dir: &Path,
+    name: &str,
+) -> eyre::Result<Option<KernelBundle>> {
+    validate_bundle_name(name)?;
+    let dir = kernel_bundles_dir(data_dir).join(name);
+    let kernel = dir.join(KERNEL_FILE_NAME);
+    if !kernel.is_file() {
+        return Ok(None);
+    }
+
+    let initramfs = dir.join(INITRAMFS_FILE_NAME);
+    Ok(Some(KernelBundle {
+        name: name.to_string(),
+        kernel,
+        initramfs: initramfs.is_file().then_some(initramfs),
+    }))
+}
+
+fn install_kernel_bundle(
+    data_dir: &Path,
+    name: &str,
+    kernel: &Path,
+    initramfs: Option<&Path>,
+) -> eyre::Result<KernelBundle> {
+    validate_bundle_name(name)?;
+    require_regular_file(kernel, "kernel")?;
+    if let Some(initramfs) = initramfs {
+        require_regular_file(initramfs, "initramfs")?;
+    }
+
+    let bundles_dir = kernel_bundles_dir(data_dir);
+    let dir = bundles_dir.join(name);
+    let staging = bundles_dir.join(format!(".{name}.staging"));
+    match std::fs::remove_dir_all(&staging) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => {
+            return Err(err).with_context(|| format!("remove {}", staging.display()));
+        }
+    }
+    std::fs::create_dir_all(&staging)
+        .with_context(|| format!("create kernel bundle directory {}", staging.display()))?;
+    std::fs::copy(kernel, staging.join(KERNEL_FILE_NAME))
+        .with_context(|| format!("copy kernel {}", kernel.display()))?;
+    if let Some(initramfs) = initramfs {
+        std::fs::copy(initramfs, staging.join(INITRAMFS_FILE_NAME))
+            .with_context(|| format!("copy initramfs {}", initramfs.display()))?;
+    }
+
+    match std::fs::remove_dir_all(&dir) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err).with_context(|| format!("replace {}", dir.display())),
+    }
+    std::fs::rename(&staging, &dir)
+        .with_context(|| format!("install kernel bundle {}", dir.display()))?;
+
+    find_kernel_bundle(data_dir, name)?
+        .ok_or_else(|| eyre::eyre!("kernel bundle {name} is missing after install"))
+}
+
+fn list_kernel_bundles(data_dir: &Path) -> eyre::Result<Vec<KernelBundle>> {
+    let bundles_dir = kernel_bundles_dir(data_dir);
+    let entries = match std::fs::read_dir(&bundles_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("read {}", bundles_dir.display()));
+        }
+    };
+
+    let mut bundles = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if validate_bundle_name(&name).is_err() {
+            continue;
+        }
+        if let Some(bundle) = find_kernel_bundle(data_dir, &name)? {
+            bundles.push(bundle);
+        }
+    }
+    bundles.sort_by(|left, right| left.name.cmp(&right.name));
+    Ok(bundles)
+}
+
+fn print_bundles(bundles: &[KernelBundle]) -> eyre::Result<()> {
+    let mut table = Table::new(["NAME", "KERNEL", "INITRAMFS"]);
+    for bundle in bundles {
+        table.add_row([
+            bundle.name.clone(),
+            bundle.kernel.display().to_string(),
+            bundle
+                .initramfs
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+    table.print()
+}
+
+fn validate_bundle_name(name: &str) -> eyre::Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'));
+    if !valid {
+        eyre::bail!(
+            "invalid kernel bundle name {name:?}: use letters, digits, '-', '_' or '.', not starting with '.'"
+        );
+    }
+    Ok(())
+}
+
+fn require_regular_file(path: &Path, description: &str) -> eyre::Result<()> {
+    let metadata = std::fs::metadata(path)
+        .with_context(|| format!("{description} {} does not exist", path.display()))?;
+    if !metadata.is_file() {
+        eyre::bail!("{description} {} is not a regular file", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::kernel::{
+        find_kernel_bundle, install_kernel_bundle, list_kernel_bundles, validate_bundle_name,
+    };
+
+    #[test]
+    fn install_copies_files_into_named_bundle() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let data_dir = dir.path().join("bento");
+        let kernel = dir.path().join("vmlinuz");
+        let initramfs = dir.path().join("initrd.img");
+        std::fs::write(&kernel, b"kernel").expect("write kernel");
+        std::fs::write(&initramfs, b"initramfs").expect("write initramfs");
+
+        let bundle = install_kernel_bundle(&data_dir, "lts", &kernel, Some(&initramfs))
+            .expect("install bundle");
+        let replaced =
+            install_kernel_bundle(&data_dir, "lts", &kernel, None).expect("reinstall bundle");
+
+        assert_eq!(bundle.kernel, data_dir.join("assets/kernels/lts/kernel"));
+        assert_eq!(
+            std::fs::read(&bundle.kernel).expect("read kernel"),
+            b"kernel"
+        );
+        assert_eq!(replaced.initramfs, None);
+        assert_eq!(
+            find_kernel_bundle(&data_dir, "lts").expect("find bundle"),
+            Some(replaced)
+        );
+        assert_eq!(list_kernel_bundles(&data_dir).expect("list").len(), 1);
+    }
+
+    #[test]
+    fn install_rejects_missing_and_non_regular_sources() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let data_dir = dir.path().join("bento");
+
+        assert!(
+            install_kernel_bundle(&data_dir, "default", &dir.path().join("nope"), None).is_err()
+        );
+        assert!(install_kernel_bundle(&data_dir, "default", dir.path(), None).is_err());
+        assert!(!data_dir.join("assets/kernels/default").exists());
+    }
+
+    #[test]
+    fn bundle_names_cannot_escape_the_bundles_dir() {
+        assert!(validate_bundle_name("default").is_ok());
+        assert!(validate_bundle_name("6.12-lts").is_ok());
+        assert!(validate_bundle_name("../default").is_err());
+        assert!(validate_bundle_name(".staging").is_err());
+        assert!(validate_bundle_name("").is_err());
+    }
+}
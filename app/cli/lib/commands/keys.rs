@@ -0,0 +1,62 @@
+use clap::{Args, Subcommand};
+
+use crate::context::Context;
+use crate::ssh;
+use crate::ui;
+
+const EXAMPLES: &[&str] = &["bento keys show", "bento keys regenerate"];
+
+#[derive(Debug, Args)]
+#[command(
+    about = "Manage the SSH key bento uses to reach guests",
+    after_help = crate::help::examples(EXAMPLES)
+)]
+pub struct Cmd {
+    #[command(subcommand)]
+    command: KeysSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum KeysSubcommand {
+    #[command(about = "Print the public key injected into guests")]
+    Show,
+    #[command(about = "Replace the SSH keypair with a new one")]
+    Regenerate,
+}
+
+impl Cmd {
+    pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
+        let data_dir = context.runtime().await?.local_data_dir().to_path_buf();
+        match self.command {
+            KeysSubcommand::Show => {
+                println!("{}", ssh::public_key(&data_dir)?);
+            }
+            KeysSubcommand::Regenerate => {
+                let public_key = ssh::regenerate(&data_dir)?;
+                println!("{public_key}");
+                ui::success("Regenerated bento SSH key");
+                ui::warn(
+                    "running VMs keep accepting the old key until they are restarted or re-created",
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::app::Cli;
+    use crate::commands::Command;
+
+    #[test]
+    fn keys_command_parses_subcommands() {
+        let cli = Cli::try_parse_from(["bento", "keys", "regenerate"])
+            .expect("keys command should parse");
+
+        assert!(matches!(cli.command, Command::Keys(_)));
+        assert!(Cli::try_parse_from(["bento", "keys"]).is_err());
+    }
+}
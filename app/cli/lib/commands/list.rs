@@ -1,8 +1,10 @@
 use clap::Args;
+use libvm::BrokenMachine;
+use serde::Serialize;
 
 use crate::context::Context;
 use crate::ui::{self, OutputFormat, Table};
-use crate::view::MachineView;
+use crate::view::{BrokenMachineView, MachineView};
 
 #[derive(Debug, Args)]
 #[command(about = "List VMs")]
@@ -15,7 +17,9 @@ pub struct Cmd {
 impl Cmd {
     pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
         let default_machine = context.config()?.default_machine().map(str::to_string);
-        let machines = context.runtime().await?.list_machines().await?;
+        let runtime = context.runtime().await?;
+        let machines = runtime.list_machines().await?;
+        let broken = runtime.list_broken_machines().await?;
         let mut views = Vec::with_capacity(machines.len());
 
         for machine in machines {
@@ -27,16 +31,34 @@ impl Cmd {
         }
 
         match self.format {
-            OutputFormat::Json => ui::print_json(&views),
-            OutputFormat::Plain => print_table(&views),
+            OutputFormat::Json => {
+                let entries = views
+                    .iter()
+                    .map(ListEntry::Machine)
+                    .chain(
+                        broken
+                            .iter()
+                            .map(|machine| ListEntry::Broken(BrokenMachineView::new(machine))),
+                    )
+                    .collect::<Vec<_>>();
+                ui::print_json(&entries)
+            }
+            OutputFormat::Plain => print_table(&views, &broken),
         }
     }
 }
 
-fn print_table(views: &[MachineView]) -> eyre::Result<()> {
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum ListEntry<'a> {
+    Machine(&'a MachineView),
+    Broken(BrokenMachineView),
+}
+
+fn print_table(views: &[MachineView], broken: &[BrokenMachine]) -> eyre::Result<()> {
     let now = ui::now_unix();
     let mut table = Table::new([
-        "ID", "NAME", "STATE", "CPUS", "MEMORY", "DISK", "CREATED", "DEFAULT",
+        "ID", "NAME", "STATE", "IMAGE", "CPUS", "MEMORY", "DISK", "UPTIME", "CREATED", "DEFAULT",
     ]);
 
     for view in views {
@@ -44,13 +66,37 @@ fn print_table(views: &[MachineView]) -> eyre::Result<()> {
             ui::short_id(&view.id).to_string(),
             view.name.clone(),
             view.state.to_string(),
+            view.image.clone(),
             view.resources.cpus.to_string(),
             ui::human_memory_mib(Some(view.resources.memory_mib)),
             ui::human_bytes(view.root_disk_size),
+            view.uptime(now).unwrap_or_else(|| "-".to_string()),
             ui::relative_time(view.created_at, now),
             if view.default { "*" } else { "-" }.to_string(),
         ]);
     }
 
-    table.print()
+    for machine in broken {
+        table.add_row([
+            ui::short_id(&machine.id).to_string(),
+            machine.name.clone(),
+            "broken".to_string(),
+            "-".to_string(),
+            "-".to_string(),
+            "-".to_string(),
+            "-".to_string(),
+            "-".to_string(),
+            "-".to_string(),
+            "-".to_string(),
+        ]);
+    }
+
+    table.print()?;
+    for machine in broken {
+        ui::warn(format!(
+            "{} has an unreadable config: {}",
+            machine.name, machine.reason
+        ));
+    }
+    Ok(())
 }
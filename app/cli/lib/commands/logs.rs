@@ -1,10 +1,16 @@
-use std::io::{Read, Seek, SeekFrom, Write};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use clap::Args;
 
 use crate::context::Context;
 
+/// How often `--follow` checks the log for new output.
+const LOG_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 #[derive(Debug, Args)]
 #[command(about = "Show VM logs")]
 pub struct Cmd {
@@ -12,7 +18,7 @@ pub struct Cmd {
     #[arg(value_name = "VM")]
     name: Option<String>,
 
-    /// Continue streaming logs as they are written.
+    /// Continue streaming logs as they are written until interrupted.
     #[arg(long)]
     follow: bool,
 }
@@ -35,21 +41,120 @@ impl Cmd {
             return Ok(());
         }
 
-        let mut file = std::fs::File::open(&path)?;
-        let mut position = file.seek(SeekFrom::Start(0))?;
-
+        let mut follower = LogFollower::open(path)?;
+        let shutdown = tokio::signal::ctrl_c();
+        tokio::pin!(shutdown);
         loop {
-            file.seek(SeekFrom::Start(position))?;
-            let mut buffer = Vec::new();
-            file.read_to_end(&mut buffer)?;
+            let buffer = follower.poll()?;
             if !buffer.is_empty() {
-                position = position.saturating_add(u64::try_from(buffer.len()).unwrap_or(u64::MAX));
                 let stdout = std::io::stdout();
                 let mut out = stdout.lock();
                 out.write_all(&buffer)?;
                 out.flush()?;
             }
-            tokio::time::sleep(Duration::from_millis(500)).await;
+            tokio::select! {
+                result = &mut shutdown => {
+                    result?;
+                    return Ok(());
+                }
+                _ = tokio::time::sleep(LOG_POLL_INTERVAL) => {}
+            }
+        }
+    }
+}
+
+/// Tails a log file across truncation and rotation.
+///
+/// When the file shrinks below the read offset, or the path now names a
+/// different inode, reading restarts from the beginning of the current file.
+struct LogFollower {
+    path: PathBuf,
+    file: File,
+    inode: u64,
+    position: u64,
+}
+
+impl LogFollower {
+    fn open(path: PathBuf) -> io::Result<Self> {
+        let file = File::open(&path)?;
+        let inode = file.metadata()?.ino();
+        Ok(Self {
+            path,
+            file,
+            inode,
+            position: 0,
+        })
+    }
+
+    fn poll(&mut self) -> io::Result<Vec<u8>> {
+        match std::fs::metadata(&self.path) {
+            Ok(metadata) if metadata.ino() != self.inode => {
+                self.file = File::open(&self.path)?;
+                self.inode = metadata.ino();
+                self.position = 0;
+            }
+            Ok(_) => {}
+            // Rotated away and not recreated yet; keep draining the old file.
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+
+        if self.file.metadata()?.len() < self.position {
+            self.position = 0;
         }
+
+        self.file.seek(SeekFrom::Start(self.position))?;
+        let mut buffer = Vec::new();
+        self.file.read_to_end(&mut buffer)?;
+        self.position = self
+            .position
+            .saturating_add(u64::try_from(buffer.len()).unwrap_or(u64::MAX));
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use crate::commands::logs::LogFollower;
+
+    #[test]
+    fn follower_restarts_after_truncation() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("vmmon.log");
+        std::fs::write(&path, "booting\nstarting services\n").expect("write log");
+        let mut follower = LogFollower::open(path.clone()).expect("open follower");
+        assert_eq!(
+            follower.poll().expect("poll"),
+            b"booting\nstarting services\n"
+        );
+
+        std::fs::OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .open(&path)
+            .expect("truncate log")
+            .write_all(b"running\n")
+            .expect("write after truncation");
+
+        assert_eq!(follower.poll().expect("poll"), b"running\n");
+    }
+
+    #[test]
+    fn follower_reopens_rotated_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("vmmon.log");
+        std::fs::write(&path, "old generation\n").expect("write log");
+        let mut follower = LogFollower::open(path.clone()).expect("open follower");
+        assert_eq!(follower.poll().expect("poll"), b"old generation\n");
+
+        std::fs::rename(&path, dir.path().join("vmmon.log.1")).expect("rotate log");
+        std::fs::write(&path, "new generation, longer than before\n").expect("write new log");
+
+        assert_eq!(
+            follower.poll().expect("poll"),
+            b"new generation, longer than before\n"
+        );
     }
 }
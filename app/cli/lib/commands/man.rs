@@ -0,0 +1,71 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use eyre::Context as _;
+
+use crate::app::Cli;
+use crate::context::Context;
+
+#[derive(Debug, Args)]
+#[command(
+    about = "Write roff man pages for bento and its subcommands",
+    hide = true
+)]
+pub struct Cmd {
+    /// Directory to write the pages to. Created when missing.
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    out: PathBuf,
+}
+
+impl Cmd {
+    pub async fn run(self, _context: &mut Context) -> eyre::Result<()> {
+        std::fs::create_dir_all(&self.out)
+            .with_context(|| format!("create {}", self.out.display()))?;
+        for page in write_man_pages(&self.out)? {
+            println!("{}", page.display());
+        }
+        Ok(())
+    }
+}
+
+/// Writes `bento.1` plus one `bento-<sub>[-<sub>].1` page per visible subcommand.
+fn write_man_pages(out: &Path) -> eyre::Result<Vec<PathBuf>> {
+    let mut command = Cli::command();
+    command.build();
+    let mut pages = Vec::new();
+    write_command_pages(&command, out, &mut pages)?;
+    Ok(pages)
+}
+
+fn write_command_pages(
+    command: &clap::Command,
+    out: &Path,
+    pages: &mut Vec<PathBuf>,
+) -> eyre::Result<()> {
+    let page = clap_mangen::Man::new(command.clone())
+        .generate_to(out)
+        .with_context(|| format!("write man page for {}", command.get_name()))?;
+    pages.push(page);
+    for subcommand in command.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        write_command_pages(subcommand, out, pages)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::man::write_man_pages;
+
+    #[test]
+    fn writes_one_page_per_visible_subcommand() {
+        let dir = tempfile::tempdir().expect("tempdir");
+
+        let pages = write_man_pages(dir.path()).expect("write man pages");
+
+        assert!(pages.contains(&dir.path().join("bento.1")));
+        assert!(dir.path().join("bento-create.1").is_file());
+        assert!(dir.path().join("bento-image-list.1").is_file());
+        assert!(!dir.path().join("bento-cleanup.1").exists());
+        assert!(!dir.path().join("bento-man.1").exists());
+    }
+}
@@ -3,18 +3,29 @@ use clap::Subcommand;
 use crate::context::Context;
 
 pub mod cleanup;
+pub mod completions;
 pub mod create;
 pub mod default;
+pub mod doctor;
 pub mod exec;
+pub mod image;
+pub mod ip;
+pub mod kernel;
+pub mod keys;
 pub mod list;
 pub mod logs;
+pub mod man;
 pub mod network;
+pub mod pause;
 pub mod profile;
+pub mod rebuild;
 pub mod restart;
+pub mod resume;
 pub mod rm;
 mod rootfs_image;
 pub mod run;
 pub mod secret;
+pub mod services;
 pub mod set;
 pub mod shell;
 pub mod shell_proxy;
@@ -22,6 +33,7 @@ pub mod show;
 pub mod start;
 mod start_options;
 pub mod stop;
+pub mod wait;
 
 #[derive(Debug, Subcommand)]
 pub enum Command {
@@ -29,26 +41,41 @@ pub enum Command {
     Create(create::Cmd),
     #[command(hide = true)]
     Cleanup(cleanup::Cmd),
+    #[command(hide = true)]
+    Completions(completions::Cmd),
     Start(start::Cmd),
     Stop(stop::Cmd),
+    Pause(pause::Cmd),
+    Resume(resume::Cmd),
     Restart(restart::Cmd),
+    Rebuild(rebuild::Cmd),
     #[command(name = "default")]
     Default(default::Cmd),
+    Doctor(doctor::Cmd),
     Secret(secret::Cmd),
+    Services(services::Cmd),
     #[command(name = "rm")]
     Rm(rm::Cmd),
     Shell(shell::Cmd),
     Exec(exec::Cmd),
+    #[command(visible_alias = "images")]
+    Image(image::Cmd),
+    Ip(ip::Cmd),
+    Kernel(kernel::Cmd),
+    Keys(keys::Cmd),
     #[command(visible_alias = "ls")]
     List(list::Cmd),
     #[command(visible_alias = "status")]
     Show(show::Cmd),
     Logs(logs::Cmd),
+    #[command(hide = true)]
+    Man(man::Cmd),
     Network(network::Cmd),
     Profile(profile::Cmd),
     Set(set::Cmd),
-    #[command(hide = true)]
+    #[command(hide = true, alias = "proxy")]
     ShellProxy(shell_proxy::Cmd),
+    Wait(wait::Cmd),
 }
 
 impl Command {
@@ -57,21 +84,33 @@ impl Command {
             Self::Run(command) => command.run(context).await,
             Self::Create(command) => command.run(context).await,
             Self::Cleanup(command) => command.run(context).await,
+            Self::Completions(command) => command.run(context).await,
             Self::Start(command) => command.run(context).await,
             Self::Stop(command) => command.run(context).await,
+            Self::Pause(command) => command.run(context).await,
+            Self::Resume(command) => command.run(context).await,
             Self::Restart(command) => command.run(context).await,
+            Self::Rebuild(command) => command.run(context).await,
             Self::Default(command) => command.run(context).await,
+            Self::Doctor(command) => command.run(context).await,
             Self::Secret(command) => command.run(context).await,
+            Self::Services(command) => command.run(context).await,
             Self::Rm(command) => command.run(context).await,
             Self::Shell(command) => command.run(context).await,
             Self::Exec(command) => command.run(context).await,
+            Self::Image(command) => command.run(context).await,
+            Self::Ip(command) => command.run(context).await,
+            Self::Kernel(command) => command.run(context).await,
+            Self::Keys(command) => command.run(context).await,
             Self::List(command) => command.run(context).await,
             Self::Show(command) => command.run(context).await,
             Self::Logs(command) => command.run(context).await,
+            Self::Man(command) => command.run(context).await,
             Self::Network(command) => command.run(context).await,
             Self::Profile(command) => command.run(context).await,
             Self::Set(command) => command.run(context).await,
             Self::ShellProxy(command) => command.run(context).await,
+            Self::Wait(command) => command.run(context).await,
         }
     }
 }
@@ -0,0 +1,40 @@
+use clap::Args;
+use libvm::LibVmError;
+
+use crate::context::Context;
+use crate::ui::Spinner;
+
+#[derive(Debug, Args)]
+#[command(about = "Pause a running VM")]
+pub struct Cmd {
+    /// Name or ID of the VM to pause. Defaults to the configured default VM.
+    #[arg(value_name = "VM")]
+    name: Option<String>,
+}
+
+impl Cmd {
+    pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
+        let mut spinner = Spinner::start(
+            context.progress(),
+            "Finding",
+            self.name.as_deref().unwrap_or("default VM"),
+        );
+        let (name, machine) = context.machine(self.name.as_deref()).await?;
+
+        spinner.step("Pausing", &name);
+        match machine.pause().await {
+            Ok(_) => {}
+            Err(LibVmError::MachineNotRunning { .. }) => {
+                eyre::bail!("VM {name} is not running, only a running VM can be paused");
+            }
+            Err(LibVmError::MonitorProtocol { message, .. }) => {
+                eyre::bail!("could not pause {name}: {message}");
+            }
+            Err(err) => return Err(err.into()),
+        }
+
+        spinner.step("Paused", &name);
+        spinner.finish_success("Paused");
+        Ok(())
+    }
+}
@@ -2,12 +2,14 @@ use std::collections::BTreeMap;
 use std::process::Command;
 
 use clap::{Args, Subcommand};
+use eyre::Context as _;
 use libvm::MachineNetworkConfig;
 use utils::HumanSize;
 
 use crate::context::Context;
 use crate::profile::{
-    parse_profile, validate_profile, MountMode, NamedProfile, Profile, ProfileMount,
+    missing_mount_sources, parse_profile, parse_profile_unvalidated, profile_json_schema,
+    profile_problems, validate_profile, MountMode, NamedProfile, Profile, ProfileMount,
     ProfileNetwork, ProfileResources, ProfileStore,
 };
 use crate::ui::{self, OutputFormat, Table};
@@ -18,6 +20,7 @@ const EXAMPLES: &[&str] = &[
     "bento profile create dev --image ghcr.io/me/dev:latest",
     "bento profile edit dev",
     "bento profile validate dev",
+    "bento profile schema > profile.schema.json",
 ];
 
 const CREATE_EXAMPLES: &[&str] = &[
@@ -52,6 +55,8 @@ pub enum ProfileSubcommand {
     Validate(ValidateCmd),
     #[command(about = "Print a profile path")]
     Path(PathCmd),
+    #[command(about = "Print the JSON Schema for profile files")]
+    Schema,
 }
 
 #[derive(Debug, Args)]
@@ -151,6 +156,7 @@ impl Cmd {
             ProfileSubcommand::Rm(command) => remove_profile(&store, command),
             ProfileSubcommand::Validate(command) => validate_profile_arg(&store, command),
             ProfileSubcommand::Path(command) => print_profile_path(&store, command),
+            ProfileSubcommand::Schema => ui::print_json(&profile_json_schema()),
         }
     }
 }
@@ -296,15 +302,34 @@ fn remove_profile(store: &ProfileStore, command: RmCmd) -> eyre::Result<()> {
 
 fn validate_profile_arg(store: &ProfileStore, command: ValidateCmd) -> eyre::Result<()> {
     let path = std::path::PathBuf::from(&command.profile);
-    if path.components().count() > 1 || path.extension().is_some() {
-        let raw = std::fs::read_to_string(&path)?;
-        parse_profile(&raw)?;
-        ui::success(format!("valid {}", path.display()));
+    let (label, profile) = if path.components().count() > 1 || path.extension().is_some() {
+        (path.display().to_string(), read_profile_unvalidated(&path)?)
     } else {
-        let named = store.resolve(&command.profile)?;
-        ui::success(format!("valid {}", named.name));
+        match store.find_profile_path(&command.profile)? {
+            Some(path) => (command.profile, read_profile_unvalidated(&path)?),
+            None => {
+                let named = store.resolve(&command.profile)?;
+                (named.name, named.profile)
+            }
+        }
+    };
+
+    let mut problems = profile_problems(&profile);
+    problems.extend(missing_mount_sources(&profile));
+    if problems.is_empty() {
+        ui::success(format!("valid {label}"));
+        return Ok(());
     }
-    Ok(())
+    for problem in &problems {
+        eprintln!("{} {problem}", ui::error_label());
+    }
+    eyre::bail!("{label} has {} problem(s)", problems.len())
+}
+
+fn read_profile_unvalidated(path: &std::path::Path) -> eyre::Result<Profile> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("read profile {}", path.display()))?;
+    parse_profile_unvalidated(&raw).with_context(|| format!("parse profile {}", path.display()))
 }
 
 fn print_profile_path(store: &ProfileStore, command: PathCmd) -> eyre::Result<()> {
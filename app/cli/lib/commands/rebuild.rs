@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+
+use clap::Args;
+use libvm::MachineStatus;
+
+use crate::commands::create::root_disk_note;
+use crate::commands::image::short_image_id;
+use crate::commands::rootfs_image::{
+    get_base_rootfs_image, get_overlay_template, record_base_rootfs_metadata,
+    verify_base_rootfs_arch,
+};
+use crate::context::Context;
+use crate::ssh::forget_pinned_host_key;
+use crate::ui::{self, watch_image_progress, Spinner};
+
+const EXAMPLES: &[&str] = &["bento rebuild dev", "bento stop dev && bento rebuild dev"];
+
+#[derive(Debug, Args)]
+#[command(
+    about = "Reset a VM's root disk to a fresh copy of its base image",
+    after_help = crate::help::examples(EXAMPLES)
+)]
+pub struct Cmd {
+    /// Name or ID of the VM to rebuild.
+    #[arg(value_name = "VM")]
+    name: String,
+
+    /// Use the image even when its architecture does not match the host.
+    #[arg(long)]
+    allow_arch_mismatch: bool,
+}
+
+impl Cmd {
+    pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
+        let show_progress = context.progress();
+        let (_reference, machine) = context.machine(Some(&self.name)).await?;
+        let data = machine.inspect().await?;
+        if !matches!(
+            data.status,
+            MachineStatus::Stopped | MachineStatus::Error { .. }
+        ) {
+            eyre::bail!(
+                "VM {} is {}; stop it with `bento stop {}` before rebuilding",
+                data.name,
+                data.status.label(),
+                data.name
+            );
+        }
+        if data.image_ref.is_empty() {
+            eyre::bail!(
+                "VM {} has no recorded base image to rebuild from",
+                data.name
+            );
+        }
+
+        let runtime = context.runtime().await?;
+        let base_rootfs = {
+            let (image_progress, image_events) = ocidisk::ImageProgressSender::default_channel();
+            let image_progress_task =
+                watch_image_progress(data.image_ref.clone(), image_events, !show_progress);
+            let image = get_base_rootfs_image(runtime, &data.image_ref, Some(image_progress)).await;
+            let _ = image_progress_task.await;
+            image?
+        };
+        verify_base_rootfs_arch(&base_rootfs, self.allow_arch_mismatch)?;
+        let mut metadata = BTreeMap::new();
+        record_base_rootfs_metadata(&mut metadata, &base_rootfs);
+        let overlay_template = if data.has_overlay_root_disk() {
+            Some(get_overlay_template(runtime)?)
+        } else {
+            None
+        };
+
+        ui::warn(format!(
+            "rebuilding {} discards everything written to its root disk",
+            data.name
+        ));
+        let progress = Spinner::start(show_progress, "Rebuilding", &data.name);
+        let (_, root_disk) = machine
+            .rebuild_root_disk(&base_rootfs.path, overlay_template.as_deref(), metadata)
+            .await?;
+        forget_pinned_host_key(&data.machine_dir)?;
+        progress.finish_success("Rebuilt");
+        eprintln!(
+            "root disk: {} from {} ({})",
+            root_disk_note(root_disk),
+            base_rootfs.image_ref,
+            short_image_id(&base_rootfs.image_id)
+        );
+        Ok(())
+    }
+}
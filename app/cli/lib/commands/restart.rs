@@ -15,7 +15,11 @@ pub struct Cmd {
 
 impl Cmd {
     pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
-        let mut spinner = Spinner::start("Finding", self.name.as_deref().unwrap_or("default VM"));
+        let mut spinner = Spinner::start(
+            context.progress(),
+            "Finding",
+            self.name.as_deref().unwrap_or("default VM"),
+        );
         let (name, machine) = context.machine(self.name.as_deref()).await?;
 
         spinner.step("Stopping", &name);
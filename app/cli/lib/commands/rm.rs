@@ -1,6 +1,9 @@
 use clap::Args;
 use libvm::LibVmError;
+use ocidisk::OciDiskError;
 
+use crate::commands::image::{attached_machines, image_store, short_image_id};
+use crate::commands::rootfs_image::base_image_id;
 use crate::config::GlobalConfig;
 use crate::context::Context;
 use crate::ui::{self, Spinner};
@@ -15,14 +18,19 @@ pub struct Cmd {
     /// Stop the VM first if it is running.
     #[arg(long)]
     force: bool,
+
+    /// Also delete the VM's base image from the cache once no other VM uses it.
+    #[arg(long)]
+    prune_image: bool,
 }
 
 impl Cmd {
     pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
-        let mut spinner = Spinner::start("Finding", &self.name);
+        let mut spinner = Spinner::start(context.progress(), "Finding", &self.name);
         let (_reference, machine) = context.machine(Some(&self.name)).await?;
         let data = machine.inspect().await?;
         let machine_name = data.name;
+        let base_image = base_image_id(&data.metadata).map(str::to_string);
         let removed_default = context.config()?.default_machine() == Some(machine_name.as_str());
 
         if self.force {
@@ -47,6 +55,51 @@ impl Cmd {
         if removed_default {
             ui::warn("removed default machine. Set a new one with `bento default <vm>`.");
         }
+        if self.prune_image {
+            prune_base_image(context, &machine_name, base_image.as_deref()).await?;
+        }
         Ok(())
     }
 }
+
+async fn prune_base_image(
+    context: &mut Context,
+    machine_name: &str,
+    image_id: Option<&str>,
+) -> eyre::Result<()> {
+    let Some(image_id) = image_id else {
+        ui::warn(format!(
+            "{machine_name} has no recorded base image, nothing to prune"
+        ));
+        return Ok(());
+    };
+
+    let runtime = context.runtime().await?;
+    let mut users = Vec::new();
+    for machine in runtime.list_machines().await? {
+        let data = machine.inspect().await?;
+        if base_image_id(&data.metadata) == Some(image_id) {
+            users.push(data.name);
+        }
+    }
+    if !users.is_empty() {
+        ui::warn(format!(
+            "kept image {}, still used by {}",
+            short_image_id(image_id),
+            users.join(", ")
+        ));
+        return Ok(());
+    }
+
+    let store = image_store(context).await?;
+    let machines = attached_machines(context, &store, image_id).await?;
+    match store.remove_unless_attached(image_id, true, &machines) {
+        Ok(_) => ui::success(format!("removed image {}", short_image_id(image_id))),
+        Err(OciDiskError::TagNotFound { .. }) => ui::warn(format!(
+            "image {} is no longer cached",
+            short_image_id(image_id)
+        )),
+        Err(err) => return Err(err.into()),
+    }
+    Ok(())
+}
@@ -1,8 +1,13 @@
 use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 use eyre::Context as _;
 use libvm::Runtime;
-use ocidisk::{ImageProgressSender, ImageStore, RootfsImage, RootfsOptions};
+use ocidisk::{
+    ImageProgressSender, ImageStore, Platform, RootfsImage, RootfsImageSource, RootfsOptions,
+};
+
+use crate::ui;
 
 const IMAGE_ID_METADATA_KEY: &str = "bento.image.id";
 const IMAGE_PLATFORM_METADATA_KEY: &str = "bento.image.platform";
@@ -22,6 +27,82 @@ pub(crate) async fn get_base_rootfs_image(
         .wrap_err_with(|| format!("failed to get base rootfs image for {image_ref}"))
 }
 
+/// Resolves `image_ref` from the local image cache without pulling or converting it.
+pub(crate) fn get_cached_base_rootfs_image(
+    runtime: &Runtime,
+    image_ref: &str,
+) -> eyre::Result<RootfsImage> {
+    let options = RootfsOptions::for_host().wrap_err("failed to select host OCI platform")?;
+    let store = ImageStore::open(runtime.local_images_dir())
+        .wrap_err("failed to open Bento image cache")?;
+    store
+        .get_cached(image_ref, options)
+        .wrap_err_with(|| format!("failed to look up base rootfs image {image_ref}"))?
+        .ok_or_else(|| {
+            eyre::eyre!("image {image_ref} is not cached; run `bento image pull {image_ref}` first")
+        })
+}
+
+/// Returns the empty ext4 disk that overlay root disks are cloned from.
+pub(crate) fn get_overlay_template(runtime: &Runtime) -> eyre::Result<PathBuf> {
+    ImageStore::open(runtime.local_images_dir())
+        .and_then(|store| store.overlay_template())
+        .wrap_err("failed to prepare the overlay template disk")
+}
+
+/// Returns where the overlay template lives without creating it.
+pub(crate) fn overlay_template_path(runtime: &Runtime) -> eyre::Result<PathBuf> {
+    ImageStore::open(runtime.local_images_dir())
+        .map(|store| store.overlay_template_path())
+        .wrap_err("failed to open Bento image cache")
+}
+
+/// Refuses base images built for another architecture than the host.
+///
+/// Registry and OCI archive images record their architecture. Raw disks and rootfs
+/// tarballs do not, so those only get a warning.
+pub(crate) fn verify_base_rootfs_arch(
+    image: &RootfsImage,
+    allow_mismatch: bool,
+) -> eyre::Result<()> {
+    let host = Platform::host().wrap_err("failed to select host OCI platform")?;
+    let image_arch = match image.source {
+        RootfsImageSource::OciRegistry | RootfsImageSource::OciArchive => {
+            Some(image.platform.architecture.as_str())
+        }
+        RootfsImageSource::Disk | RootfsImageSource::Tar => None,
+    };
+    if let Some(warning) = check_arch(
+        &image.image_ref,
+        image_arch,
+        &host.architecture,
+        allow_mismatch,
+    )? {
+        ui::warn(warning);
+    }
+    Ok(())
+}
+
+fn check_arch(
+    image_ref: &str,
+    image_arch: Option<&str>,
+    host_arch: &str,
+    allow_mismatch: bool,
+) -> eyre::Result<Option<String>> {
+    match image_arch {
+        None => Ok(Some(format!(
+            "architecture of {image_ref} is unknown; assuming it matches host '{host_arch}'"
+        ))),
+        Some(arch) if arch == host_arch => Ok(None),
+        Some(arch) if allow_mismatch => Ok(Some(format!(
+            "image arch '{arch}' does not match host '{host_arch}'; the VM may not boot"
+        ))),
+        Some(arch) => eyre::bail!(
+            "image arch '{arch}' does not match host '{host_arch}'; use --rosetta or a matching image\n\nhint: pass --allow-arch-mismatch to use {image_ref} anyway"
+        ),
+    }
+}
+
 pub(crate) fn record_base_rootfs_metadata(
     metadata: &mut BTreeMap<String, String>,
     image: &RootfsImage,
@@ -36,3 +117,51 @@ pub(crate) fn record_base_rootfs_metadata(
         image.source.to_string(),
     );
 }
+
+/// Returns the cached image a machine was created from, when it was recorded.
+pub(crate) fn base_image_id(metadata: &BTreeMap<String, String>) -> Option<&str> {
+    metadata.get(IMAGE_ID_METADATA_KEY).map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::commands::rootfs_image::{base_image_id, check_arch};
+
+    #[test]
+    fn base_image_id_reads_recorded_metadata() {
+        let mut metadata = BTreeMap::new();
+        assert_eq!(base_image_id(&metadata), None);
+
+        metadata.insert("bento.image.id".to_string(), "sha256:abc".to_string());
+
+        assert_eq!(base_image_id(&metadata), Some("sha256:abc"));
+    }
+
+    #[test]
+    fn matching_arch_passes_silently() {
+        let warning = check_arch("alpine", Some("arm64"), "arm64", false).expect("matching arch");
+
+        assert_eq!(warning, None);
+    }
+
+    #[test]
+    fn mismatched_arch_is_refused_unless_allowed() {
+        let err = check_arch("alpine", Some("amd64"), "arm64", false)
+            .expect_err("mismatched arch should fail");
+        assert!(err
+            .to_string()
+            .starts_with("image arch 'amd64' does not match host 'arm64'"));
+
+        let warning = check_arch("alpine", Some("amd64"), "arm64", true).expect("allowed mismatch");
+        assert!(warning.is_some());
+    }
+
+    #[test]
+    fn unknown_arch_warns_and_proceeds() {
+        let warning = check_arch("disk:./rootfs.img", None, "arm64", false).expect("unknown arch");
+
+        assert!(warning.is_some_and(|warning| warning.contains("unknown")));
+    }
+}
@@ -1,20 +1,30 @@
 use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use clap::Args;
-use libvm::{MachineNetworkConfig, MachineRef, Memory, Runtime, DEFAULT_GUEST_READINESS_TIMEOUT};
+use clap::{Args, ValueEnum};
+use libvm::{
+    LibVmError, MachineKillOptions, MachineNetworkConfig, MachineRef, MachineStatus,
+    MachineStopOptions, Memory, Runtime,
+};
+use serde::Serialize;
 use vm_spec::Mount;
 
 use crate::commands::create::{
-    profile_mount_to_mount, read_userdata_path, resolve_boot_assets, VmOverrideArgs,
+    profile_mount_to_mount, read_userdata_path, require_default_kernel,
+    require_nested_virtualization, resolve_boot_assets, VmOverrideArgs,
+};
+use crate::commands::rootfs_image::{
+    get_base_rootfs_image, record_base_rootfs_metadata, verify_base_rootfs_arch,
+};
+use crate::commands::start_options::{
+    machine_start_options, wait_for_guest_ready, wait_for_ssh_ready, BootTimeoutArgs,
 };
-use crate::commands::rootfs_image::{get_base_rootfs_image, record_base_rootfs_metadata};
-use crate::commands::start_options::machine_start_options;
 use crate::constants::{DEFAULT_PROFILE_NAME, PROFILE_METADATA_KEY};
 use crate::context::Context;
 use crate::profile::ProfileStore;
-use crate::ssh;
-use crate::ui::{watch_image_progress, Spinner};
+use crate::ssh::{self, HostKeyPolicy};
+use crate::ui::{self, watch_image_progress, OutputFormat, Spinner};
 
 const EXAMPLES: &[&str] = &[
     "bento run",
@@ -22,8 +32,24 @@ const EXAMPLES: &[&str] = &[
     "bento run dev -- cargo test",
     "bento run dev --image disk:./target/rootfs.img -- cargo test",
     "bento run dev --keep-on-failure -- cargo test",
+    "bento run dev --attach none",
+    "bento run dev --format json -- cargo test",
+    "bento run dev --boot-timeout 90s -- cargo test",
 ];
 
+/// How long `run` waits for the ephemeral VM to shut down before killing it.
+const EPHEMERAL_STOP_TIMEOUT: Duration = Duration::from_secs(20);
+/// How long `run` waits for the monitor to exit after killing it.
+const EPHEMERAL_KILL_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Copy, Clone, Debug, ValueEnum, Eq, PartialEq)]
+pub enum RunAttach {
+    /// Open a guest shell, or run the given command, once the VM is ready.
+    Shell,
+    /// Leave the VM running in the background without attaching.
+    None,
+}
+
 #[derive(Debug, Args)]
 #[command(
     about = "Run an ephemeral VM from a profile or image",
@@ -45,6 +71,29 @@ pub struct Cmd {
     /// Keep the ephemeral VM only when the guest command exits non-zero.
     #[arg(long)]
     pub keep_on_failure: bool,
+    /// Skip guest host key verification instead of pinning the key seen on first connect.
+    #[arg(long)]
+    pub insecure: bool,
+    /// How to attach once the VM is ready. `none` leaves it running and implies `--keep`.
+    #[arg(long, value_enum, value_name = "MODE")]
+    pub attach: Option<RunAttach>,
+    /// Attach as soon as the guest agent is ready instead of first probing guest ssh.
+    #[arg(long)]
+    pub no_wait_for_ssh: bool,
+    /// Copy the base rootfs in full instead of cloning it copy-on-write.
+    #[arg(long)]
+    pub copy: bool,
+    /// Output format. `json` replaces progress output with one JSON event per line on stdout.
+    #[arg(
+        long,
+        visible_alias = "output",
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t = OutputFormat::Plain
+    )]
+    pub format: OutputFormat,
+    #[command(flatten)]
+    pub(crate) boot: BootTimeoutArgs,
     #[command(flatten)]
     pub(crate) overrides: VmOverrideArgs,
     /// Guest command and arguments to execute after `--`.
@@ -54,29 +103,47 @@ pub struct Cmd {
 
 impl Cmd {
     pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
-        if self.keep_on_failure && self.command.is_empty() {
-            eyre::bail!("--keep-on-failure requires a command");
-        }
+        self.validate()?;
 
-        let mut progress = Spinner::start("Reading", "run recipe");
+        let events = RunEvents::new(self.format, context.progress());
+        let mut progress = events.spinner("Reading", "run recipe");
         let mut resolved = self.resolve()?;
         let runtime = context.runtime().await?;
         progress.step("Finding", "boot assets");
         let data_dir = runtime.local_data_dir().to_path_buf();
-        let boot_assets =
-            resolve_boot_assets(&data_dir, resolved.kernel.take(), resolved.initramfs.take());
+        let kernel_configured = resolved.kernel.is_some() || resolved.kernel_bundle.is_some();
+        let boot_assets = resolve_boot_assets(
+            &data_dir,
+            resolved.kernel.take(),
+            resolved.initramfs.take(),
+            resolved.kernel_bundle.as_deref(),
+        )?;
+        if !kernel_configured {
+            require_default_kernel(&boot_assets.kernel)?;
+        }
+        if resolved.nested_virtualization {
+            require_nested_virtualization()?;
+        }
         progress.finish_clear();
         let base_rootfs = {
             let (image_progress, image_events) = ocidisk::ImageProgressSender::default_channel();
-            let image_progress_task =
-                watch_image_progress(resolved.image_ref.clone(), image_events);
+            let image_progress_task = watch_image_progress(
+                resolved.image_ref.clone(),
+                image_events,
+                !events.shows_progress(),
+            );
             let image =
                 get_base_rootfs_image(runtime, &resolved.image_ref, Some(image_progress)).await;
             let _ = image_progress_task.await;
             image?
         };
+        verify_base_rootfs_arch(&base_rootfs, self.overrides.allow_arch_mismatch)?;
+        events.emit(RunEvent::ImagePulled {
+            image_ref: &base_rootfs.image_ref,
+            image_id: &base_rootfs.image_id,
+        })?;
         record_base_rootfs_metadata(&mut resolved.metadata, &base_rootfs);
-        let mut progress = Spinner::start("Creating", "ephemeral VM");
+        let mut progress = events.spinner("Creating", "ephemeral VM");
         let machine = runtime
             .machine(resolved.image_ref.clone(), base_rootfs.path)
             .labels(resolved.labels)
@@ -87,46 +154,114 @@ impl Cmd {
                     .memory_mib
                     .map(|memory| Memory::mebibytes(u64::from(memory))),
             )
+            .maybe_min_memory(
+                resolved
+                    .min_memory_mib
+                    .map(|memory| Memory::mebibytes(u64::from(memory))),
+            )
             .kernel(boot_assets.kernel)
             .maybe_initramfs(boot_assets.initramfs)
             .maybe_root_disk_size(resolved.disk_size_bytes)
+            .copy_root_disk(self.copy)
             .nested_virtualization(resolved.nested_virtualization)
             .rosetta(resolved.rosetta)
+            .graphics(resolved.graphics)
+            .keyboard(resolved.keyboard)
+            .entropy(resolved.entropy)
+            .memory_balloon(resolved.memory_balloon)
             .maybe_userdata(resolved.userdata)
             .disks(resolved.disks)
             .mounts(resolved.mounts)
             .network(resolved.network)
             .create()
             .await?;
-        let machine_name = machine.inspect().await?.name;
+        let machine_data = machine.inspect().await?;
+        let machine_name = machine_data.name.clone();
+        let host_keys = HostKeyPolicy::for_machine(&machine_data, self.insecure);
+        events.emit(RunEvent::Created {
+            machine: &machine_name,
+        })?;
         progress.step("Starting", &machine_name);
+        let boot_timeout = self.boot.timeout();
         machine
-            .start_with(machine_start_options(runtime, &machine)?)
+            .start_with(machine_start_options(runtime, &machine)?.boot_timeout(boot_timeout))
             .await?;
+        let ready_deadline = tokio::time::Instant::now() + boot_timeout;
+        events.emit(RunEvent::Started {
+            machine: &machine_name,
+        })?;
         progress.step("Waiting", &machine_name);
-        machine
-            .wait_for_guest_running(DEFAULT_GUEST_READINESS_TIMEOUT)
-            .await
-            .map_err(|error| eyre::eyre!("guest readiness check failed: {error}"))?;
+        wait_for_guest_ready(&machine, remaining_until(ready_deadline)).await?;
+        if self.attach != Some(RunAttach::None) && !self.no_wait_for_ssh {
+            wait_for_ssh_ready(&machine, remaining_until(ready_deadline)).await?;
+        }
+        events.emit(RunEvent::Ready {
+            machine: &machine_name,
+        })?;
 
         progress.step("Ready", &machine_name);
         progress.finish_success("Started");
 
+        if self.attach == Some(RunAttach::None) {
+            events.emit(RunEvent::Kept {
+                machine: &machine_name,
+            })?;
+            if !events.is_json() {
+                println!("{machine_name}");
+                ui::success(format!(
+                    "{machine_name} is running, attach with `bento shell {machine_name}`"
+                ));
+            }
+            return Ok(());
+        }
+
+        events.emit(RunEvent::Attached {
+            machine: &machine_name,
+            command: &self.command,
+        })?;
         let status = if self.command.is_empty() {
-            ssh::run_remote_shell_status(&data_dir, &machine_name, None)?
+            ssh::run_remote_shell_status(&data_dir, &machine_name, None, &host_keys)?
         } else {
-            ssh::run_remote_command(&data_dir, &machine_name, None, &self.command)?
+            ssh::run_remote_command(&data_dir, &machine_name, None, &host_keys, &self.command)?
         };
         let code = status.code().unwrap_or(1);
+        events.emit(RunEvent::Exited {
+            machine: &machine_name,
+            code,
+        })?;
         let should_keep = self.keep || (self.keep_on_failure && code != 0);
 
-        if !should_keep {
+        if should_keep {
+            events.emit(RunEvent::Kept {
+                machine: &machine_name,
+            })?;
+        } else {
             cleanup_ephemeral(runtime, &machine_name).await?;
+            events.emit(RunEvent::CleanedUp {
+                machine: &machine_name,
+            })?;
         }
 
         std::process::exit(code);
     }
 
+    fn validate(&self) -> eyre::Result<()> {
+        if self.attach == Some(RunAttach::None) {
+            if !self.command.is_empty() {
+                eyre::bail!(
+                    "--attach none cannot run a command; use `bento exec` once the VM is up"
+                );
+            }
+            if self.keep_on_failure {
+                eyre::bail!("--keep-on-failure has no effect with --attach none");
+            }
+        }
+        if self.keep_on_failure && self.command.is_empty() {
+            eyre::bail!("--keep-on-failure requires a command");
+        }
+        Ok(())
+    }
+
     fn resolve(&self) -> eyre::Result<ResolvedRun> {
         if self.profile.is_some() && self.profile_name.is_some() {
             eyre::bail!("profile specified twice; use either positional profile or --profile");
@@ -187,11 +322,17 @@ impl Cmd {
             userdata,
             cpus: self.overrides.cpus.or(cpus),
             memory_mib: self.overrides.memory_mib()?.or(memory_mib),
+            min_memory_mib: self.overrides.min_memory_mib()?,
             kernel: self.overrides.kernel.clone(),
             initramfs: self.overrides.initramfs.clone(),
+            kernel_bundle: self.overrides.kernel_bundle.clone(),
             disk_size_bytes: self.overrides.disk_size_bytes()?.or(disk_size_bytes),
             nested_virtualization: self.overrides.nested_virtualization,
             rosetta: self.overrides.rosetta,
+            graphics: self.overrides.graphics,
+            keyboard: self.overrides.keyboard,
+            entropy: !self.overrides.no_entropy,
+            memory_balloon: !self.overrides.no_memory_balloon,
             disks: self.overrides.disks.clone(),
         })
     }
@@ -206,27 +347,137 @@ struct ResolvedRun {
     userdata: Option<String>,
     cpus: Option<u8>,
     memory_mib: Option<u32>,
+    min_memory_mib: Option<u32>,
     kernel: Option<PathBuf>,
     initramfs: Option<PathBuf>,
+    kernel_bundle: Option<String>,
     disk_size_bytes: Option<u64>,
     nested_virtualization: bool,
     rosetta: bool,
+    graphics: bool,
+    keyboard: bool,
+    entropy: bool,
+    memory_balloon: bool,
     disks: Vec<PathBuf>,
 }
 
+/// Lifecycle events printed by `bento run --format json`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+enum RunEvent<'a> {
+    ImagePulled {
+        image_ref: &'a str,
+        image_id: &'a str,
+    },
+    Created {
+        machine: &'a str,
+    },
+    Started {
+        machine: &'a str,
+    },
+    Ready {
+        machine: &'a str,
+    },
+    Attached {
+        machine: &'a str,
+        #[serde(skip_serializing_if = "<[String]>::is_empty")]
+        command: &'a [String],
+    },
+    Exited {
+        machine: &'a str,
+        code: i32,
+    },
+    Kept {
+        machine: &'a str,
+    },
+    CleanedUp {
+        machine: &'a str,
+    },
+}
+
+struct RunEvents {
+    format: OutputFormat,
+    progress: bool,
+}
+
+impl RunEvents {
+    fn new(format: OutputFormat, progress: bool) -> Self {
+        Self { format, progress }
+    }
+
+    fn is_json(&self) -> bool {
+        self.format == OutputFormat::Json
+    }
+
+    fn shows_progress(&self) -> bool {
+        self.progress && !self.is_json()
+    }
+
+    fn spinner(&self, label: &str, target: &str) -> Spinner {
+        if self.is_json() {
+            Spinner::quiet()
+        } else {
+            Spinner::start(self.progress, label, target)
+        }
+    }
+
+    fn emit(&self, event: RunEvent<'_>) -> eyre::Result<()> {
+        if self.is_json() {
+            ui::print_json_line(&event)?;
+        }
+        Ok(())
+    }
+}
+
 async fn cleanup_ephemeral(runtime: &Runtime, name: &str) -> eyre::Result<()> {
     let machine = runtime
         .get_machine(&MachineRef::parse(name.to_string())?)
         .await?;
-    match machine.stop().await {
-        Ok(_) => {}
-        Err(error) if error.to_string().contains("is not running") => {}
-        Err(error) => return Err(error.into()),
+    let stopped = match machine
+        .stop_with(MachineStopOptions::new().timeout(EPHEMERAL_STOP_TIMEOUT))
+        .await
+    {
+        Ok(_) | Err(LibVmError::MachineNotRunning { .. }) => true,
+        Err(error) => {
+            tracing::warn!(machine = name, %error, "graceful stop failed, killing ephemeral VM");
+            false
+        }
+    };
+    if !stopped {
+        match machine
+            .kill_with(MachineKillOptions::new().timeout(EPHEMERAL_KILL_TIMEOUT))
+            .await
+        {
+            Ok(_) | Err(LibVmError::MachineNotRunning { .. }) => {}
+            Err(error) => return Err(ephemeral_stop_timeout(name, error)),
+        }
+    }
+
+    if !matches!(
+        machine.inspect().await?.status,
+        MachineStatus::Stopped | MachineStatus::Error { .. }
+    ) {
+        return Err(ephemeral_stop_timeout(
+            name,
+            "monitor is still running after kill",
+        ));
     }
     machine.remove().await?;
     Ok(())
 }
 
+/// Time left before `deadline`, so the guest and ssh waits share one boot budget.
+fn remaining_until(deadline: tokio::time::Instant) -> Duration {
+    deadline.saturating_duration_since(tokio::time::Instant::now())
+}
+
+fn ephemeral_stop_timeout(name: &str, cause: impl std::fmt::Display) -> eyre::Report {
+    let waited = (EPHEMERAL_STOP_TIMEOUT + EPHEMERAL_KILL_TIMEOUT).as_secs();
+    eyre::eyre!(
+        "ephemeral VM {name} did not stop within {waited}s ({cause}); it was kept, remove it with `bento rm --force {name}`"
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::{Path, PathBuf};
@@ -235,7 +486,9 @@ mod tests {
 
     use crate::app::Cli;
     use crate::commands::create::resolve_boot_assets;
+    use crate::commands::run::{ephemeral_stop_timeout, RunAttach, RunEvent};
     use crate::commands::Command;
+    use crate::ui::OutputFormat;
 
     #[test]
     fn run_command_parses_create_parity_overrides() {
@@ -265,6 +518,7 @@ mod tests {
             "none",
             "--label",
             "env=dev",
+            "--copy",
         ])
         .expect("run command should parse");
         let Command::Run(run) = cli.command else {
@@ -280,6 +534,8 @@ mod tests {
         );
         assert!(run.overrides.nested_virtualization);
         assert!(run.overrides.rosetta);
+        assert!(!run.no_wait_for_ssh);
+        assert!(run.copy);
         assert_eq!(run.overrides.disks.len(), 1);
         assert_eq!(run.overrides.mounts.len(), 1);
         assert_eq!(
@@ -308,6 +564,17 @@ mod tests {
         assert_eq!(run.image.as_deref(), Some("tar:./target/rootfs.tar"));
     }
 
+    #[test]
+    fn run_command_can_skip_ssh_probe() {
+        let cli = Cli::try_parse_from(["bento", "run", "dev", "--no-wait-for-ssh"])
+            .expect("run command should parse");
+        let Command::Run(run) = cli.command else {
+            panic!("expected run command");
+        };
+
+        assert!(run.no_wait_for_ssh);
+    }
+
     #[test]
     fn run_command_leaves_default_initramfs_for_libvm_generation() {
         let cli = Cli::try_parse_from([
@@ -328,7 +595,9 @@ mod tests {
             Path::new("/data/bento"),
             run.overrides.kernel.clone(),
             run.overrides.initramfs.clone(),
-        );
+            None,
+        )
+        .expect("resolve boot assets");
 
         assert_eq!(assets.kernel, PathBuf::from("/data/bento/assets/default"));
         assert_eq!(assets.initramfs, None);
@@ -354,7 +623,9 @@ mod tests {
             Path::new("/data/bento"),
             run.overrides.kernel.clone(),
             run.overrides.initramfs.clone(),
-        );
+            None,
+        )
+        .expect("resolve boot assets");
 
         assert_eq!(assets.kernel, PathBuf::from("/data/bento/assets/default"));
         assert_eq!(assets.initramfs, Some(PathBuf::from("./initrd.img")));
@@ -365,4 +636,76 @@ mod tests {
         assert!(Cli::try_parse_from(["bento", "run", "dev", "--memory", "4096"]).is_err());
         assert!(Cli::try_parse_from(["bento", "run", "dev", "--disk-size", "40"]).is_err());
     }
+
+    #[test]
+    fn run_command_accepts_output_alias_for_json_events() {
+        let cli = Cli::try_parse_from(["bento", "run", "dev", "--output", "json", "--", "true"])
+            .expect("run command should parse");
+        let Command::Run(run) = cli.command else {
+            panic!("expected run command");
+        };
+
+        assert_eq!(run.format, OutputFormat::Json);
+        assert_eq!(run.command, vec!["true".to_string()]);
+    }
+
+    #[test]
+    fn run_events_serialize_as_tagged_json_lines() {
+        let event = serde_json::to_value(RunEvent::Exited {
+            machine: "dev-1",
+            code: 3,
+        })
+        .expect("serialize event");
+        let attached = serde_json::to_value(RunEvent::Attached {
+            machine: "dev-1",
+            command: &[],
+        })
+        .expect("serialize event");
+
+        assert_eq!(
+            event,
+            serde_json::json!({ "event": "exited", "machine": "dev-1", "code": 3 })
+        );
+        assert_eq!(
+            attached,
+            serde_json::json!({ "event": "attached", "machine": "dev-1" })
+        );
+    }
+
+    #[test]
+    fn ephemeral_stop_timeout_explains_how_to_clean_up() {
+        let message = ephemeral_stop_timeout("dev-1", "timed out").to_string();
+
+        assert!(message.contains("did not stop within 30s (timed out)"));
+        assert!(message.contains("bento rm --force dev-1"));
+    }
+
+    #[test]
+    fn detached_run_rejects_commands() {
+        let parse = |args: &[&str]| {
+            let cli = Cli::try_parse_from(args).expect("run command should parse");
+            let Command::Run(run) = cli.command else {
+                panic!("expected run command");
+            };
+            run
+        };
+
+        let detached = parse(&["bento", "run", "dev", "--attach", "none"]);
+        let with_command = parse(&["bento", "run", "dev", "--attach", "none", "--", "true"]);
+
+        assert_eq!(detached.attach, Some(RunAttach::None));
+        assert!(detached.validate().is_ok());
+        assert!(with_command.validate().is_err());
+    }
+
+    #[test]
+    fn run_command_parses_boot_timeout() {
+        let cli = Cli::try_parse_from(["bento", "run", "dev", "--boot-timeout", "90s"])
+            .expect("run command should parse");
+        let Command::Run(run) = cli.command else {
+            panic!("expected run command");
+        };
+
+        assert_eq!(run.boot.timeout(), std::time::Duration::from_secs(90));
+    }
 }
@@ -1017,6 +1017,9 @@ fn validate_secret_name(name: &str) -> eyre::Result<()> {
 }
 
 fn resolve_default_data_dir() -> eyre::Result<PathBuf> {
+    if let Some(data_dir) = env_absolute_path("BENTO_DATA_HOME")? {
+        return Ok(data_dir);
+    }
     if let Some(data_home) = env_absolute_path("XDG_DATA_HOME")? {
         return Ok(data_home.join("bento"));
     }
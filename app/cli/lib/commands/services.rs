@@ -0,0 +1,89 @@
+use clap::Args;
+use libvm::{LibVmError, MachineService, MachineServiceKind};
+use serde::Serialize;
+
+use crate::context::Context;
+use crate::ui::{self, OutputFormat, Table};
+
+const EXAMPLES: &[&str] = &["bento services dev", "bento services --format json"];
+
+#[derive(Debug, Args)]
+#[command(
+    about = "List services served on a running VM's control socket",
+    after_help = crate::help::examples(EXAMPLES)
+)]
+pub struct Cmd {
+    /// Name or ID of the VM. Defaults to the configured default VM.
+    #[arg(value_name = "VM")]
+    name: Option<String>,
+
+    /// Output format.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = OutputFormat::Plain)]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Serialize)]
+struct ServiceView {
+    name: String,
+    available: bool,
+    message: Option<String>,
+    kind: &'static str,
+    vsock_port: Option<u32>,
+}
+
+impl Cmd {
+    pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
+        let (name, machine) = context.machine(self.name.as_deref()).await?;
+        let services = match machine.services().await {
+            Ok(services) => services,
+            Err(LibVmError::MachineNotRunning { .. }) => {
+                eyre::bail!("VM {name} is not running");
+            }
+            Err(LibVmError::MonitorProtocol { message, .. }) => {
+                eyre::bail!("could not list services for {name}: {message}");
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let views = services
+            .into_iter()
+            .map(ServiceView::from)
+            .collect::<Vec<_>>();
+        match self.format {
+            OutputFormat::Json => ui::print_json(&views),
+            OutputFormat::Plain => print_table(&views),
+        }
+    }
+}
+
+impl From<MachineService> for ServiceView {
+    fn from(service: MachineService) -> Self {
+        Self {
+            name: service.name,
+            available: service.available,
+            message: service.message,
+            kind: service.kind.label(),
+            vsock_port: match service.kind {
+                MachineServiceKind::Vsock { port } => port,
+                _ => None,
+            },
+        }
+    }
+}
+
+fn print_table(services: &[ServiceView]) -> eyre::Result<()> {
+    let mut table = Table::new(["SERVICE", "KIND", "PORT", "AVAILABLE", "MESSAGE"]);
+    for service in services {
+        table.add_row([
+            service.name.clone(),
+            service.kind.to_string(),
+            service
+                .vsock_port
+                .map(|port| port.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            ui::yes_no(service.available).to_string(),
+            service.message.clone().unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+    table.print()
+}
@@ -2,6 +2,7 @@ use clap::Args;
 use libvm::{MachineNetworkConfig, MachineUpdate, Memory};
 use utils::HumanSize;
 
+use crate::commands::create::require_nested_virtualization;
 use crate::config::GlobalConfig;
 use crate::context::Context;
 use crate::ui;
@@ -20,6 +21,16 @@ const SETTINGS: &[(&str, &str)] = &[
         "Enable or disable nested virtualization",
     ),
     ("rosetta=true|false", "Enable or disable Rosetta"),
+    (
+        "graphics=true|false",
+        "Attach or detach the graphics and pointing devices",
+    ),
+    ("keyboard=true|false", "Attach or detach the USB keyboard"),
+    ("entropy=true|false", "Attach or detach the entropy device"),
+    (
+        "memory-balloon=true|false",
+        "Attach or detach the memory balloon device",
+    ),
 ];
 
 const EXAMPLES: &[&str] = &[
@@ -125,9 +136,17 @@ impl ParsedSet {
                 "disk" => update = update.root_disk_size(parse_disk(value)?),
                 "network" => update = update.network(parse_machine_network_config(value)?),
                 "nested-virtualization" => {
-                    update = update.nested_virtualization(parse_bool(value)?);
+                    let enabled = parse_bool(value)?;
+                    if enabled {
+                        require_nested_virtualization()?;
+                    }
+                    update = update.nested_virtualization(enabled);
                 }
                 "rosetta" => update = update.rosetta(parse_bool(value)?),
+                "graphics" => update = update.graphics(parse_bool(value)?),
+                "keyboard" => update = update.keyboard(parse_bool(value)?),
+                "entropy" => update = update.entropy(parse_bool(value)?),
+                "memory-balloon" => update = update.memory_balloon(parse_bool(value)?),
                 other => eyre::bail!("unsupported setting {other:?}"),
             }
         }
@@ -145,8 +164,12 @@ fn normalize_key(key: &str) -> eyre::Result<&'static str> {
         "network" | "net" => Ok("network"),
         "nested-virtualization" | "nested_virtualization" => Ok("nested-virtualization"),
         "rosetta" => Ok("rosetta"),
+        "graphics" => Ok("graphics"),
+        "keyboard" => Ok("keyboard"),
+        "entropy" => Ok("entropy"),
+        "memory-balloon" | "memory_balloon" | "balloon" => Ok("memory-balloon"),
         _ => Err(eyre::eyre!(
-            "unknown setting {key:?}; allowed settings are name, cpus, memory, disk, network, nested-virtualization, rosetta"
+            "unknown setting {key:?}; allowed settings are name, cpus, memory, disk, network, nested-virtualization, rosetta, graphics, keyboard, entropy, memory-balloon"
         )),
     }
 }
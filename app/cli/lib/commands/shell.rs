@@ -1,9 +1,12 @@
+use std::time::Duration;
+
 use clap::{Args, ValueEnum};
 use eyre::bail;
-use libvm::MachineData;
+use libvm::{Machine, MachineData};
 
+use crate::commands::start_options::parse_duration;
 use crate::context::Context;
-use crate::ssh;
+use crate::ssh::{self, HostKeyPolicy};
 use crate::terminal;
 use crate::ui;
 
@@ -24,9 +27,18 @@ pub struct Cmd {
     #[arg(long, short = 'u')]
     pub user: Option<String>,
 
+    /// Skip guest host key verification instead of pinning the key seen on first connect.
+    #[arg(long)]
+    pub insecure: bool,
+
     /// Attach through the guest shell or serial console.
     #[arg(long, value_enum)]
     pub attach: Option<AttachMode>,
+
+    /// Ping vmmon this often during a serial attach and disconnect when a
+    /// ping goes unanswered for as long, for example 30s.
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub keepalive: Option<Duration>,
 }
 
 impl Cmd {
@@ -42,12 +54,39 @@ impl Cmd {
                 ui::warn("--user is ignored for serial attach");
             }
             let stream = machine.open_serial_stream().await?;
-            return terminal::attach_serial_stream(stream).await;
+            let keepalive = async {
+                match self.keepalive {
+                    Some(interval) => keepalive(&machine, &machine_name, interval).await,
+                    None => std::future::pending().await,
+                }
+            };
+            return terminal::attach_serial_stream(stream, keepalive).await;
+        }
+        if self.keepalive.is_some() {
+            ui::warn("--keepalive only applies to serial attach");
         }
 
         ensure_guest_ready(&inspect_data)?;
         let data_dir = context.runtime().await?.local_data_dir().to_path_buf();
-        ssh::exec_remote_shell(&data_dir, &machine_name, self.user.as_deref())
+        ssh::exec_remote_shell(
+            &data_dir,
+            &machine_name,
+            self.user.as_deref(),
+            &HostKeyPolicy::for_machine(&inspect_data, self.insecure),
+        )
+    }
+}
+
+/// Pings vmmon every `interval` and returns once a ping fails, so a serial
+/// session does not outlive the monitor that carries it.
+async fn keepalive(machine: &Machine, name: &str, interval: Duration) -> eyre::Result<()> {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        if let Err(err) = machine.ping(interval).await {
+            bail!("machine `{name}` stopped answering keepalive pings: {err}");
+        }
     }
 }
 
@@ -11,19 +11,29 @@ use crate::context::Context;
 
 const FIRST_BACKEND_BYTE_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// Relays stdin and stdout to the guest sshd, for use as an ssh `ProxyCommand`
+/// such as `ProxyCommand bento proxy dev`.
 #[derive(Debug, Args)]
 #[command(hide = true)]
 pub struct Cmd {
-    #[arg(long)]
-    pub name: String,
+    /// Name or ID of the running VM.
+    #[arg(value_name = "VM", required_unless_present = "name")]
+    pub vm: Option<String>,
+
+    /// Name or ID of the running VM. Kept for ssh configs that pass it as a flag.
+    #[arg(long, conflicts_with = "vm")]
+    pub name: Option<String>,
 }
 
 impl Cmd {
     pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
+        let Some(name) = self.vm.or(self.name) else {
+            eyre::bail!("a VM name is required");
+        };
         let machine = context
             .runtime()
             .await?
-            .get_machine(&MachineRef::parse(self.name)?)
+            .get_machine(&MachineRef::parse(name)?)
             .await?;
         let stream = machine
             .open_shell_stream(true)
@@ -160,7 +170,30 @@ mod tests {
     use tokio::io::{AsyncReadExt, AsyncWriteExt};
     use tokio::net::UnixStream;
 
+    use clap::Parser;
+
+    use crate::app::Cli;
     use crate::commands::shell_proxy::{proxy_streams, proxy_streams_with_timeout};
+    use crate::commands::Command;
+
+    #[test]
+    fn proxy_accepts_positional_vm_and_legacy_name_flag() {
+        for args in [["bento", "proxy", "dev"], ["bento", "shell-proxy", "dev"]] {
+            let cli = Cli::try_parse_from(args).expect("proxy command should parse");
+            let Command::ShellProxy(proxy) = cli.command else {
+                panic!("expected proxy command");
+            };
+            assert_eq!(proxy.vm.as_deref(), Some("dev"));
+        }
+
+        let cli = Cli::try_parse_from(["bento", "shell-proxy", "--name", "dev"])
+            .expect("legacy proxy flag should parse");
+        let Command::ShellProxy(proxy) = cli.command else {
+            panic!("expected proxy command");
+        };
+        assert_eq!(proxy.name.as_deref(), Some("dev"));
+        assert!(Cli::try_parse_from(["bento", "proxy"]).is_err());
+    }
 
     #[tokio::test]
     async fn proxy_exits_when_backend_closes_while_input_is_pending() {
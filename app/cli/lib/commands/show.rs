@@ -1,8 +1,11 @@
 use clap::Args;
+use libvm::{MachineNetworkConfig, MachineRef};
 
+use crate::commands::image::short_image_id;
+use crate::commands::rootfs_image::base_image_id;
 use crate::context::Context;
 use crate::ui::{self, OutputFormat};
-use crate::view::MachineView;
+use crate::view::{BrokenMachineView, MachineView};
 
 #[derive(Debug, Args)]
 #[command(about = "Show VM details")]
@@ -14,14 +17,37 @@ pub struct Cmd {
     /// Output format.
     #[arg(long, value_enum, value_name = "FORMAT", default_value_t = OutputFormat::Plain)]
     format: OutputFormat,
+
+    /// Include live memory and CPU usage reported by the VM monitor.
+    #[arg(long)]
+    metrics: bool,
 }
 
 impl Cmd {
     pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
-        let (_name, machine) = context.machine(self.name.as_deref()).await?;
+        let name = context.resolve_machine_name(self.name.as_deref())?;
+        let machine_ref = MachineRef::parse(name)?;
+        let runtime = context.runtime().await?;
+        let machine = match runtime.get_machine(&machine_ref).await {
+            Ok(machine) => machine,
+            Err(err) => {
+                // A config that no longer decodes fails the lookup, so report it as broken.
+                let Some(broken) = runtime.get_broken_machine(&machine_ref).await? else {
+                    return Err(err.into());
+                };
+                let view = BrokenMachineView::new(&broken);
+                return match self.format {
+                    OutputFormat::Json => ui::print_json(&view),
+                    OutputFormat::Plain => print_broken(&view),
+                };
+            }
+        };
         let data = machine.inspect().await?;
         let default = context.config()?.default_machine() == Some(data.name.as_str());
-        let view = MachineView::new(&data, default);
+        let mut view = MachineView::new(&data, default);
+        if self.metrics && data.status.is_running() {
+            view.metrics = Some(machine.metrics().await?.into());
+        }
 
         match self.format {
             OutputFormat::Json => ui::print_json(&view),
@@ -44,22 +70,94 @@ fn print_human(view: &MachineView) -> eyre::Result<()> {
             ui::human_memory_mib(Some(view.resources.memory_mib)),
         ),
         ("Disk".to_string(), ui::human_bytes(view.root_disk_size)),
-        ("Network".to_string(), view.network.name()),
+        ("Network".to_string(), network_label(&view.network)),
     ];
 
+    for mount in &view.spec.mounts {
+        let access = if mount.read_only { "ro" } else { "rw" };
+        rows.push((
+            "Mount".to_string(),
+            format!("{} -> {} ({access})", mount.source.display(), mount.tag),
+        ));
+    }
+    for disk in &view.disks {
+        let access = if disk.read_only { "ro" } else { "rw" };
+        rows.push((
+            "Disk image".to_string(),
+            format!(
+                "{} {} ({access}, {})",
+                disk.role,
+                disk.path.display(),
+                ui::human_bytes(disk.size_bytes)
+            ),
+        ));
+    }
+
+    if let Some(mac) = &view.mac {
+        rows.push(("MAC".to_string(), mac.clone()));
+    }
+    if !view.addresses.is_empty() {
+        rows.push(("Addresses".to_string(), view.addresses.join(", ")));
+    }
+
     if let Some(profile) = &view.profile {
         rows.push(("Profile".to_string(), profile.clone()));
     }
     if !view.image.is_empty() {
         rows.push(("Image".to_string(), view.image.clone()));
     }
+    if let Some(image_id) = base_image_id(&view.metadata) {
+        rows.push(("Image ID".to_string(), short_image_id(image_id).to_string()));
+    }
     rows.push(("Created".to_string(), ui::format_unix(view.created_at)));
     if let Some(started_at) = view.started_at {
         rows.push(("Started".to_string(), ui::format_unix(started_at)));
     }
+    if let Some(uptime) = view.uptime(ui::now_unix()) {
+        rows.push(("Uptime".to_string(), uptime));
+    }
+    if let Some(pid) = view.vmmon_pid {
+        rows.push(("Monitor PID".to_string(), pid.to_string()));
+    }
+    if let Some(metrics) = &view.metrics {
+        rows.push((
+            "Memory target".to_string(),
+            format!("{}M", metrics.memory_mib),
+        ));
+        rows.push((
+            "Balloon target".to_string(),
+            metrics
+                .balloon_target_mib
+                .map(|mib| format!("{mib}M"))
+                .unwrap_or_else(|| "unavailable".to_string()),
+        ));
+        rows.push((
+            "CPU time".to_string(),
+            metrics
+                .cpu_time_ms
+                .map(|ms| format!("{:.1}s", ms as f64 / 1000.0))
+                .unwrap_or_else(|| "unavailable".to_string()),
+        ));
+    }
     if let Some(summary) = &view.summary {
         rows.push(("Summary".to_string(), summary.clone()));
     }
 
     ui::print_detail_rows(&rows)
 }
+
+fn print_broken(view: &BrokenMachineView) -> eyre::Result<()> {
+    ui::print_detail_rows(&[
+        ("Name", view.name.as_str()),
+        ("ID", view.id.as_str()),
+        ("State", view.state),
+        ("Error", view.error.as_str()),
+    ])
+}
+
+fn network_label(network: &MachineNetworkConfig) -> String {
+    match network {
+        MachineNetworkConfig::None => "none (no network device)".to_string(),
+        other => other.name(),
+    }
+}
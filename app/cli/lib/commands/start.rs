@@ -1,7 +1,10 @@
+use std::time::Duration;
+
 use clap::Args;
-use libvm::DEFAULT_GUEST_READINESS_TIMEOUT;
 
-use crate::commands::start_options::machine_start_options;
+use crate::commands::start_options::{
+    machine_start_options, parse_duration, wait_for_guest_ready, BootTimeoutArgs,
+};
 use crate::context::Context;
 use crate::ui::Spinner;
 
@@ -11,22 +14,35 @@ pub struct Cmd {
     /// Name or ID of the VM to start. Defaults to the configured default VM.
     #[arg(value_name = "VM")]
     name: Option<String>,
+
+    #[command(flatten)]
+    boot: BootTimeoutArgs,
+
+    /// Stop the VM once no shell, serial or API client has connected for this long, for example `30m`.
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    idle_timeout: Option<Duration>,
 }
 
 impl Cmd {
     pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
-        let mut spinner = Spinner::start("Finding", self.name.as_deref().unwrap_or("default VM"));
+        let mut spinner = Spinner::start(
+            context.progress(),
+            "Finding",
+            self.name.as_deref().unwrap_or("default VM"),
+        );
         let (name, machine) = context.machine(self.name.as_deref()).await?;
 
         spinner.step("Starting", &name);
-        let options = machine_start_options(context.runtime().await?, &machine)?;
+        let boot_timeout = self.boot.timeout();
+        let mut options =
+            machine_start_options(context.runtime().await?, &machine)?.boot_timeout(boot_timeout);
+        if let Some(idle_timeout) = self.idle_timeout {
+            options = options.idle_timeout(idle_timeout);
+        }
         let data = machine.start_with(options).await?;
 
         spinner.step("Waiting", &name);
-        machine
-            .wait_for_guest_running(DEFAULT_GUEST_READINESS_TIMEOUT)
-            .await
-            .map_err(|err| eyre::eyre!("guest readiness check failed: {err}"))?;
+        wait_for_guest_ready(&machine, boot_timeout).await?;
 
         spinner.step("Ready", &data.name);
         spinner.finish_success("Started");
@@ -1,8 +1,33 @@
 use std::ffi::OsString;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use clap::Args;
 use eyre::Context as _;
-use libvm::{Machine, MachineExitCommand, MachineStartOptions, Runtime};
+use libvm::{
+    Machine, MachineExitCommand, MachineStartOptions, Runtime, DEFAULT_GUEST_READINESS_TIMEOUT,
+};
+
+/// Number of vmmon log lines included when the guest fails to boot in time.
+const BOOT_FAILURE_LOG_LINES: usize = 20;
+/// Upper bound on how much of the vmmon log is read to find those lines.
+const BOOT_FAILURE_LOG_BYTES: u64 = 16 * 1024;
+/// Pause between guest shell probes while sshd comes up.
+const SSH_PROBE_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Args)]
+pub(crate) struct BootTimeoutArgs {
+    /// How long to wait for the guest to boot, for example `90s`, `10m` or `1h`. Defaults to 5m.
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    boot_timeout: Option<Duration>,
+}
+
+impl BootTimeoutArgs {
+    pub(crate) fn timeout(&self) -> Duration {
+        self.boot_timeout.unwrap_or(DEFAULT_GUEST_READINESS_TIMEOUT)
+    }
+}
 
 pub(crate) fn machine_start_options(
     runtime: &Runtime,
@@ -16,6 +41,92 @@ pub(crate) fn machine_start_options(
     ))
 }
 
+/// Waits for the guest agent, attaching the tail of the vmmon log on failure.
+pub(crate) async fn wait_for_guest_ready(machine: &Machine, timeout: Duration) -> eyre::Result<()> {
+    let Err(error) = machine.wait_for_guest_running(timeout).await else {
+        return Ok(());
+    };
+
+    let tail = match machine.inspect().await {
+        Ok(data) => log_tail(&data.trace_log_path(), BOOT_FAILURE_LOG_LINES),
+        Err(_) => None,
+    };
+    match tail {
+        Some(tail) => Err(eyre::eyre!(
+            "guest readiness check failed after {}s: {error}\n\nlast vmmon log lines:\n{tail}",
+            timeout.as_secs()
+        )),
+        None => Err(eyre::eyre!(
+            "guest readiness check failed after {}s: {error}",
+            timeout.as_secs()
+        )),
+    }
+}
+
+/// Opens and drops guest shell streams until one connects, so the ssh client
+/// does not race the guest sshd right after boot.
+pub(crate) async fn wait_for_ssh_ready(machine: &Machine, timeout: Duration) -> eyre::Result<()> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let error = match tokio::time::timeout_at(deadline, machine.open_shell_stream(false)).await
+        {
+            Ok(Ok(_stream)) => return Ok(()),
+            Ok(Err(error)) => error,
+            Err(_) => eyre::bail!(
+                "timed out after {}s waiting for guest ssh",
+                timeout.as_secs()
+            ),
+        };
+        if tokio::time::Instant::now() + SSH_PROBE_INTERVAL >= deadline {
+            eyre::bail!(
+                "guest ssh did not accept connections within {}s: {error}",
+                timeout.as_secs()
+            );
+        }
+        tokio::time::sleep(SSH_PROBE_INTERVAL).await;
+    }
+}
+
+pub(crate) fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let split = input
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (value, unit) = input.split_at(split);
+    let value = value
+        .parse::<u64>()
+        .map_err(|_| format!("invalid duration {input:?}, expected a value like 90s, 10m or 1h"))?;
+    let seconds = match unit {
+        "" | "s" => Some(value),
+        "m" => value.checked_mul(60),
+        "h" => value.checked_mul(60 * 60),
+        _ => {
+            return Err(format!(
+                "invalid duration unit {unit:?} in {input:?}, expected s, m or h"
+            ))
+        }
+    };
+    match seconds {
+        Some(0) => Err("duration must be greater than zero".to_string()),
+        Some(seconds) => Ok(Duration::from_secs(seconds)),
+        None => Err(format!("duration {input:?} is too large")),
+    }
+}
+
+fn log_tail(path: &Path, lines: usize) -> Option<String> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(BOOT_FAILURE_LOG_BYTES)))
+        .ok()?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer).ok()?;
+
+    let text = String::from_utf8_lossy(&buffer);
+    let all: Vec<&str> = text.lines().collect();
+    let tail = all[all.len().saturating_sub(lines)..].join("\n");
+    (!tail.trim().is_empty()).then_some(tail)
+}
+
 fn cleanup_exit_command_options(
     executable: PathBuf,
     data_dir: &Path,
@@ -37,8 +148,9 @@ fn cleanup_exit_command_options(
 mod tests {
     use std::ffi::OsString;
     use std::path::{Path, PathBuf};
+    use std::time::Duration;
 
-    use crate::commands::start_options::cleanup_exit_command_options;
+    use crate::commands::start_options::{cleanup_exit_command_options, log_tail, parse_duration};
 
     #[test]
     fn cleanup_exit_command_uses_current_executable_shape() {
@@ -61,4 +173,25 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn parse_duration_accepts_seconds_minutes_and_hours() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("5d").is_err());
+        assert!(parse_duration("m").is_err());
+    }
+
+    #[test]
+    fn log_tail_returns_last_lines() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("vmmon.log");
+        std::fs::write(&path, "one\ntwo\nthree\n").expect("write log");
+
+        assert_eq!(log_tail(&path, 2).as_deref(), Some("two\nthree"));
+        assert_eq!(log_tail(&dir.path().join("missing.log"), 2), None);
+    }
 }
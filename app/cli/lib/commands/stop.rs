@@ -1,4 +1,5 @@
 use clap::Args;
+use libvm::LibVmError;
 
 use crate::context::Context;
 use crate::ui::Spinner;
@@ -10,14 +11,18 @@ pub struct Cmd {
     #[arg(value_name = "VM")]
     name: Option<String>,
 
-    /// Force stop instead of asking the VM to shut down.
+    /// Power off immediately instead of asking the guest to shut down first.
     #[arg(long)]
     force: bool,
 }
 
 impl Cmd {
     pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
-        let mut spinner = Spinner::start("Finding", self.name.as_deref().unwrap_or("default VM"));
+        let mut spinner = Spinner::start(
+            context.progress(),
+            "Finding",
+            self.name.as_deref().unwrap_or("default VM"),
+        );
         let (name, machine) = context.machine(self.name.as_deref()).await?;
 
         if self.force {
@@ -25,7 +30,15 @@ impl Cmd {
             machine.kill().await?;
         } else {
             spinner.step("Stopping", &name);
-            machine.stop().await?;
+            match machine.stop().await {
+                Ok(_) => {}
+                Err(LibVmError::Io(err)) if err.kind() == std::io::ErrorKind::TimedOut => {
+                    eyre::bail!(
+                        "{err}; the guest did not shut down in time, use `bento stop --force {name}` to power it off"
+                    );
+                }
+                Err(err) => return Err(err.into()),
+            }
         }
 
         spinner.step("Stopped", &name);
@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use clap::{Args, ValueEnum};
+use libvm::{LibVmError, Machine, MachineStatus, MachineStatusSource};
+
+use crate::commands::start_options::parse_duration;
+use crate::context::Context;
+
+/// Pause between status checks when the status stream is unavailable or ended.
+const STATUS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+const EXAMPLES: &[&str] = &[
+    "bento wait dev --for running",
+    "bento wait dev --for stopped --timeout 2m",
+    "bento start dev && bento wait dev --for running --timeout 30s",
+];
+
+#[derive(Debug, Args)]
+#[command(
+    about = "Wait until a VM reaches a state",
+    after_help = crate::help::examples(EXAMPLES)
+)]
+pub struct Cmd {
+    /// Name or ID of the VM. Defaults to the configured default VM.
+    #[arg(value_name = "VM")]
+    name: Option<String>,
+
+    /// State to wait for.
+    #[arg(long = "for", value_enum, value_name = "STATE")]
+    state: WaitState,
+
+    /// Give up waiting after this long, for example 30s or 2m.
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    timeout: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum WaitState {
+    Running,
+    Stopped,
+}
+
+impl WaitState {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Stopped => "stopped",
+        }
+    }
+
+    fn matches(self, status: &MachineStatus) -> bool {
+        match self {
+            Self::Running => matches!(status, MachineStatus::Running { .. }),
+            Self::Stopped => matches!(status, MachineStatus::Stopped),
+        }
+    }
+
+    /// Follows vmmon's status stream until the VM reaches this state, errors,
+    /// or the stream ends because vmmon exited.
+    async fn watch(self, machine: &Machine) -> Result<(), LibVmError> {
+        let mut watch = machine.watch_status().await?;
+        while let Some(update) = watch.next().await? {
+            if update.source == MachineStatusSource::Vm
+                && (self.matches(&update.status)
+                    || matches!(update.status, MachineStatus::Error { .. }))
+            {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Cmd {
+    pub async fn run(self, context: &mut Context) -> eyre::Result<()> {
+        let (_name, machine) = context.machine(self.name.as_deref()).await?;
+        let deadline = self
+            .timeout
+            .map(|timeout| tokio::time::Instant::now() + timeout);
+
+        loop {
+            let data = machine.inspect().await?;
+            if self.state.matches(&data.status) {
+                return Ok(());
+            }
+            if let MachineStatus::Error { message } = &data.status {
+                eyre::bail!(
+                    "VM {} is in an error state: {}",
+                    data.name,
+                    message.as_deref().unwrap_or("unknown error")
+                );
+            }
+            if deadline.is_some_and(|deadline| tokio::time::Instant::now() >= deadline) {
+                eyre::bail!(
+                    "timed out waiting for VM {} to be {}",
+                    data.name,
+                    self.state.label()
+                );
+            }
+
+            // Follow the live status stream while vmmon runs, then re-inspect so
+            // the reconciled status decides the outcome. Stream errors only mean
+            // vmmon is not reachable yet or has exited, which inspect reports.
+            let _ = match deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline, self.state.watch(&machine))
+                    .await
+                    .unwrap_or(Ok(())),
+                None => self.state.watch(&machine).await,
+            };
+            tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use libvm::MachineStatus;
+
+    use crate::commands::wait::WaitState;
+
+    #[test]
+    fn wait_state_matches_only_its_status() {
+        let running = MachineStatus::Running {
+            guest_ready: false,
+            message: None,
+        };
+        let starting = MachineStatus::Starting { message: None };
+
+        assert!(WaitState::Running.matches(&running));
+        assert!(!WaitState::Running.matches(&starting));
+        assert!(WaitState::Stopped.matches(&MachineStatus::Stopped));
+        assert!(!WaitState::Stopped.matches(&running));
+    }
+}
@@ -2,12 +2,14 @@ use std::ffi::OsString;
 use std::path::PathBuf;
 
 use eyre::Context as _;
-use libvm::{NetdRuntimeConfig, NetworkDriverKind, RuntimeNetworkingConfig};
+use libvm::{NetdRuntimeConfig, NetworkDriverKind, RuntimeNetworkingConfig, VzNatRuntimeConfig};
 use serde::Deserialize;
 use serde_yaml_ng::{Mapping, Value};
 
 const APP_DIR_NAME: &str = "bento";
 const CONFIG_FILE_NAME: &str = "config.yaml";
+/// Top-level keys understood by [`RawGlobalConfig`].
+const GLOBAL_CONFIG_KEYS: &[&str] = &["default_machine", "networking"];
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct GlobalConfig {
@@ -32,6 +34,12 @@ impl GlobalConfig {
 
         let mut config = parse_global_config(&raw)
             .with_context(|| format!("parse global config {}", config_path.display()))?;
+        for key in unknown_global_config_keys(&raw) {
+            crate::ui::warn(format!(
+                "ignoring unknown key `{key}` in {}",
+                config_path.display()
+            ));
+        }
         config.networking.policy_config_dir = Some(config_dir);
         Ok(config)
     }
@@ -102,21 +110,45 @@ fn parse_global_config(input: &str) -> eyre::Result<GlobalConfig> {
         .transpose()?
         .flatten()
         .unwrap_or(NetworkDriverKind::Netd);
-    let netd = parsed
-        .networking
-        .and_then(|networking| networking.drivers.and_then(|drivers| drivers.netd))
-        .map(NetdRuntimeConfig::from)
-        .unwrap_or_default();
+    let drivers = parsed.networking.and_then(|networking| networking.drivers);
+    let (netd, vznat) = match drivers {
+        Some(drivers) => (drivers.netd, drivers.vznat),
+        None => (None, None),
+    };
+    let netd = netd.map(NetdRuntimeConfig::from).unwrap_or_default();
     validate_netd_config(&netd)?;
+    let vznat = vznat
+        .map(VzNatRuntimeConfig::try_from)
+        .transpose()?
+        .unwrap_or_default();
 
     Ok(GlobalConfig {
         default_machine,
         networking: RuntimeNetworkingConfig::default()
             .with_private_driver(private_driver)
-            .with_netd(netd),
+            .with_netd(netd)
+            .with_vznat(vznat),
     })
 }
 
+/// Top-level keys that [`parse_global_config`] silently ignores.
+///
+/// Unknown keys are tolerated so an older bento can read a config written for
+/// a newer one, but a typo should still be visible to the user.
+fn unknown_global_config_keys(input: &str) -> Vec<String> {
+    let Ok(Value::Mapping(mapping)) = serde_yaml_ng::from_str::<Value>(input) else {
+        return Vec::new();
+    };
+    mapping
+        .keys()
+        .filter_map(|key| match key {
+            Value::String(key) if GLOBAL_CONFIG_KEYS.contains(&key.as_str()) => None,
+            Value::String(key) => Some(key.clone()),
+            other => Some(format!("{other:?}")),
+        })
+        .collect()
+}
+
 #[derive(Debug, Deserialize)]
 struct RawGlobalConfig {
     default_machine: Option<String>,
@@ -140,6 +172,7 @@ struct RawPrivateNetworkingConfig {
 #[serde(deny_unknown_fields)]
 struct RawNetworkDriversConfig {
     netd: Option<RawNetdConfig>,
+    vznat: Option<RawVzNatConfig>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -151,6 +184,13 @@ struct RawNetdConfig {
     tls_ca_key: Option<PathBuf>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawVzNatConfig {
+    ipv6: Option<bool>,
+    subnet: Option<String>,
+}
+
 impl RawNetworkingConfig {
     fn parse_private_driver(&self) -> eyre::Result<Option<NetworkDriverKind>> {
         self.private
@@ -176,6 +216,19 @@ impl From<RawNetdConfig> for NetdRuntimeConfig {
     }
 }
 
+impl TryFrom<RawVzNatConfig> for VzNatRuntimeConfig {
+    type Error = eyre::Report;
+
+    fn try_from(raw: RawVzNatConfig) -> eyre::Result<Self> {
+        if let Some(subnet) = raw.subnet {
+            return Err(eyre::eyre!(
+                "[networking.drivers.vznat].subnet {subnet:?} is not supported: Virtualization.framework NAT has no subnet setting and macOS assigns it from Shared_Net_Address in /Library/Preferences/SystemConfiguration/com.apple.vmnet.plist; use the netd driver with [networking.drivers.netd].subnet instead"
+            ));
+        }
+        Ok(Self::new().with_ipv6(raw.ipv6.unwrap_or(false)))
+    }
+}
+
 fn parse_network_driver(value: &str) -> eyre::Result<NetworkDriverKind> {
     match value {
         "netd" => Ok(NetworkDriverKind::Netd),
@@ -254,3 +307,94 @@ fn write_default_machine_to_path(
     std::fs::write(config_path, rendered)
         .with_context(|| format!("write global config {}", config_path.display()))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{
+        parse_global_config, unknown_global_config_keys, RawGlobalConfig, GLOBAL_CONFIG_KEYS,
+    };
+
+    #[test]
+    fn global_config_keys_match_raw_global_config_fields() {
+        let raw: RawGlobalConfig = serde_yaml_ng::from_str("{}").expect("empty config parses");
+        // Listing every field without `..` stops compiling when a field is added,
+        // which is the cue to update GLOBAL_CONFIG_KEYS as well.
+        let RawGlobalConfig {
+            default_machine: _,
+            networking: _,
+        } = raw;
+        assert_eq!(GLOBAL_CONFIG_KEYS.len(), 2);
+
+        for key in GLOBAL_CONFIG_KEYS {
+            let input = format!("{key}: [1]");
+            assert!(
+                serde_yaml_ng::from_str::<RawGlobalConfig>(&input).is_err(),
+                "{key} is not a RawGlobalConfig field"
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_top_level_keys_are_reported() {
+        let unknown = unknown_global_config_keys(
+            r#"
+default_machine: devbox
+defualt_machine: typo
+networking:
+  private:
+    driver: netd
+"#,
+        );
+
+        assert_eq!(unknown, vec!["defualt_machine".to_string()]);
+        assert!(unknown_global_config_keys("default_machine: devbox").is_empty());
+        assert!(unknown_global_config_keys("not a mapping").is_empty());
+    }
+
+    #[test]
+    fn vznat_ipv6_reaches_runtime_networking_config() {
+        let config = parse_global_config(
+            r#"
+networking:
+  drivers:
+    vznat:
+      ipv6: true
+"#,
+        )
+        .expect("vznat config parses");
+
+        assert!(config.networking.vznat.ipv6);
+        assert!(
+            !parse_global_config("{}")
+                .expect("empty config parses")
+                .networking
+                .vznat
+                .ipv6
+        );
+    }
+
+    #[test]
+    fn vznat_subnet_is_rejected_with_an_explanation() {
+        let err = parse_global_config(
+            r#"
+networking:
+  drivers:
+    vznat:
+      subnet: 192.168.64.0/24
+"#,
+        )
+        .expect_err("vznat subnet is not supported");
+
+        let message = err.to_string();
+        assert!(
+            message
+                .contains("[networking.drivers.vznat].subnet \"192.168.64.0/24\" is not supported"),
+            "{message}"
+        );
+        assert!(message.contains("Shared_Net_Address"), "{message}");
+        assert!(
+            message.contains("[networking.drivers.netd].subnet"),
+            "{message}"
+        );
+    }
+}
@@ -1,5 +1,7 @@
 pub(crate) const PROFILE_METADATA_KEY: &str = "bento.profile";
 
+pub(crate) const CONFIG_METADATA_KEY: &str = "bento.config";
+
 pub(crate) const DEFAULT_PROFILE_NAME: &str = "default";
 
 pub(crate) const DEFAULT_PROFILE_IMAGE: &str = "ghcr.io/vandycknick/archlinux:latest";
@@ -7,3 +9,5 @@ pub(crate) const DEFAULT_PROFILE_IMAGE: &str = "ghcr.io/vandycknick/archlinux:la
 pub(crate) const GUEST_SSH_PRIVATE_KEY_FILE_NAME: &str = "id_ed25519";
 
 pub(crate) const GUEST_SSH_PUBLIC_KEY_FILE_NAME: &str = "id_ed25519.pub";
+
+pub(crate) const MACHINE_KNOWN_HOSTS_FILE_NAME: &str = "known_hosts";
@@ -6,14 +6,16 @@ use crate::config::GlobalConfig;
 #[derive(Debug)]
 pub struct Context {
     verbose: u8,
+    progress: bool,
     config: Option<GlobalConfig>,
     runtime: Option<Runtime>,
 }
 
 impl Context {
-    pub fn new(verbose: u8) -> Self {
+    pub fn new(verbose: u8, progress: bool) -> Self {
         Self {
             verbose,
+            progress,
             config: None,
             runtime: None,
         }
@@ -23,6 +25,11 @@ impl Context {
         self.verbose
     }
 
+    /// Whether spinners and progress bars may be drawn on an interactive stderr.
+    pub fn progress(&self) -> bool {
+        self.progress
+    }
+
     pub(crate) fn config(&mut self) -> eyre::Result<&GlobalConfig> {
         if self.config.is_none() {
             self.config = Some(GlobalConfig::load().context("load global config")?);
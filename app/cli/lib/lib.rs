@@ -5,6 +5,7 @@ pub mod constants;
 pub mod context;
 pub mod errors;
 pub mod help;
+mod logging;
 pub mod profile;
 pub mod ssh;
 pub mod terminal;
@@ -18,6 +19,7 @@ use app::Cli;
 pub async fn run() -> ExitCode {
     let cli = Cli::parse();
     let verbose = cli.verbose;
+    logging::init(verbose);
 
     match cli.run().await {
         Ok(()) => ExitCode::SUCCESS,
@@ -0,0 +1,38 @@
+use tracing_subscriber::EnvFilter;
+
+/// Installs the stderr log subscriber for the `-v` count.
+///
+/// `RUST_LOG` takes precedence when set so a single crate can be traced
+/// without turning everything up.
+pub(crate) fn init(verbose: u8) {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(verbosity_level(verbose)));
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_target(verbose >= 2)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
+fn verbosity_level(verbose: u8) -> &'static str {
+    match verbose {
+        0 => "warn",
+        1 => "info",
+        2 => "debug",
+        _ => "trace",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logging::verbosity_level;
+
+    #[test]
+    fn each_verbose_flag_lowers_the_level() {
+        assert_eq!(verbosity_level(0), "warn");
+        assert_eq!(verbosity_level(1), "info");
+        assert_eq!(verbosity_level(2), "debug");
+        assert_eq!(verbosity_level(3), "trace");
+        assert_eq!(verbosity_level(7), "trace");
+    }
+}
@@ -106,6 +106,13 @@ pub(crate) enum MountMode {
     Rw,
 }
 
+impl NamedProfile {
+    /// Loads a profile file outside the profile directory, named after its file stem.
+    pub fn from_path(path: &Path) -> eyre::Result<Self> {
+        load_profile(profile_name_from_path(path)?, path.to_path_buf())
+    }
+}
+
 pub(crate) struct ProfileStore {
     root: PathBuf,
 }
@@ -138,15 +145,7 @@ impl ProfileStore {
     }
 
     pub fn load_path(&self, name: String, path: PathBuf) -> eyre::Result<NamedProfile> {
-        let raw = std::fs::read_to_string(&path)
-            .with_context(|| format!("read profile {}", path.display()))?;
-        let profile =
-            parse_profile(&raw).with_context(|| format!("parse profile {}", path.display()))?;
-        Ok(NamedProfile {
-            name,
-            path: Some(path),
-            profile,
-        })
+        load_profile(name, path)
     }
 
     pub fn list(&self) -> eyre::Result<Vec<NamedProfile>> {
@@ -273,11 +272,120 @@ impl Profile {
 }
 
 pub(crate) fn parse_profile(raw: &str) -> eyre::Result<Profile> {
-    let profile: Profile = serde_yaml_ng::from_str(raw).context("deserialize profile yaml")?;
+    let profile = parse_profile_unvalidated(raw)?;
     validate_profile(&profile)?;
     Ok(profile)
 }
 
+/// Deserializes a profile without running the semantic checks in [`validate_profile`].
+pub(crate) fn parse_profile_unvalidated(raw: &str) -> eyre::Result<Profile> {
+    serde_yaml_ng::from_str(raw).context("deserialize profile yaml")
+}
+
+/// Returns a JSON Schema (draft 2020-12) describing the profile YAML format.
+pub(crate) fn profile_json_schema() -> serde_json::Value {
+    let size = |description: &str| {
+        serde_json::json!({
+            "type": "string",
+            "description": description,
+            "examples": ["512mb", "4gb"],
+        })
+    };
+
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "BentoBox profile",
+        "type": "object",
+        "additionalProperties": false,
+        "required": ["version", "image"],
+        "properties": {
+            "version": {
+                "description": "Profile format version.",
+                "const": "1",
+            },
+            "description": {
+                "type": "string",
+                "description": "Human-readable profile description.",
+            },
+            "image": {
+                "type": "string",
+                "minLength": 1,
+                "description": "Image reference VMs are created from.",
+                "default": DEFAULT_PROFILE_IMAGE,
+            },
+            "resources": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "cpus": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": 255,
+                        "description": "Number of virtual CPUs. Defaults to the runtime default.",
+                    },
+                    "memory": size("RAM size with a unit. Defaults to the runtime default."),
+                },
+            },
+            "disk_size": size("Root disk size with a unit. Defaults to the image size."),
+            "userdata": {
+                "type": "string",
+                "pattern": "^#!",
+                "description": "Script run on first boot. Must start with a shebang.",
+            },
+            "mounts": {
+                "type": "array",
+                "default": [],
+                "items": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["source", "target"],
+                    "properties": {
+                        "source": {
+                            "type": "string",
+                            "minLength": 1,
+                            "description": "Host path. `~` expands to the home directory.",
+                        },
+                        "target": {
+                            "type": "string",
+                            "pattern": "^/",
+                            "description": "Absolute guest path.",
+                        },
+                        "mode": {
+                            "enum": ["ro", "rw"],
+                            "default": "rw",
+                        },
+                    },
+                },
+            },
+            "network": {
+                "type": "object",
+                "additionalProperties": false,
+                "default": { "kind": "private" },
+                "properties": {
+                    "kind": {
+                        "enum": ["private", "none", "named"],
+                        "description": "Defaults to `named` when `name` is set, otherwise `private`.",
+                    },
+                    "name": {
+                        "type": "string",
+                        "not": { "enum": ["private", "none"] },
+                        "description": "Named network to join.",
+                    },
+                    "policy_ref": {
+                        "type": "string",
+                        "description": "Network policy applied to a private network.",
+                    },
+                },
+            },
+            "labels": {
+                "type": "object",
+                "default": {},
+                "additionalProperties": { "type": "string" },
+            },
+        },
+    })
+}
+
 fn parse_size_config(input: &str, field: &str) -> eyre::Result<HumanSize> {
     input
         .parse::<HumanSize>()
@@ -328,41 +436,65 @@ fn normalize_network(raw: ProfileNetworkConfig) -> eyre::Result<ProfileNetwork>
 }
 
 pub(crate) fn validate_profile(profile: &Profile) -> eyre::Result<()> {
+    match profile_problems(profile).into_iter().next() {
+        Some(problem) => bail!("{problem}"),
+        None => Ok(()),
+    }
+}
+
+/// Returns every semantic problem with a profile instead of stopping at the first.
+pub(crate) fn profile_problems(profile: &Profile) -> Vec<String> {
+    let mut problems = Vec::new();
     if profile.version != "1" {
-        bail!(
+        problems.push(format!(
             "unsupported profile version `{}`, supported versions: 1",
             profile.version
-        );
+        ));
     }
     if profile.image.trim().is_empty() {
-        bail!("profile image cannot be empty");
+        problems.push("profile image cannot be empty".to_string());
     }
-    let _ = profile.memory_mib()?;
-    if let Some(disk_size_bytes) = profile.disk_size_bytes()? {
-        if disk_size_bytes == 0 {
-            bail!("profile disk_size must be greater than 0");
-        }
+    if let Err(err) = profile.memory_mib() {
+        problems.push(err.to_string());
+    }
+    match profile.disk_size_bytes() {
+        Ok(Some(0)) => problems.push("profile disk_size must be greater than 0".to_string()),
+        Ok(_) => {}
+        Err(err) => problems.push(err.to_string()),
     }
     if let Some(userdata) = &profile.userdata {
         if userdata.trim().is_empty() {
-            bail!("profile userdata cannot be empty");
-        }
-        if !userdata.starts_with("#!") {
-            bail!("profile userdata must start with a shebang (`#!`)");
+            problems.push("profile userdata cannot be empty".to_string());
+        } else if !userdata.starts_with("#!") {
+            problems.push("profile userdata must start with a shebang (`#!`)".to_string());
         }
     }
     for mount in &profile.mounts {
         if mount.source.as_os_str().is_empty() {
-            bail!("mount source cannot be empty");
+            problems.push("mount source cannot be empty".to_string());
         }
         if !mount.target.starts_with('/') {
-            bail!(
+            problems.push(format!(
                 "mount target must be an absolute guest path: {}",
                 mount.target
-            );
+            ));
         }
     }
-    Ok(())
+    problems
+}
+
+/// Reports mount sources that do not exist on this host.
+pub(crate) fn missing_mount_sources(profile: &Profile) -> Vec<String> {
+    profile
+        .mounts
+        .iter()
+        .filter(|mount| !mount.source.as_os_str().is_empty())
+        .filter_map(|mount| match resolve_host_path(&mount.source) {
+            Ok(source) if source.exists() => None,
+            Ok(source) => Some(format!("mount source {} does not exist", source.display())),
+            Err(err) => Some(format!("mount source {}: {err}", mount.source.display())),
+        })
+        .collect()
 }
 
 pub(crate) fn resolve_host_path(path: &Path) -> eyre::Result<PathBuf> {
@@ -433,6 +565,18 @@ fn is_profile_file(path: &Path) -> bool {
         )
 }
 
+fn load_profile(name: String, path: PathBuf) -> eyre::Result<NamedProfile> {
+    let raw = std::fs::read_to_string(&path)
+        .with_context(|| format!("read profile {}", path.display()))?;
+    let profile =
+        parse_profile(&raw).with_context(|| format!("parse profile {}", path.display()))?;
+    Ok(NamedProfile {
+        name,
+        path: Some(path),
+        profile,
+    })
+}
+
 fn profile_name_from_path(path: &Path) -> eyre::Result<String> {
     path.file_stem()
         .and_then(|stem| stem.to_str())
@@ -446,7 +590,83 @@ fn default_mount_mode() -> MountMode {
 
 #[cfg(test)]
 mod tests {
-    use crate::profile::parse_profile;
+    use std::collections::BTreeMap;
+
+    use crate::profile::{
+        missing_mount_sources, parse_profile, parse_profile_unvalidated, profile_json_schema,
+        profile_problems, validate_profile, MountMode, Profile, ProfileMount, ProfileNetwork,
+        ProfileResources,
+    };
+
+    #[test]
+    fn reports_every_profile_problem() {
+        let profile = parse_profile_unvalidated(
+            r#"
+version: "2"
+image: ""
+disk_size: 0gb
+userdata: echo hello
+mounts:
+  - source: /definitely/not/here
+    target: relative
+"#,
+        )
+        .expect("profile yaml should deserialize");
+
+        let problems = profile_problems(&profile);
+        let missing = missing_mount_sources(&profile);
+
+        assert_eq!(problems.len(), 5, "{problems:?}");
+        assert_eq!(
+            missing,
+            vec!["mount source /definitely/not/here does not exist".to_string()]
+        );
+        assert!(validate_profile(&profile)
+            .expect_err("invalid profile")
+            .to_string()
+            .contains("unsupported profile version"));
+    }
+
+    #[test]
+    fn json_schema_covers_every_profile_field() {
+        let profile = Profile {
+            version: "1".to_string(),
+            description: Some("dev".to_string()),
+            image: "ubuntu:24.04".to_string(),
+            resources: Some(ProfileResources {
+                cpus: Some(2),
+                memory: Some("2gb".to_string()),
+            }),
+            disk_size: Some("40gb".to_string()),
+            userdata: Some("#!/bin/sh".to_string()),
+            mounts: vec![ProfileMount {
+                source: "~/src".into(),
+                target: "/src".to_string(),
+                mode: MountMode::Ro,
+            }],
+            network: Some(ProfileNetwork::None),
+            labels: BTreeMap::from([("team".to_string(), "vm".to_string())]),
+        };
+        let keys = |value: &serde_json::Value| {
+            value
+                .as_object()
+                .map(|object| object.keys().cloned().collect::<Vec<_>>())
+                .unwrap_or_default()
+        };
+
+        let schema = profile_json_schema();
+        let serialized = serde_json::to_value(&profile).expect("serialize profile");
+
+        assert_eq!(keys(&schema["properties"]), keys(&serialized));
+        assert_eq!(
+            keys(&schema["properties"]["resources"]["properties"]),
+            keys(&serialized["resources"])
+        );
+        assert_eq!(
+            keys(&schema["properties"]["mounts"]["items"]["properties"]),
+            keys(&serialized["mounts"][0])
+        );
+    }
 
     #[test]
     fn parses_private_network_policy_ref() {
@@ -5,19 +5,75 @@ use std::process::{Command, ExitStatus};
 
 use eyre::{bail, Context as _};
 use libvm::host::{current_host_user, generate_ssh_keypair};
+use libvm::MachineData;
 
-use crate::constants::{GUEST_SSH_PRIVATE_KEY_FILE_NAME, GUEST_SSH_PUBLIC_KEY_FILE_NAME};
+use crate::constants::{
+    GUEST_SSH_PRIVATE_KEY_FILE_NAME, GUEST_SSH_PUBLIC_KEY_FILE_NAME, MACHINE_KNOWN_HOSTS_FILE_NAME,
+};
+
+/// How ssh verifies the guest host key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HostKeyPolicy {
+    /// Pin the key seen on first connect in a per-machine known_hosts file and
+    /// refuse to connect if it changes.
+    Pinned { known_hosts: PathBuf },
+    /// Skip host key verification entirely.
+    Insecure,
+}
+
+impl HostKeyPolicy {
+    pub(crate) fn for_machine(data: &MachineData, insecure: bool) -> Self {
+        if insecure {
+            return Self::Insecure;
+        }
+
+        Self::Pinned {
+            known_hosts: known_hosts_path(&data.machine_dir),
+        }
+    }
+
+    fn ssh_options(&self) -> [String; 2] {
+        match self {
+            Self::Pinned { known_hosts } => [
+                "StrictHostKeyChecking=accept-new".to_string(),
+                format!("UserKnownHostsFile={}", known_hosts.to_string_lossy()),
+            ],
+            Self::Insecure => [
+                "StrictHostKeyChecking=no".to_string(),
+                "UserKnownHostsFile=/dev/null".to_string(),
+            ],
+        }
+    }
+}
+
+fn known_hosts_path(machine_dir: &Path) -> PathBuf {
+    machine_dir.join(MACHINE_KNOWN_HOSTS_FILE_NAME)
+}
+
+/// Forgets the guest host key pinned for the machine in `machine_dir`. A
+/// rebuilt root disk comes with new guest host keys, which the pin would reject.
+pub(crate) fn forget_pinned_host_key(machine_dir: &Path) -> eyre::Result<()> {
+    let known_hosts = known_hosts_path(machine_dir);
+    match std::fs::remove_file(&known_hosts) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => {
+            Err(err).wrap_err_with(|| format!("failed to remove {}", known_hosts.display()))
+        }
+    }
+}
 
 pub(crate) fn exec_remote_shell(
     data_dir: &Path,
     name: &str,
     user: Option<&str>,
+    host_keys: &HostKeyPolicy,
 ) -> eyre::Result<()> {
     let remote_command = format!(
         "/bin/sh -lc '{}; exec \"${{SHELL:-/bin/bash}}\" -l || exec /bin/sh'",
         current_dir_prologue()?
     );
-    let err = ssh_command(data_dir, name, user, true, Some(&remote_command))?.exec();
+    let err = ssh_command(data_dir, name, user, host_keys, true, Some(&remote_command))?.exec();
 
     if err.kind() == io::ErrorKind::NotFound {
         bail!("`ssh` command not found. install OpenSSH client and retry");
@@ -30,6 +86,7 @@ pub(crate) fn run_remote_command(
     data_dir: &Path,
     name: &str,
     user: Option<&str>,
+    host_keys: &HostKeyPolicy,
     argv: &[String],
 ) -> eyre::Result<ExitStatus> {
     if argv.is_empty() {
@@ -38,21 +95,29 @@ pub(crate) fn run_remote_command(
 
     let remote_command = format!("{}; exec {}", current_dir_prologue()?, shell_join(argv));
 
-    ssh_command(data_dir, name, user, false, Some(&remote_command))?
-        .status()
-        .context("run remote command over ssh")
+    ssh_command(
+        data_dir,
+        name,
+        user,
+        host_keys,
+        false,
+        Some(&remote_command),
+    )?
+    .status()
+    .context("run remote command over ssh")
 }
 
 pub(crate) fn run_remote_shell_status(
     data_dir: &Path,
     name: &str,
     user: Option<&str>,
+    host_keys: &HostKeyPolicy,
 ) -> eyre::Result<ExitStatus> {
     let remote_command = format!(
         "/bin/sh -lc '{}; exec \"${{SHELL:-/bin/bash}}\" -l || exec /bin/sh'",
         current_dir_prologue()?
     );
-    ssh_command(data_dir, name, user, true, Some(&remote_command))?
+    ssh_command(data_dir, name, user, host_keys, true, Some(&remote_command))?
         .status()
         .context("run remote shell over ssh")
 }
@@ -61,18 +126,20 @@ fn ssh_command(
     data_dir: &Path,
     name: &str,
     user: Option<&str>,
+    host_keys: &HostKeyPolicy,
     allocate_tty: bool,
     remote_command: Option<&str>,
 ) -> eyre::Result<Command> {
     let exe = std::env::current_exe().context("resolve CLI binary path")?;
     let proxy_command = format!(
-        "{} shell-proxy --name {}",
+        "{} proxy {}",
         shell_quote(&exe.to_string_lossy()),
         shell_quote(name),
     );
     let host_user = current_host_user().context("resolve current host user")?;
     let ssh_user = user.unwrap_or(host_user.name.as_str());
     let private_key_path = ensure_guest_ssh_keypair(data_dir).context("ensure bento SSH keys")?;
+    let [strict_host_key_checking, known_hosts_file] = host_keys.ssh_options();
 
     let mut command = Command::new("ssh");
     command
@@ -97,9 +164,9 @@ fn ssh_command(
         .arg("-o")
         .arg(format!("HostKeyAlias=bento/{name}"))
         .arg("-o")
-        .arg("StrictHostKeyChecking=no")
+        .arg(strict_host_key_checking)
         .arg("-o")
-        .arg("UserKnownHostsFile=/dev/null")
+        .arg(known_hosts_file)
         .arg("-o")
         .arg("Compression=no")
         .arg("-o")
@@ -134,6 +201,34 @@ fn ensure_guest_ssh_keypair(data_dir: &Path) -> eyre::Result<PathBuf> {
     Ok(private_key_path)
 }
 
+/// Returns the OpenSSH public key bento injects into guests, creating the
+/// keypair first if needed.
+pub(crate) fn public_key(data_dir: &Path) -> eyre::Result<String> {
+    ensure_guest_ssh_keypair(data_dir).context("ensure bento SSH keys")?;
+    let (_, public_key_path) = guest_ssh_key_paths(data_dir);
+    let public_key = std::fs::read_to_string(&public_key_path)
+        .with_context(|| format!("read SSH public key {}", public_key_path.display()))?;
+    Ok(public_key.trim().to_string())
+}
+
+/// Replaces the bento SSH keypair and returns the new public key.
+///
+/// The new pair is generated next to the current one and renamed into place,
+/// so an interrupted regeneration never leaves a half-written key behind.
+pub(crate) fn regenerate(data_dir: &Path) -> eyre::Result<String> {
+    let (private_key_path, public_key_path) = guest_ssh_key_paths(data_dir);
+    let staged_private_key_path = private_key_path.with_extension("new");
+    let staged_public_key_path = public_key_path.with_extension("pub.new");
+    let keypair = generate_ssh_keypair(&staged_private_key_path, &staged_public_key_path, None)
+        .context("generate bento SSH keypair")?;
+
+    std::fs::rename(&staged_private_key_path, &private_key_path)
+        .with_context(|| format!("replace SSH private key {}", private_key_path.display()))?;
+    std::fs::rename(&staged_public_key_path, &public_key_path)
+        .with_context(|| format!("replace SSH public key {}", public_key_path.display()))?;
+    Ok(keypair.public_key_openssh.trim().to_string())
+}
+
 fn guest_ssh_key_paths(data_dir: &Path) -> (PathBuf, PathBuf) {
     let keys_dir = data_dir.join("keys");
     (
@@ -163,7 +258,11 @@ fn shell_quote(input: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use crate::ssh::guest_ssh_key_paths;
+    use std::path::PathBuf;
+
+    use crate::ssh::{
+        forget_pinned_host_key, guest_ssh_key_paths, public_key, regenerate, HostKeyPolicy,
+    };
 
     #[test]
     fn guest_ssh_key_paths_use_data_dir_keys_dir() {
@@ -174,4 +273,60 @@ mod tests {
         assert_eq!(private_key_path, temp_dir.path().join("keys/id_ed25519"));
         assert_eq!(public_key_path, temp_dir.path().join("keys/id_ed25519.pub"));
     }
+
+    #[test]
+    fn forget_pinned_host_key_removes_machine_known_hosts() {
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let known_hosts = temp_dir.path().join("known_hosts");
+        std::fs::write(&known_hosts, "[bento-dev] ssh-ed25519 AAAA\n")
+            .expect("known_hosts should be written");
+
+        forget_pinned_host_key(temp_dir.path()).expect("pin should be forgotten");
+
+        assert!(!known_hosts.exists());
+        forget_pinned_host_key(temp_dir.path()).expect("missing pin should be fine");
+    }
+
+    #[test]
+    fn pinned_host_keys_use_machine_known_hosts() {
+        let pinned = HostKeyPolicy::Pinned {
+            known_hosts: PathBuf::from("/data/machines/abc/known_hosts"),
+        };
+
+        assert_eq!(
+            pinned.ssh_options(),
+            [
+                "StrictHostKeyChecking=accept-new".to_string(),
+                "UserKnownHostsFile=/data/machines/abc/known_hosts".to_string(),
+            ]
+        );
+        assert_eq!(
+            HostKeyPolicy::Insecure.ssh_options(),
+            [
+                "StrictHostKeyChecking=no".to_string(),
+                "UserKnownHostsFile=/dev/null".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn regenerate_replaces_keypair_with_private_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::tempdir().expect("tempdir should be created");
+        let original = public_key(temp_dir.path()).expect("create keypair");
+
+        let regenerated = regenerate(temp_dir.path()).expect("regenerate keypair");
+        let (private_key_path, _) = guest_ssh_key_paths(temp_dir.path());
+        let mode = std::fs::metadata(&private_key_path)
+            .expect("private key metadata")
+            .permissions()
+            .mode();
+
+        assert_ne!(original, regenerated);
+        assert!(regenerated.starts_with("ssh-ed25519 "));
+        assert_eq!(public_key(temp_dir.path()).expect("read key"), regenerated);
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!private_key_path.with_extension("new").exists());
+    }
 }
@@ -1,12 +1,20 @@
+use std::future::Future;
 use std::os::fd::{AsFd, AsRawFd};
 
 use eyre::Context as _;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::UnixStream;
 
-pub(crate) async fn attach_serial_stream(stream: UnixStream) -> eyre::Result<()> {
+/// Relays the serial console until either side closes or `keepalive` fails.
+pub(crate) async fn attach_serial_stream(
+    stream: UnixStream,
+    keepalive: impl Future<Output = eyre::Result<()>>,
+) -> eyre::Result<()> {
     print_serial_exit_hint();
-    proxy_serial_stdio(stream).await
+    tokio::select! {
+        result = proxy_serial_stdio(stream) => result,
+        result = keepalive => result,
+    }
 }
 
 fn print_serial_exit_hint() {
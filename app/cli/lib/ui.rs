@@ -68,6 +68,16 @@ pub fn print_json(value: &impl Serialize) -> eyre::Result<()> {
     Ok(())
 }
 
+/// Writes `value` as a single compact JSON line, for streams of events.
+pub fn print_json_line(value: &impl Serialize) -> eyre::Result<()> {
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    serde_json::to_writer(&mut out, value)?;
+    writeln!(out)?;
+    out.flush()?;
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct Spinner {
     pb: Option<ProgressBar>,
@@ -101,9 +111,11 @@ pub struct PullProgressDisplay {
 }
 
 impl Spinner {
-    pub fn start(label: &str, target: impl Into<String>) -> Self {
+    /// Starts a spinner on stderr. It only draws when `progress` is enabled and stderr is a
+    /// terminal; otherwise just the final success line is printed.
+    pub fn start(progress: bool, label: &str, target: impl Into<String>) -> Self {
         let target = target.into();
-        let is_tty = stderr_is_interactive();
+        let is_tty = progress && stderr_is_interactive();
         let (pb, echo_guard) = if is_tty {
             let template = format!("   {{spinner}} {label:<12} {{msg}}");
             let style = ProgressStyle::default_spinner()
@@ -383,6 +395,10 @@ impl PullProgressDisplay {
                 }
                 self.current_applying_layer = Some(index);
             }
+            ImageProgress::ReusingRootfs { .. } => {
+                self.header
+                    .set_message(format!("{:<12} {}", "Reusing", self.reference));
+            }
             ImageProgress::WritingExt4 => {
                 self.finish_current_applying_layer();
                 self.header
@@ -441,10 +457,15 @@ impl PullProgressDisplay {
 pub fn watch_image_progress(
     reference: impl Into<String>,
     mut events: ImageProgressReceiver,
+    quiet: bool,
 ) -> JoinHandle<()> {
     let reference = reference.into();
     tokio::spawn(async move {
-        let mut display = PullProgressDisplay::new(&reference);
+        let mut display = if quiet {
+            PullProgressDisplay::quiet(&reference)
+        } else {
+            PullProgressDisplay::new(&reference)
+        };
         while let Some(event) = events.recv().await {
             display.handle_event(event);
         }
@@ -660,6 +681,21 @@ pub fn relative_time(timestamp: i64, now: i64) -> String {
     format!("{years} years ago")
 }
 
+/// Formats how long ago `started_at` was as a compact uptime such as `3h 12m`.
+pub fn uptime(started_at: i64, now: i64) -> String {
+    let seconds = (now - started_at).max(0);
+    let (days, hours, minutes) = (seconds / 86400, seconds % 86400 / 3600, seconds % 3600 / 60);
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else if minutes > 0 {
+        format!("{minutes}m")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
 pub fn format_unix(timestamp: i64) -> String {
     DateTime::<Utc>::from_timestamp(timestamp, 0)
         .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
@@ -686,7 +722,7 @@ fn write_columns(out: &mut impl Write, values: &[String], widths: &[usize]) -> e
 
 #[cfg(test)]
 mod tests {
-    use super::{relative_time, short_id};
+    use super::{relative_time, short_id, uptime};
 
     #[test]
     fn relative_time_formatting() {
@@ -707,6 +743,17 @@ mod tests {
         assert_eq!(relative_time(now - 604800 * 2, now), "2 weeks ago");
     }
 
+    #[test]
+    fn uptime_formatting() {
+        let now = 1000000;
+
+        assert_eq!(uptime(now - 42, now), "42s");
+        assert_eq!(uptime(now - 300, now), "5m");
+        assert_eq!(uptime(now - 3600 * 3 - 720, now), "3h 12m");
+        assert_eq!(uptime(now - 86400 * 2 - 3600, now), "2d 1h");
+        assert_eq!(uptime(now + 10, now), "0s");
+    }
+
     #[test]
     fn short_id_uses_first_eight_characters_when_available() {
         assert_eq!(short_id("1234567890abcdef"), "12345678");
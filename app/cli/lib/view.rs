@@ -1,7 +1,9 @@
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
-use libvm::{MachineData, MachineNetworkConfig, MachineStatus};
+use libvm::{
+    BrokenMachine, MachineData, MachineDisk, MachineMetrics, MachineNetworkConfig, MachineStatus,
+};
 use serde::Serialize;
 use vm_spec::VmSpec;
 
@@ -16,11 +18,15 @@ pub struct MachineView {
     pub profile: Option<String>,
     pub image: String,
     pub network: MachineNetworkConfig,
+    pub mac: Option<String>,
+    pub addresses: Vec<String>,
     pub created_at: i64,
     pub modified_at: i64,
     pub started_at: Option<i64>,
+    pub vmmon_pid: Option<i32>,
     pub updated_at: i64,
     pub root_disk_size: Option<u64>,
+    pub disks: Vec<MachineDiskView>,
     pub resources: MachineResourcesView,
     pub guest: MachineGuestView,
     pub ready: bool,
@@ -29,6 +35,47 @@ pub struct MachineView {
     pub metadata: BTreeMap<String, String>,
     pub dir: PathBuf,
     pub spec: VmSpec,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<MachineMetricsView>,
+}
+
+/// A stored machine whose configuration can no longer be read.
+#[derive(Debug, Clone, Serialize)]
+pub struct BrokenMachineView {
+    pub id: String,
+    pub name: String,
+    pub state: &'static str,
+    pub error: String,
+}
+
+impl BrokenMachineView {
+    pub fn new(machine: &BrokenMachine) -> Self {
+        Self {
+            id: machine.id.clone(),
+            name: machine.name.clone(),
+            state: "broken",
+            error: machine.reason.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MachineDiskView {
+    pub role: &'static str,
+    pub path: PathBuf,
+    pub read_only: bool,
+    pub size_bytes: Option<u64>,
+}
+
+impl From<MachineDisk> for MachineDiskView {
+    fn from(disk: MachineDisk) -> Self {
+        Self {
+            role: disk.role.label(),
+            path: disk.path,
+            read_only: disk.read_only,
+            size_bytes: disk.size_bytes,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -37,6 +84,26 @@ pub struct MachineResourcesView {
     pub memory_mib: u32,
 }
 
+/// Live resource usage, filled in only when `bento show --metrics` asks for it.
+#[derive(Debug, Clone, Serialize)]
+pub struct MachineMetricsView {
+    pub memory_mib: u64,
+    pub balloon_target_mib: Option<u64>,
+    pub cpu_time_ms: Option<u64>,
+}
+
+impl From<MachineMetrics> for MachineMetricsView {
+    fn from(metrics: MachineMetrics) -> Self {
+        Self {
+            memory_mib: metrics.memory_mib,
+            balloon_target_mib: metrics.balloon_target_mib,
+            cpu_time_ms: metrics
+                .cpu_time
+                .map(|cpu_time| u64::try_from(cpu_time.as_millis()).unwrap_or(u64::MAX)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct MachineGuestView {
     pub status: String,
@@ -61,11 +128,15 @@ impl MachineView {
             profile: data.metadata.get(PROFILE_METADATA_KEY).cloned(),
             image: data.image_ref.clone(),
             network: data.network.clone(),
+            mac: data.guest_mac.clone(),
+            addresses: data.guest_addresses.clone(),
             created_at: data.created_at,
             modified_at: data.modified_at,
             started_at: data.started_at,
+            vmmon_pid: data.vmmon_pid,
             updated_at: data.updated_at,
             root_disk_size: data.root_disk_size,
+            disks: data.disks().into_iter().map(Into::into).collect(),
             resources: MachineResourcesView {
                 cpus: hardware.and_then(|hardware| hardware.cpus).unwrap_or(1),
                 memory_mib: hardware.and_then(|hardware| hardware.memory).unwrap_or(512),
@@ -81,8 +152,16 @@ impl MachineView {
             metadata: data.metadata.clone(),
             dir: data.machine_dir.clone(),
             spec: data.spec.clone(),
+            metrics: None,
         }
     }
+
+    /// Uptime of a machine whose monitor is up.
+    pub fn uptime(&self, now: i64) -> Option<String> {
+        let started_at = self.started_at?;
+        matches!(self.state, "starting" | "running" | "paused")
+            .then(|| crate::ui::uptime(started_at, now))
+    }
 }
 
 pub fn state_label(state: &MachineStatus) -> &'static str {
@@ -4,14 +4,16 @@ use eyre::{eyre, Context};
 use crate::provision::{command_exists, command_output, run_command};
 
 const ROOT_MOUNTPOINT: &str = "/";
+const OVERLAY_FSTYPE: &str = "overlay";
 
 pub(crate) fn apply(config: &ResizeRootfsConfig) -> eyre::Result<()> {
     if !config.enabled {
         return Ok(());
     }
 
-    let source = findmnt("SOURCE", ROOT_MOUNTPOINT)?;
-    let fstype = findmnt("FSTYPE", ROOT_MOUNTPOINT)?;
+    let target = resize_target()?;
+    let source = findmnt("SOURCE", &target)?;
+    let fstype = findmnt("FSTYPE", &target)?;
     tracing::info!(source = %source, fstype = %fstype, "resizing root filesystem");
     resize_filesystem(&source, &fstype)?;
     tracing::info!(source = %source, fstype = %fstype, "reconciled root filesystem size");
@@ -19,6 +21,24 @@ pub(crate) fn apply(config: &ResizeRootfsConfig) -> eyre::Result<()> {
     Ok(())
 }
 
+/// Returns the path whose filesystem backs writes to the root. On an overlay
+/// root that is the upper layer, which lives on the overlay disk.
+fn resize_target() -> eyre::Result<String> {
+    if findmnt("FSTYPE", ROOT_MOUNTPOINT)? != OVERLAY_FSTYPE {
+        return Ok(ROOT_MOUNTPOINT.to_string());
+    }
+    let options = findmnt("OPTIONS", ROOT_MOUNTPOINT)?;
+    overlay_upperdir(&options)
+        .map(str::to_string)
+        .ok_or_else(|| eyre!("overlay root filesystem has no upperdir in {options:?}"))
+}
+
+fn overlay_upperdir(options: &str) -> Option<&str> {
+    options
+        .split(',')
+        .find_map(|option| option.strip_prefix("upperdir="))
+}
+
 fn findmnt(field: &str, target: &str) -> eyre::Result<String> {
     let output = command_output("findmnt", ["-n", "-o", field, "--target", target])?;
     let value = output.trim();
@@ -117,6 +137,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn overlay_upperdir_is_read_from_mount_options() {
+        assert_eq!(
+            crate::provision::resize::overlay_upperdir(
+                "rw,relatime,lowerdir=/run/bento/overlay/lower,upperdir=/run/bento/overlay/disk/upper,workdir=/run/bento/overlay/disk/work"
+            ),
+            Some("/run/bento/overlay/disk/upper")
+        );
+        assert_eq!(
+            crate::provision::resize::overlay_upperdir("rw,lowerdir=/lower"),
+            None
+        );
+    }
+
     #[test]
     fn unsupported_filesystems_do_not_have_resize_plan() {
         assert_eq!(
@@ -115,10 +115,10 @@ pub(crate) fn mount_one(
     }
 }
 
-pub(crate) fn mount_block_auto(source: &[u8], target: &[u8]) -> i32 {
+pub(crate) fn mount_block_auto(source: &[u8], target: &[u8], flags: libc::c_ulong) -> i32 {
     const TYPES: &[&[u8]] = &[b"ext4", b"xfs", b"btrfs", b"erofs", b"squashfs"];
     for &fstype in TYPES {
-        if mount_one(source, target, Some(fstype), 0, None) == 0 {
+        if mount_one(source, target, Some(fstype), flags, None) == 0 {
             return 0;
         }
     }
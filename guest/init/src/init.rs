@@ -7,6 +7,13 @@ use crate::io;
 const DEFAULT_ROOT: &[u8] = b"/dev/vda";
 pub(crate) const DEFAULT_INIT: &[u8] = b"/sbin/init";
 const MNT_ROOT: &[u8] = b"/mnt/root";
+const OVERLAY_LOWER_DIR: &[u8] = b"/run/bento/overlay/lower";
+const OVERLAY_DISK_DIR: &[u8] = b"/run/bento/overlay/disk";
+const OVERLAY_UPPER_DIR: &[u8] = b"/run/bento/overlay/disk/upper";
+const OVERLAY_WORK_DIR: &[u8] = b"/run/bento/overlay/disk/work";
+const OVERLAY_MOUNT_DATA: &[u8] = b"lowerdir=/run/bento/overlay/lower,\
+upperdir=/run/bento/overlay/disk/upper,\
+workdir=/run/bento/overlay/disk/work";
 const AGENT_PAYLOAD_DIR: &[u8] = b"/agent";
 const AGENT_SOURCE_BINARY: &[u8] = b"/agent/bento-agent";
 const AGENT_RUN_DIR: &[u8] = b"/run/agent";
@@ -34,6 +41,7 @@ struct BootConfig {
     root: Vec<u8>,
     init: Vec<u8>,
     rootfstype: Option<Vec<u8>>,
+    overlay: Option<Vec<u8>>,
 }
 
 pub(crate) fn init(_argc: i32, _argv: *const *const u8) -> i32 {
@@ -64,9 +72,7 @@ fn run_init() -> Result<(), &'static str> {
         return Err("root= does not point at a block device");
     }
 
-    if mount_root(&config) != 0 {
-        return Err("failed to mount root filesystem");
-    }
+    mount_root(&config)?;
     prepare_agent_handoff()?;
 
     let init_path = target_init_path(&config.init).ok_or("target init path is too long")?;
@@ -142,6 +148,7 @@ fn read_boot_config() -> BootConfig {
             .unwrap_or(DEFAULT_INIT)
             .to_vec(),
         rootfstype: cmdline_value(&cmdline, b"rootfstype=").map(|value| value.to_vec()),
+        overlay: cmdline_value(&cmdline, b"bento.overlay=").map(|value| value.to_vec()),
     }
 }
 
@@ -167,11 +174,57 @@ fn is_block_device(path: &[u8]) -> bool {
     (stat.st_mode & libc::S_IFMT) == libc::S_IFBLK
 }
 
-fn mount_root(config: &BootConfig) -> i32 {
+fn mount_root(config: &BootConfig) -> Result<(), &'static str> {
+    let Some(overlay) = config.overlay.as_deref() else {
+        if mount_block(config, MNT_ROOT, 0) != 0 {
+            return Err("failed to mount root filesystem");
+        }
+        return Ok(());
+    };
+    mount_overlay_root(config, overlay)
+}
+
+/// Mounts `root=` read-only under /run and layers the ext4 disk named by
+/// `bento.overlay=` over it. The lower and upper mounts stay reachable after
+/// switch_root because /run is moved into the new root.
+fn mount_overlay_root(config: &BootConfig, overlay: &[u8]) -> Result<(), &'static str> {
+    if !is_block_device(overlay) {
+        return Err("bento.overlay= does not point at a block device");
+    }
+    for path in [OVERLAY_LOWER_DIR, OVERLAY_DISK_DIR] {
+        if applets::files::mkdir_parents(path, 0o755) != 0 {
+            return Err("failed to create overlay mount point");
+        }
+    }
+    if mount_block(config, OVERLAY_LOWER_DIR, libc::MS_RDONLY) != 0 {
+        return Err("failed to mount read-only root filesystem");
+    }
+    if applets::system::mount_one(overlay, OVERLAY_DISK_DIR, Some(b"ext4"), 0, None) != 0 {
+        return Err("failed to mount overlay disk");
+    }
+    for path in [OVERLAY_UPPER_DIR, OVERLAY_WORK_DIR] {
+        if applets::files::mkdir_parents(path, 0o755) != 0 {
+            return Err("failed to create overlay directory");
+        }
+    }
+    if applets::system::mount_one(
+        b"overlay",
+        MNT_ROOT,
+        Some(b"overlay"),
+        0,
+        Some(OVERLAY_MOUNT_DATA),
+    ) != 0
+    {
+        return Err("failed to mount overlay root filesystem");
+    }
+    Ok(())
+}
+
+fn mount_block(config: &BootConfig, target: &[u8], flags: libc::c_ulong) -> i32 {
     if let Some(fstype) = config.rootfstype.as_deref() {
-        applets::system::mount_one(&config.root, MNT_ROOT, Some(fstype), 0, None)
+        applets::system::mount_one(&config.root, target, Some(fstype), flags, None)
     } else {
-        applets::system::mount_block_auto(&config.root, MNT_ROOT)
+        applets::system::mount_block_auto(&config.root, target, flags)
     }
 }
 
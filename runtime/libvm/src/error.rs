@@ -5,7 +5,7 @@ use thiserror::Error;
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum LibVmError {
-    #[error("could not resolve Bento data directory from XDG_DATA_HOME or HOME")]
+    #[error("could not resolve Bento data directory from BENTO_DATA_HOME, XDG_DATA_HOME or HOME")]
     DataDirUnavailable,
 
     #[error("could not resolve Bento config directory from XDG_CONFIG_HOME or HOME")]
@@ -55,6 +55,22 @@ pub enum LibVmError {
     )]
     VmMonExecutableNotFound { expected_path: PathBuf },
 
+    #[error(
+        "vmmon at {path} is not an executable file. Rebuild it or restore its execute permission."
+    )]
+    VmMonExecutableNotExecutable { path: PathBuf },
+
+    #[error(transparent)]
+    SocketPathTooLong(#[from] protocol::socket::SocketPathTooLong),
+
+    #[error(
+        "run directory {path} must be a directory only the current user can access; remove it or set XDG_RUNTIME_DIR"
+    )]
+    InsecureRunDir { path: PathBuf },
+
+    #[error("could not query host capabilities: {message}")]
+    HostCapabilities { message: String },
+
     #[error("invalid create request for machine {name:?}: {reason}")]
     InvalidCreateRequest { name: String, reason: String },
 
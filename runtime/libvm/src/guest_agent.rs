@@ -24,7 +24,7 @@ use crate::constants::{
 use crate::host::{self, HostUser};
 use crate::network::VmmonNetworkAttachment;
 use crate::paths::LocalPaths;
-use crate::RuntimeNetworkingConfig;
+use crate::{RuntimeNetworkingConfig, VzNatRuntimeConfig};
 
 pub(crate) struct GuestAgentConfigInput<'a> {
     pub(crate) paths: &'a LocalPaths,
@@ -55,7 +55,13 @@ struct ForwardPluginUdsConfig {
 
 pub(crate) fn build_config(input: GuestAgentConfigInput<'_>) -> eyre::Result<AgentConfig> {
     let host_context = load_host_context(input.paths, input.networking)?;
-    build_config_with_host_context(input.machine_name, input.spec, input.network, &host_context)
+    build_config_with_host_context(
+        input.machine_name,
+        input.spec,
+        input.network,
+        &input.networking.vznat,
+        &host_context,
+    )
 }
 
 pub(crate) fn write_config(path: &Path, config: &AgentConfig) -> eyre::Result<()> {
@@ -86,11 +92,12 @@ fn build_config_with_host_context(
     machine_name: &str,
     spec: &VmSpec,
     network: &VmmonNetworkAttachment,
+    vznat: &VzNatRuntimeConfig,
     host_context: &GuestAgentHostContext,
 ) -> eyre::Result<AgentConfig> {
     Ok(AgentConfig {
         forward: build_forward_config(spec)?,
-        provision: build_provision_config(machine_name, spec, network, host_context)?,
+        provision: build_provision_config(machine_name, spec, network, vznat, host_context)?,
     })
 }
 
@@ -130,6 +137,7 @@ fn build_provision_config(
     machine_name: &str,
     spec: &VmSpec,
     network: &VmmonNetworkAttachment,
+    vznat: &VzNatRuntimeConfig,
     host_context: &GuestAgentHostContext,
 ) -> eyre::Result<ProvisionConfig> {
     Ok(ProvisionConfig {
@@ -153,7 +161,7 @@ fn build_provision_config(
             pem: pem_with_trailing_newline(&host_context.certificate_authority_pem),
             update_trust: true,
         }),
-        network: build_provision_network_config(network)?,
+        network: build_provision_network_config(network, vznat)?,
         rosetta: AgentRosettaConfig {
             enabled: spec
                 .hardware
@@ -235,6 +243,7 @@ fn provision_userdata(spec: &VmSpec) -> eyre::Result<Option<UserdataConfig>> {
 
 fn build_provision_network_config(
     network: &VmmonNetworkAttachment,
+    vznat: &VzNatRuntimeConfig,
 ) -> eyre::Result<ProvisionNetworkConfig> {
     let interfaces = match network {
         VmmonNetworkAttachment::None => Vec::new(),
@@ -245,7 +254,7 @@ fn build_provision_network_config(
                 mac_address: None,
             },
             dhcp4: true,
-            dhcp6: false,
+            dhcp6: vznat.ipv6,
         }],
         VmmonNetworkAttachment::UnixDatagram { mac, .. } => vec![NetworkInterfaceConfig {
             name: UNIX_DATAGRAM_INTERFACE_NAME.to_string(),
@@ -336,6 +345,7 @@ mod tests {
     use crate::host::{self, HostUser};
     use crate::network::VmmonNetworkAttachment;
     use crate::paths::LocalPaths;
+    use crate::VzNatRuntimeConfig;
 
     fn sample_spec(kernel_cmdline: Vec<String>) -> VmSpec {
         VmSpec {
@@ -353,8 +363,13 @@ mod tests {
             hardware: Some(Hardware {
                 cpus: Some(4),
                 memory: Some(4096),
+                min_memory: None,
                 nested_virtualization: Some(false),
                 rosetta: Some(false),
+                graphics: Some(false),
+                keyboard: Some(false),
+                entropy: Some(true),
+                memory_balloon: Some(true),
             }),
             storage: Some(Storage { disks: Vec::new() }),
             mounts: Vec::new(),
@@ -374,6 +389,7 @@ mod tests {
         spec.vsock
             .get_or_insert_with(|| Vsock {
                 endpoints: Vec::new(),
+                connect_retry: None,
             })
             .endpoints
             .push(endpoint);
@@ -511,10 +527,24 @@ mod tests {
         assert!(err.to_string().contains("does not match"));
     }
 
+    #[test]
+    fn provision_network_without_attachment_configures_no_interfaces() {
+        let config = build_provision_network_config(
+            &VmmonNetworkAttachment::None,
+            &VzNatRuntimeConfig::default(),
+        )
+        .expect("network provision config should render");
+
+        assert!(config.interfaces.is_empty());
+    }
+
     #[test]
     fn provision_network_for_vznat_matches_virtio_net_driver() {
-        let config = build_provision_network_config(&VmmonNetworkAttachment::VzNat { mac: None })
-            .expect("network provision config should render");
+        let config = build_provision_network_config(
+            &VmmonNetworkAttachment::VzNat { mac: None },
+            &VzNatRuntimeConfig::default(),
+        )
+        .expect("network provision config should render");
 
         assert_eq!(config.interfaces.len(), 1);
         assert_eq!(config.interfaces[0].name, "en");
@@ -527,6 +557,18 @@ mod tests {
         assert!(!config.interfaces[0].dhcp6);
     }
 
+    #[test]
+    fn provision_network_for_vznat_enables_ipv6_when_configured() {
+        let config = build_provision_network_config(
+            &VmmonNetworkAttachment::VzNat { mac: None },
+            &VzNatRuntimeConfig::new().with_ipv6(true),
+        )
+        .expect("network provision config should render");
+
+        assert!(config.interfaces[0].dhcp4);
+        assert!(config.interfaces[0].dhcp6);
+    }
+
     #[test]
     fn guest_agent_has_no_forward_without_endpoint() {
         let config = build_forward_config(&sample_spec(Vec::new())).expect("forward config");
@@ -543,6 +585,7 @@ mod tests {
             "demo",
             &spec,
             &VmmonNetworkAttachment::None,
+            &VzNatRuntimeConfig::default(),
             &host_context(),
         )
         .expect("resolve provision config");
@@ -564,6 +607,7 @@ mod tests {
             "demo",
             &spec,
             &VmmonNetworkAttachment::None,
+            &VzNatRuntimeConfig::default(),
             &host_context(),
         )
         .expect_err("cloud-config userdata should be rejected");
@@ -590,6 +634,7 @@ mod tests {
                 path: PathBuf::from("/run/bento/net.sock"),
                 mac: "02:00:00:00:00:01".to_string(),
             },
+            &VzNatRuntimeConfig::default(),
             &host_context(),
         )
         .expect("resolve provision config");
@@ -654,6 +699,7 @@ mod tests {
             "demo",
             &spec,
             &VmmonNetworkAttachment::None,
+            &VzNatRuntimeConfig::default(),
             &host_context(),
         )
         .expect("resolve provision config");
@@ -718,6 +764,7 @@ mod tests {
             "demo",
             &spec,
             &VmmonNetworkAttachment::None,
+            &VzNatRuntimeConfig::default(),
             &host_context(),
         )
         .expect("build agent config");
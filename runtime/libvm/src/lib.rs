@@ -35,9 +35,12 @@ mod vmmon;
 pub use crate::error::LibVmError;
 pub use crate::host::{ensure_certificate_authority, CertificateAuthority};
 pub use crate::machine::{
-    resolve_mount_location, Machine, MachineBuilder, MachineData, MachineExit, MachineExitCommand,
-    MachineExitOutcome, MachineKillOptions, MachineRef, MachineStartOptions, MachineStatus,
-    MachineStopOptions, MachineUpdate, MachineWaitOptions, Memory, DEFAULT_MACHINE_WAIT_TIMEOUT,
+    resolve_mount_location, BrokenMachine, Machine, MachineBuilder, MachineData, MachineDisk,
+    MachineDiskRole, MachineExit, MachineExitCommand, MachineExitOutcome, MachineKillOptions,
+    MachineMetrics, MachineRef, MachineService, MachineServiceKind, MachineStartOptions,
+    MachineStatus, MachineStatusSource, MachineStatusUpdate, MachineStatusWatch,
+    MachineStopOptions, MachineUpdate, MachineWaitOptions, Memory, RootDiskClone,
+    DEFAULT_MACHINE_WAIT_TIMEOUT,
 };
 pub use crate::network::{
     MachineNetworkConfig, NetworkBuilder, NetworkDefinition, NetworkDriver, NetworkDriverKind,
@@ -45,5 +48,8 @@ pub use crate::network::{
 };
 pub use crate::runtime::{
     NetdRuntimeConfig, PathChoice, Runtime, RuntimeBuilder, RuntimeConfig, RuntimeNetworkingConfig,
+    VzNatRuntimeConfig,
+};
+pub use crate::vmmon::{
+    host_capabilities, vmmon_executable, HostCapabilities, DEFAULT_GUEST_READINESS_TIMEOUT,
 };
-pub use crate::vmmon::DEFAULT_GUEST_READINESS_TIMEOUT;
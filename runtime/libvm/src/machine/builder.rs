@@ -2,13 +2,15 @@ use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use vm_spec::{Boot, Disk, Guest, GuestOs, Hardware, Kernel, Mount, Storage, VmSpec};
+use vm_spec::{Boot, Disk, Guest, GuestOs, Hardware, Hooks, Kernel, Mount, Storage, VmSpec};
 
 use crate::lock_manager::ManagedLock;
-use crate::machine::root_disk::{clone_or_copy_root_disk, resize_raw_disk};
+use crate::machine::root_disk::{
+    clone_or_copy_root_disk, create_overlay_disk, resize_raw_disk, RootDiskClone,
+};
 use crate::machine::{generate_machine_name, validate_machine_name, Machine, Memory};
 use crate::network::MachineNetworkConfig;
-use crate::paths::{root_disk_relative_path, MachinePaths};
+use crate::paths::{root_disk_path_in, root_disk_relative_path};
 use crate::runtime::core::{stopped_machine_state, write_machine_config};
 use crate::runtime::Runtime;
 use crate::store::models::{
@@ -20,6 +22,8 @@ use crate::LibVmError;
 const DEFAULT_IMAGE_CPUS: u8 = 1;
 const DEFAULT_IMAGE_MEMORY_MIB: u32 = 512;
 const ROOT_DISK_KERNEL_ARG: &str = "root=/dev/vda";
+const READ_ONLY_ROOT_KERNEL_ARG: &str = "ro";
+const OVERLAY_DISK_KERNEL_ARG: &str = "bento.overlay=/dev/vdb";
 const GENERATED_NAME_ATTEMPTS: u32 = 3;
 
 #[derive(Debug, Clone)]
@@ -31,14 +35,22 @@ struct MachineCreateRequest {
     metadata: BTreeMap<String, String>,
     cpus: Option<u8>,
     memory: Option<Memory>,
+    min_memory: Option<Memory>,
     kernel: Option<PathBuf>,
     initramfs: Option<PathBuf>,
     disk_size_bytes: Option<u64>,
+    copy_root_disk: bool,
+    overlay_template: Option<PathBuf>,
     nested_virtualization: bool,
     rosetta: bool,
+    graphics: bool,
+    keyboard: bool,
+    entropy: bool,
+    memory_balloon: bool,
     userdata: Option<String>,
     disks: Vec<PathBuf>,
     mounts: Vec<Mount>,
+    hooks: Hooks,
     network: Option<MachineNetworkConfig>,
 }
 
@@ -109,14 +121,22 @@ impl MachineBuilder {
                 metadata: BTreeMap::new(),
                 cpus: None,
                 memory: None,
+                min_memory: None,
                 kernel: None,
                 initramfs: None,
                 disk_size_bytes: None,
+                copy_root_disk: false,
+                overlay_template: None,
                 nested_virtualization: false,
                 rosetta: false,
+                graphics: false,
+                keyboard: false,
+                entropy: true,
+                memory_balloon: true,
                 userdata: None,
                 disks: Vec::new(),
                 mounts: Vec::new(),
+                hooks: Hooks::default(),
                 network: None,
             },
         }
@@ -182,6 +202,18 @@ impl MachineBuilder {
         self
     }
 
+    /// Sets the memory floor the balloon device will not reclaim below.
+    pub fn min_memory(mut self, min_memory: Memory) -> Self {
+        self.request.min_memory = Some(min_memory);
+        self
+    }
+
+    /// Sets the optional memory floor for the balloon device.
+    pub fn maybe_min_memory(mut self, min_memory: Option<Memory>) -> Self {
+        self.request.min_memory = min_memory;
+        self
+    }
+
     /// Sets a kernel path override.
     pub fn kernel(mut self, kernel: impl Into<PathBuf>) -> Self {
         self.request.kernel = Some(kernel.into());
@@ -218,6 +250,29 @@ impl MachineBuilder {
         self
     }
 
+    /// Copies the base rootfs in full instead of attempting a copy-on-write clone.
+    pub fn copy_root_disk(mut self, enabled: bool) -> Self {
+        self.request.copy_root_disk = enabled;
+        self
+    }
+
+    /// Attaches the base rootfs read-only as `/dev/vda` with a clone of the empty
+    /// ext4 disk `template` as `/dev/vdb` instead of cloning the base.
+    ///
+    /// The guest init mounts an overlayfs with `/dev/vdb` as the writable upper
+    /// layer. The base rootfs must stay in the image cache for as long as the
+    /// machine exists.
+    pub fn overlay_root_disk(mut self, template: impl Into<PathBuf>) -> Self {
+        self.request.overlay_template = Some(template.into());
+        self
+    }
+
+    /// Sets an optional overlay template, see [`MachineBuilder::overlay_root_disk`].
+    pub fn maybe_overlay_root_disk(mut self, template: Option<impl Into<PathBuf>>) -> Self {
+        self.request.overlay_template = template.map(Into::into);
+        self
+    }
+
     /// Enables or disables nested virtualization.
     pub fn nested_virtualization(mut self, enabled: bool) -> Self {
         self.request.nested_virtualization = enabled;
@@ -230,6 +285,30 @@ impl MachineBuilder {
         self
     }
 
+    /// Enables or disables the graphics and pointing devices.
+    pub fn graphics(mut self, enabled: bool) -> Self {
+        self.request.graphics = enabled;
+        self
+    }
+
+    /// Enables or disables the USB keyboard.
+    pub fn keyboard(mut self, enabled: bool) -> Self {
+        self.request.keyboard = enabled;
+        self
+    }
+
+    /// Enables or disables the entropy device. Enabled by default.
+    pub fn entropy(mut self, enabled: bool) -> Self {
+        self.request.entropy = enabled;
+        self
+    }
+
+    /// Enables or disables the memory balloon device. Enabled by default.
+    pub fn memory_balloon(mut self, enabled: bool) -> Self {
+        self.request.memory_balloon = enabled;
+        self
+    }
+
     /// Sets guest userdata.
     pub fn userdata(mut self, userdata: impl Into<String>) -> Self {
         self.request.userdata = Some(userdata.into());
@@ -254,6 +333,12 @@ impl MachineBuilder {
         self
     }
 
+    /// Sets host commands vmmon runs before start and after stop.
+    pub fn hooks(mut self, hooks: Hooks) -> Self {
+        self.request.hooks = hooks;
+        self
+    }
+
     /// Sets the durable network config.
     pub fn network(mut self, network: MachineNetworkConfig) -> Self {
         self.request.network = Some(network);
@@ -268,16 +353,50 @@ impl MachineBuilder {
 
     /// Creates the machine.
     pub async fn create(self) -> Result<Machine, LibVmError> {
+        let (machine, _) = self.create_with_root_disk().await?;
+        Ok(machine)
+    }
+
+    /// Creates the machine and reports how its root disk was cloned.
+    pub async fn create_with_root_disk(self) -> Result<(Machine, RootDiskClone), LibVmError> {
         let runtime = self.runtime;
-        let config = create_machine_config(&runtime, self.request).await?;
-        Ok(Machine::new(runtime, config.id))
+        let (config, root_disk) = create_machine_config(&runtime, self.request).await?;
+        Ok((Machine::new(runtime, config.id), root_disk))
+    }
+
+    /// Validates the request and returns the VM spec `create` would write.
+    ///
+    /// Nothing is written: no machine directory, root disk or store record is created.
+    pub async fn dry_run(self) -> Result<VmSpec, LibVmError> {
+        let request = self.request;
+        let name = match request.name.clone() {
+            Some(name) => name,
+            None => generate_machine_name()?,
+        };
+        validate_machine_name(&name)?;
+        if self
+            .runtime
+            .machine_config_by_name(name.as_str())
+            .await?
+            .is_some()
+        {
+            return Err(LibVmError::MachineAlreadyExists { name });
+        }
+
+        let spec = machine_create_spec(&request, &name)?;
+        canonicalize_existing_file(&name, &request.base_rootfs_path, "base rootfs")?;
+        let network = request.network.unwrap_or_default().into();
+        self.runtime
+            .validate_machine_network_config(&network)
+            .await?;
+        Ok(spec)
     }
 }
 
 async fn create_machine_config(
     runtime: &Runtime,
     request: MachineCreateRequest,
-) -> Result<MachineConfig, LibVmError> {
+) -> Result<(MachineConfig, RootDiskClone), LibVmError> {
     let Some(name) = request.name.clone() else {
         return create_machine_config_with_generated_name(runtime, request).await;
     };
@@ -288,7 +407,7 @@ async fn create_machine_config(
 async fn create_machine_config_with_generated_name(
     runtime: &Runtime,
     request: MachineCreateRequest,
-) -> Result<MachineConfig, LibVmError> {
+) -> Result<(MachineConfig, RootDiskClone), LibVmError> {
     for _ in 0..GENERATED_NAME_ATTEMPTS {
         let name = generate_machine_name()?;
         match create_machine_config_with_name(runtime, request.clone(), name).await {
@@ -306,15 +425,10 @@ async fn create_machine_config_with_name(
     runtime: &Runtime,
     request: MachineCreateRequest,
     name: String,
-) -> Result<MachineConfig, LibVmError> {
-    if matches!(request.disk_size_bytes, Some(0)) {
-        return Err(LibVmError::InvalidCreateRequest {
-            name,
-            reason: "root disk size must be greater than 0".to_string(),
-        });
-    }
-
-    let base_rootfs_path = canonicalize_existing_path(&request.base_rootfs_path, "base rootfs")?;
+) -> Result<(MachineConfig, RootDiskClone), LibVmError> {
+    let spec = machine_create_spec(&request, &name)?;
+    let base_rootfs_path =
+        canonicalize_existing_file(&name, &request.base_rootfs_path, "base rootfs")?;
     let root_disk_size = request.disk_size_bytes.or_else(|| {
         fs::metadata(&base_rootfs_path)
             .ok()
@@ -326,46 +440,120 @@ async fn create_machine_config_with_name(
             reason: "root disk size must be greater than 0".to_string(),
         });
     }
-    let kernel_path = canonicalize_optional_existing_path(request.kernel.as_deref(), "kernel")?;
+
+    let network = request.network.unwrap_or_default().into();
+    runtime.validate_machine_network_config(&network).await?;
+    let create = create_machine_guard(
+        runtime,
+        MachineCreatePlan {
+            name,
+            spec,
+            image_ref: request.image_ref.clone(),
+            root_disk_size,
+            labels: request.labels,
+            metadata: request.metadata,
+            network,
+        },
+    )
+    .await?;
+    let rootfs_path = root_disk_path_in(create.dir());
+    let root_disk = if let Some(template) = &request.overlay_template {
+        let size_bytes = match root_disk_size {
+            Some(size_bytes) => size_bytes,
+            None => fs::metadata(&base_rootfs_path)?.len(),
+        };
+        create_overlay_disk(&base_rootfs_path, template, &rootfs_path, size_bytes)?
+    } else {
+        let root_disk =
+            clone_or_copy_root_disk(&base_rootfs_path, &rootfs_path, request.copy_root_disk)?;
+        if let Some(size_bytes) = request.disk_size_bytes {
+            resize_raw_disk(&rootfs_path, size_bytes)?;
+        }
+        root_disk
+    };
+
+    Ok((create.commit(runtime).await?, root_disk))
+}
+
+/// Validates the request fields that end up in the VM spec and builds it.
+fn machine_create_spec(request: &MachineCreateRequest, name: &str) -> Result<VmSpec, LibVmError> {
+    if matches!(request.disk_size_bytes, Some(0)) {
+        return Err(LibVmError::InvalidCreateRequest {
+            name: name.to_string(),
+            reason: "root disk size must be greater than 0".to_string(),
+        });
+    }
+
+    let kernel_path =
+        canonicalize_optional_existing_path(name, request.kernel.as_deref(), "kernel")?;
     let initramfs_path =
-        canonicalize_optional_existing_path(request.initramfs.as_deref(), "initramfs")?;
+        canonicalize_optional_existing_path(name, request.initramfs.as_deref(), "initramfs")?;
     if let Some(userdata) = request.userdata.as_deref() {
         if userdata.trim().is_empty() {
             return Err(LibVmError::InvalidCreateRequest {
-                name,
+                name: name.to_string(),
                 reason: "userdata cannot be empty".to_string(),
             });
         }
     }
-    let userdata = request.userdata;
-    let disk_paths = canonicalize_existing_paths(&request.disks, "disk")?;
+    let userdata = request.userdata.clone();
+    let disk_paths = canonicalize_existing_files(name, &request.disks, "disk")?;
 
     let resolved_cpus = request.cpus.unwrap_or(DEFAULT_IMAGE_CPUS);
     let resolved_memory = request
         .memory
-        .map(|memory| memory.to_vm_spec_mebibytes(&name))
+        .map(|memory| memory.to_vm_spec_mebibytes(name))
         .transpose()?
         .unwrap_or(DEFAULT_IMAGE_MEMORY_MIB);
+    let resolved_min_memory = request
+        .min_memory
+        .map(|memory| memory.to_vm_spec_mebibytes(name))
+        .transpose()?;
+    if let Some(min_memory) = resolved_min_memory {
+        if min_memory > resolved_memory {
+            return Err(LibVmError::InvalidCreateRequest {
+                name: name.to_string(),
+                reason: format!(
+                    "minimum memory {min_memory} MiB exceeds memory {resolved_memory} MiB"
+                ),
+            });
+        }
+    }
 
-    let mounts = assign_mount_tags(request.mounts);
-    let disks = std::iter::once(Disk {
+    let mounts = assign_mount_tags(request.mounts.clone());
+    let mut cmdline = vec![ROOT_DISK_KERNEL_ARG.to_string()];
+    let mut disks = Vec::new();
+    if request.overlay_template.is_some() {
+        if request.copy_root_disk {
+            return Err(LibVmError::InvalidCreateRequest {
+                name: name.to_string(),
+                reason: "an overlay root disk cannot also be copied".to_string(),
+            });
+        }
+        cmdline.push(READ_ONLY_ROOT_KERNEL_ARG.to_string());
+        cmdline.push(OVERLAY_DISK_KERNEL_ARG.to_string());
+        disks.push(Disk {
+            path: canonicalize_existing_file(name, &request.base_rootfs_path, "base rootfs")?,
+            read_only: true,
+        });
+    }
+    disks.push(Disk {
         path: root_disk_relative_path(),
         read_only: false,
-    })
-    .chain(disk_paths.into_iter().map(|path| Disk {
+    });
+    disks.extend(disk_paths.into_iter().map(|path| Disk {
         path,
         read_only: false,
-    }))
-    .collect();
+    }));
 
-    let spec = VmSpec {
+    Ok(VmSpec {
         guest: Some(Guest {
             os: Some(GuestOs::Linux),
         }),
         boot: Some(Boot {
             kernel: Some(Kernel {
                 path: kernel_path,
-                cmdline: vec![ROOT_DISK_KERNEL_ARG.to_string()],
+                cmdline,
                 initramfs: initramfs_path,
             }),
             userdata,
@@ -373,37 +561,19 @@ async fn create_machine_config_with_name(
         hardware: Some(Hardware {
             cpus: Some(resolved_cpus),
             memory: Some(resolved_memory),
+            min_memory: resolved_min_memory,
             nested_virtualization: Some(request.nested_virtualization),
             rosetta: Some(request.rosetta),
+            graphics: Some(request.graphics),
+            keyboard: Some(request.keyboard),
+            entropy: Some(request.entropy),
+            memory_balloon: Some(request.memory_balloon),
         }),
         storage: Some(Storage { disks }),
         mounts,
+        hooks: (request.hooks != Hooks::default()).then(|| request.hooks.clone()),
         ..VmSpec::current()
-    };
-
-    let network = request.network.unwrap_or_default().into();
-    runtime.validate_machine_network_config(&network).await?;
-    let create = create_machine_guard(
-        runtime,
-        MachineCreatePlan {
-            name,
-            spec,
-            image_ref: request.image_ref.clone(),
-            root_disk_size,
-            labels: request.labels,
-            metadata: request.metadata,
-            network,
-        },
-    )
-    .await?;
-    let rootfs_path = MachinePaths::new(create.dir()).root_disk_path();
-    clone_or_copy_root_disk(&base_rootfs_path, &rootfs_path)?;
-
-    if let Some(size_bytes) = request.disk_size_bytes {
-        resize_raw_disk(&rootfs_path, size_bytes)?;
-    }
-
-    create.commit(runtime).await
+    })
 }
 
 async fn create_machine_guard(
@@ -468,6 +638,7 @@ fn assign_mount_tags(mounts: Vec<Mount>) -> Vec<Mount> {
 }
 
 fn canonicalize_optional_existing_path(
+    name: &str,
     path: Option<&Path>,
     kind: &str,
 ) -> Result<Option<PathBuf>, LibVmError> {
@@ -475,17 +646,59 @@ fn canonicalize_optional_existing_path(
         return Ok(None);
     };
 
-    Ok(Some(canonicalize_existing_path(path, kind)?))
+    Ok(Some(canonicalize_existing_file(name, path, kind)?))
+}
+
+/// Returns the read-only base rootfs disk that sits in front of the writable
+/// root disk on overlay machines.
+pub(crate) fn overlay_base_disk_mut(spec: &mut VmSpec) -> Option<&mut Disk> {
+    let disks = &mut spec.storage.as_mut()?.disks;
+    let base = overlay_base_disk_index(disks)?;
+    disks.get_mut(base)
+}
+
+/// Whether `spec` boots from a read-only base rootfs with an overlay root disk.
+pub(crate) fn has_overlay_root_disk(spec: &VmSpec) -> bool {
+    spec.storage
+        .as_ref()
+        .and_then(|storage| overlay_base_disk_index(&storage.disks))
+        .is_some()
+}
+
+/// Index of the read-only base rootfs on overlay machines.
+pub(crate) fn overlay_base_disk_index(disks: &[Disk]) -> Option<usize> {
+    let root = disks
+        .iter()
+        .position(|disk| disk.path == root_disk_relative_path())?;
+    let base = root.checked_sub(1)?;
+    disks[base].read_only.then_some(base)
 }
 
-fn canonicalize_existing_paths(paths: &[PathBuf], kind: &str) -> Result<Vec<PathBuf>, LibVmError> {
+fn canonicalize_existing_files(
+    name: &str,
+    paths: &[PathBuf],
+    kind: &str,
+) -> Result<Vec<PathBuf>, LibVmError> {
     paths
         .iter()
-        .map(|path| canonicalize_existing_path(path, kind))
+        .map(|path| canonicalize_existing_file(name, path, kind))
         .collect()
 }
 
-fn canonicalize_existing_path(path: &Path, kind: &str) -> Result<PathBuf, LibVmError> {
+/// Like `canonicalize_existing_path`, but also rejects directories and device
+/// nodes so create fails instead of the first start.
+fn canonicalize_existing_file(name: &str, path: &Path, kind: &str) -> Result<PathBuf, LibVmError> {
+    let canonical = canonicalize_existing_path(name, path, kind)?;
+    if !canonical.is_file() {
+        return Err(LibVmError::InvalidCreateRequest {
+            name: name.to_string(),
+            reason: format!("{kind} is not a regular file: {}", canonical.display()),
+        });
+    }
+    Ok(canonical)
+}
+
+fn canonicalize_existing_path(name: &str, path: &Path, kind: &str) -> Result<PathBuf, LibVmError> {
     let abs = if path.is_absolute() {
         path.to_path_buf()
     } else {
@@ -493,7 +706,7 @@ fn canonicalize_existing_path(path: &Path, kind: &str) -> Result<PathBuf, LibVmE
     };
 
     std::fs::canonicalize(&abs).map_err(|err| LibVmError::InvalidCreateRequest {
-        name: kind.to_string(),
+        name: name.to_string(),
         reason: format!("{kind} path does not exist: {} ({err})", abs.display()),
     })
 }
@@ -578,17 +791,18 @@ mod tests {
     use std::path::{Path, PathBuf};
     use std::sync::Arc;
 
-    use vm_spec::{Boot, Guest, GuestOs, Hardware, Kernel, Mount, VmSpec};
+    use vm_spec::{Boot, Guest, GuestOs, Hardware, Hooks, Kernel, Mount, VmSpec};
 
     use crate::machine::builder::{
-        assign_mount_tags, create_machine_config, create_machine_guard, MachineCreateGuard,
-        MachineCreatePlan, MachineCreateRequest, ROOT_DISK_KERNEL_ARG,
+        assign_mount_tags, create_machine_config, create_machine_guard, MachineBuilder,
+        MachineCreateGuard, MachineCreatePlan, MachineCreateRequest, OVERLAY_DISK_KERNEL_ARG,
+        READ_ONLY_ROOT_KERNEL_ARG, ROOT_DISK_KERNEL_ARG,
     };
     use crate::paths::{root_disk_relative_path, LocalPaths};
     use crate::runtime::Runtime;
     use crate::store::models::{MachineId, MachineNetworkConfig};
     use crate::store::MockDataStore;
-    use crate::{LibVmError, Memory, RuntimeNetworkingConfig};
+    use crate::{LibVmError, Memory, RootDiskClone, RuntimeNetworkingConfig};
 
     fn sample_vm_spec() -> VmSpec {
         VmSpec {
@@ -606,8 +820,13 @@ mod tests {
             hardware: Some(Hardware {
                 cpus: Some(4),
                 memory: Some(4096),
+                min_memory: None,
                 nested_virtualization: Some(false),
                 rosetta: Some(false),
+                graphics: Some(false),
+                keyboard: Some(false),
+                entropy: Some(true),
+                memory_balloon: Some(true),
             }),
             ..VmSpec::current()
         }
@@ -667,14 +886,22 @@ mod tests {
             metadata: std::collections::BTreeMap::new(),
             cpus: None,
             memory: None,
+            min_memory: None,
             kernel: None,
             initramfs: None,
             disk_size_bytes: None,
+            copy_root_disk: false,
+            overlay_template: None,
             nested_virtualization: false,
             rosetta: false,
+            graphics: false,
+            keyboard: false,
+            entropy: true,
+            memory_balloon: true,
             userdata: None,
             disks: Vec::new(),
             mounts: Vec::new(),
+            hooks: Hooks::default(),
             network: None,
         }
     }
@@ -711,9 +938,10 @@ mod tests {
         let runtime = runtime_with_mock_store(paths, store).await;
         let base_rootfs_path = write_base_rootfs(temp.path());
 
-        let machine = create_machine_config(&runtime, create_request(base_rootfs_path, "devbox"))
-            .await
-            .expect("machine should be created");
+        let (machine, _) =
+            create_machine_config(&runtime, create_request(base_rootfs_path, "devbox"))
+                .await
+                .expect("machine should be created");
 
         let root_disk = machine.machine_dir.join(root_disk_relative_path());
         assert!(root_disk.exists());
@@ -743,7 +971,7 @@ mod tests {
         let mut request = create_request(base_rootfs_path, "ignored");
         request.name = None;
 
-        let config = create_machine_config(&runtime, request)
+        let (config, _) = create_machine_config(&runtime, request)
             .await
             .expect("machine should be created");
 
@@ -788,7 +1016,7 @@ mod tests {
         let mut request = create_request(base_rootfs_path, "ignored");
         request.name = None;
 
-        let config = create_machine_config(&runtime, request)
+        let (config, _) = create_machine_config(&runtime, request)
             .await
             .expect("machine should be created after retry");
 
@@ -813,8 +1041,10 @@ mod tests {
         request.memory = Some(Memory::gibibytes(8));
         request.nested_virtualization = true;
         request.rosetta = true;
+        request.graphics = true;
+        request.memory_balloon = false;
 
-        let config = create_machine_config(&runtime, request)
+        let (config, _) = create_machine_config(&runtime, request)
             .await
             .expect("machine should be created");
 
@@ -823,6 +1053,10 @@ mod tests {
         assert_eq!(hardware.memory, Some(8192));
         assert_eq!(hardware.nested_virtualization, Some(true));
         assert_eq!(hardware.rosetta, Some(true));
+        assert_eq!(hardware.graphics, Some(true));
+        assert_eq!(hardware.keyboard, Some(false));
+        assert_eq!(hardware.entropy, Some(true));
+        assert_eq!(hardware.memory_balloon, Some(false));
     }
 
     #[tokio::test]
@@ -841,7 +1075,7 @@ mod tests {
         let mut request = create_request(base_rootfs_path, "devbox");
         request.memory = Some(Memory::bytes(1));
 
-        let config = create_machine_config(&runtime, request)
+        let (config, _) = create_machine_config(&runtime, request)
             .await
             .expect("machine should be created");
 
@@ -866,6 +1100,36 @@ mod tests {
         assert!(matches!(err, LibVmError::InvalidCreateRequest { .. }));
     }
 
+    #[tokio::test]
+    async fn dry_run_returns_spec_without_creating_machine_dir() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let paths = LocalPaths::new(temp.path().join("bento"));
+        let machines_dir = paths.machines_dir().to_path_buf();
+        let mut store = MockDataStore::new();
+        expect_empty_refresh(&mut store);
+        store
+            .expect_machine_config_by_name()
+            .withf(|name| name == "devbox")
+            .once()
+            .returning(|_| Ok(None));
+        let runtime = runtime_with_mock_store(paths, store).await;
+        let base_rootfs_path = write_base_rootfs(temp.path());
+        let mut request = create_request(base_rootfs_path, "devbox");
+        request.cpus = Some(2);
+
+        let spec = MachineBuilder { runtime, request }
+            .dry_run()
+            .await
+            .expect("dry run should succeed");
+
+        assert_eq!(spec_hardware(&spec).cpus, Some(2));
+        assert_eq!(
+            spec_kernel(&spec).cmdline,
+            vec![ROOT_DISK_KERNEL_ARG.to_string()]
+        );
+        assert!(!machines_dir.exists());
+    }
+
     #[tokio::test]
     async fn create_machine_config_rejects_zero_root_disk_size() {
         let temp = tempfile::tempdir().expect("tempdir");
@@ -884,6 +1148,49 @@ mod tests {
         assert!(matches!(err, LibVmError::InvalidCreateRequest { .. }));
     }
 
+    #[tokio::test]
+    async fn create_machine_config_rejects_min_memory_above_memory() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let paths = LocalPaths::new(temp.path().join("bento"));
+        let mut store = MockDataStore::new();
+        expect_empty_refresh(&mut store);
+        let runtime = runtime_with_mock_store(paths, store).await;
+        let base_rootfs_path = write_base_rootfs(temp.path());
+        let mut request = create_request(base_rootfs_path, "devbox");
+        request.memory = Some(Memory::gibibytes(1));
+        request.min_memory = Some(Memory::gibibytes(2));
+
+        let err = create_machine_config(&runtime, request)
+            .await
+            .expect_err("min memory above memory should be rejected");
+
+        assert!(matches!(err, LibVmError::InvalidCreateRequest { .. }));
+    }
+
+    #[tokio::test]
+    async fn create_machine_config_rejects_directory_disk() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let paths = LocalPaths::new(temp.path().join("bento"));
+        let mut store = MockDataStore::new();
+        expect_empty_refresh(&mut store);
+        let runtime = runtime_with_mock_store(paths, store).await;
+        let base_rootfs_path = write_base_rootfs(temp.path());
+        let disk_dir = temp.path().join("data-disk");
+        std::fs::create_dir(&disk_dir).expect("create disk dir");
+        let mut request = create_request(base_rootfs_path, "devbox");
+        request.disks = vec![disk_dir];
+
+        let err = create_machine_config(&runtime, request)
+            .await
+            .expect_err("directory disk should be rejected");
+
+        assert!(matches!(
+            err,
+            LibVmError::InvalidCreateRequest { ref name, ref reason }
+                if name == "devbox" && reason.contains("disk is not a regular file")
+        ));
+    }
+
     #[tokio::test]
     async fn create_machine_config_resizes_root_disk_when_requested() {
         let temp = tempfile::tempdir().expect("tempdir");
@@ -900,7 +1207,7 @@ mod tests {
         let mut request = create_request(base_rootfs_path, "devbox");
         request.disk_size_bytes = Some(8);
 
-        let config = create_machine_config(&runtime, request)
+        let (config, _) = create_machine_config(&runtime, request)
             .await
             .expect("machine should be created");
 
@@ -914,6 +1221,91 @@ mod tests {
         assert_eq!(config.root_disk_size, Some(8));
     }
 
+    #[tokio::test]
+    async fn create_machine_config_attaches_read_only_base_with_overlay() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let paths = LocalPaths::new(temp.path().join("bento"));
+        let mut store = MockDataStore::new();
+        expect_empty_refresh(&mut store);
+        store
+            .expect_machine_config_by_name()
+            .once()
+            .returning(|_| Ok(None));
+        store.expect_add_machine().once().returning(|_, _| Ok(()));
+        let runtime = runtime_with_mock_store(paths, store).await;
+        let base_rootfs_path = write_base_rootfs_with_size(temp.path(), 4);
+        let mut request = create_request(base_rootfs_path.clone(), "devbox");
+        let template = temp.path().join("overlay-template.img");
+        std::fs::write(&template, b"ext4").expect("template should be written");
+        request.overlay_template = Some(template);
+        request.disk_size_bytes = Some(8);
+
+        let (config, root_disk) = create_machine_config(&runtime, request)
+            .await
+            .expect("machine should be created");
+
+        let disks = &config
+            .spec
+            .storage
+            .as_ref()
+            .expect("spec should have storage")
+            .disks;
+        assert_eq!(root_disk, RootDiskClone::Overlay { size_bytes: 8 });
+        assert_eq!(
+            disks[0].path,
+            base_rootfs_path.canonicalize().expect("canonical base")
+        );
+        assert!(disks[0].read_only);
+        assert_eq!(disks[1].path, root_disk_relative_path());
+        assert!(!disks[1].read_only);
+        assert_eq!(
+            spec_kernel(&config.spec).cmdline,
+            vec![
+                ROOT_DISK_KERNEL_ARG.to_string(),
+                READ_ONLY_ROOT_KERNEL_ARG.to_string(),
+                OVERLAY_DISK_KERNEL_ARG.to_string()
+            ]
+        );
+        assert_eq!(
+            std::fs::read(config.machine_dir.join(root_disk_relative_path()))
+                .expect("overlay should be readable"),
+            b"ext4\0\0\0\0"
+        );
+        assert_eq!(
+            std::fs::metadata(base_rootfs_path)
+                .expect("base metadata")
+                .len(),
+            4
+        );
+    }
+
+    #[tokio::test]
+    async fn create_machine_config_failure_after_root_disk_clone_removes_machine_dir() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let paths = LocalPaths::new(temp.path().join("bento"));
+        let machines_dir = paths.machines_dir().to_path_buf();
+        let mut store = MockDataStore::new();
+        expect_empty_refresh(&mut store);
+        store
+            .expect_machine_config_by_name()
+            .once()
+            .returning(|_| Ok(None));
+        let runtime = runtime_with_mock_store(paths, store).await;
+        let base_rootfs_path = write_base_rootfs_with_size(temp.path(), 8);
+        let mut request = create_request(base_rootfs_path, "devbox");
+        request.disk_size_bytes = Some(4);
+
+        let err = create_machine_config(&runtime, request)
+            .await
+            .expect_err("shrinking the root disk should fail");
+
+        assert!(matches!(err, LibVmError::RootDisk { .. }));
+        let leftovers = std::fs::read_dir(&machines_dir)
+            .map(|entries| entries.count())
+            .unwrap_or(0);
+        assert_eq!(leftovers, 0);
+    }
+
     #[tokio::test]
     async fn create_guard_and_commit_write_vm_spec_and_state() {
         let temp = tempfile::tempdir().expect("tempdir");
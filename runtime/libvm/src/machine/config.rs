@@ -1,7 +1,16 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
 use vm_spec::VmSpec;
 
+use crate::machine::builder::overlay_base_disk_mut;
+use crate::machine::root_disk::{
+    clone_or_copy_root_disk, create_overlay_disk, resize_raw_disk, RootDiskClone, RootDiskError,
+};
 use crate::machine::{validate_machine_name, Machine, MachineData, MachineUpdate};
 use crate::network::MachineNetworkConfig;
+use crate::paths::{root_disk_path_in, root_disk_rebuild_path_in};
 use crate::runtime::core::{empty_hardware, validate_root_disk_growth, write_machine_config};
 use crate::store::models::MachineNetworkConfig as ModelMachineNetworkConfig;
 use crate::utils::now_unix;
@@ -112,6 +121,21 @@ impl Machine {
             validate_root_disk_growth(&config, size_bytes)?;
             config.root_disk_size = Some(size_bytes);
         }
+        let min_memory = config
+            .spec
+            .hardware
+            .as_ref()
+            .and_then(|hardware| hardware.min_memory);
+        if let (Some(memory), Some(min_memory)) = (update_memory, min_memory) {
+            if memory < min_memory {
+                return Err(LibVmError::InvalidMachineUpdate {
+                    reference: config.name.clone(),
+                    reason: format!(
+                        "memory {memory} MiB is below the minimum memory of {min_memory} MiB"
+                    ),
+                });
+            }
+        }
 
         let previous_spec = config.spec.clone();
         let mut spec_changed = false;
@@ -119,6 +143,10 @@ impl Machine {
             || update_memory.is_some()
             || update.nested_virtualization.is_some()
             || update.rosetta.is_some()
+            || update.graphics.is_some()
+            || update.keyboard.is_some()
+            || update.entropy.is_some()
+            || update.memory_balloon.is_some()
         {
             let hardware = config.spec.hardware.get_or_insert_with(empty_hardware);
             if let Some(cpus) = update.cpus {
@@ -133,6 +161,18 @@ impl Machine {
             if let Some(rosetta) = update.rosetta {
                 hardware.rosetta = Some(rosetta);
             }
+            if let Some(graphics) = update.graphics {
+                hardware.graphics = Some(graphics);
+            }
+            if let Some(keyboard) = update.keyboard {
+                hardware.keyboard = Some(keyboard);
+            }
+            if let Some(entropy) = update.entropy {
+                hardware.entropy = Some(entropy);
+            }
+            if let Some(memory_balloon) = update.memory_balloon {
+                hardware.memory_balloon = Some(memory_balloon);
+            }
             spec_changed = true;
         }
         if let Some(network) = network {
@@ -151,4 +191,70 @@ impl Machine {
         }
         runtime.machine_inspect_data(config).await
     }
+
+    /// Replaces the root disk of a stopped machine with a fresh copy of
+    /// `base_rootfs_path`, keeping its name, ID and config.
+    ///
+    /// Everything written to the old root disk is lost. Overlay machines get
+    /// a fresh clone of `overlay_template` on top of the new base, and fail
+    /// without one. `metadata` is merged into the machine record so callers
+    /// can note which image the disk came from.
+    pub async fn rebuild_root_disk(
+        &self,
+        base_rootfs_path: impl AsRef<Path>,
+        overlay_template: Option<&Path>,
+        metadata: BTreeMap<String, String>,
+    ) -> Result<(MachineData, RootDiskClone), LibVmError> {
+        let runtime = self.runtime();
+        let (_lock, mut config) = runtime.lock_machine_config(self.machine_id()).await?;
+        let status = runtime.reconcile_machine_runtime_locked(&config).await?;
+        if status.is_active() {
+            return Err(LibVmError::MachineAlreadyRunning {
+                reference: config.name.clone(),
+            });
+        }
+
+        let base_rootfs_path = fs::canonicalize(base_rootfs_path.as_ref())?;
+        let base_size = fs::metadata(&base_rootfs_path)?.len();
+        let root_disk_size = config.root_disk_size.unwrap_or(0).max(base_size);
+        let rootfs_path = root_disk_path_in(&config.machine_dir);
+        let rebuild_path = root_disk_rebuild_path_in(&config.machine_dir);
+        match fs::remove_file(&rebuild_path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let previous_spec = config.spec.clone();
+        let root_disk = match overlay_base_disk_mut(&mut config.spec) {
+            Some(base_disk) => {
+                let template = overlay_template.ok_or(RootDiskError::OverlayTemplateMissing)?;
+                base_disk.path = base_rootfs_path.clone();
+                create_overlay_disk(&base_rootfs_path, template, &rebuild_path, root_disk_size)?
+            }
+            None => {
+                let root_disk = clone_or_copy_root_disk(&base_rootfs_path, &rebuild_path, false)?;
+                if root_disk_size > base_size {
+                    resize_raw_disk(&rebuild_path, root_disk_size)?;
+                }
+                root_disk
+            }
+        };
+        fs::rename(&rebuild_path, &rootfs_path)?;
+
+        config.root_disk_size = Some(root_disk_size);
+        config.metadata.extend(metadata);
+        config.modified_at = now_unix();
+        let spec_changed = config.spec != previous_spec;
+        if spec_changed {
+            write_machine_config(&config.machine_dir, &config.name, &config.spec)?;
+        }
+        if let Err(err) = runtime.save_machine_config(&config).await {
+            if spec_changed {
+                let _ = write_machine_config(&config.machine_dir, &config.name, &previous_spec);
+            }
+            return Err(err);
+        }
+        Ok((runtime.machine_inspect_data(config).await?, root_disk))
+    }
 }
@@ -1,10 +1,14 @@
 use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use protocol::v1::{InspectResponse, LifecycleState};
+use protocol::v1::{
+    InspectResponse, LifecycleState, MetricsResponse, ServiceDescriptor, ServiceKind,
+};
 use vm_spec::VmSpec;
 
-use crate::network::MachineNetworkConfig;
+use crate::machine::builder::{has_overlay_root_disk, overlay_base_disk_index};
+use crate::network::{guest_mac_address, MachineNetworkConfig};
 use crate::store::models::{MachineConfig, MachineRuntimeState};
 
 /// Public machine snapshot returned by inspect and mutation operations.
@@ -41,6 +45,12 @@ pub struct MachineData {
     pub metadata: BTreeMap<String, String>,
     /// Desired network attachment recorded for the machine.
     pub network: MachineNetworkConfig,
+    /// MAC address of the guest network device, or `None` without networking.
+    pub guest_mac: Option<String>,
+    /// Addresses the guest agent reported when it registered.
+    ///
+    /// Empty while the machine is stopped or when the guest never reported.
+    pub guest_addresses: Vec<String>,
     /// Reconciled lifecycle status for the machine.
     ///
     /// `Machine::inspect` always reconciles persisted state with the local vmmon
@@ -51,12 +61,151 @@ pub struct MachineData {
     pub status: MachineStatus,
     /// Unix timestamp for when the machine last started.
     pub started_at: Option<i64>,
+    /// Process ID of the machine's vmmon, while it runs.
+    pub vmmon_pid: Option<i32>,
     /// Last persisted runtime error, when present.
     pub last_error: Option<String>,
     /// Unix timestamp for the last runtime state change.
     pub updated_at: i64,
 }
 
+/// Resource usage reported by a running machine's monitor.
+///
+/// Fields are `None` when the VM backend cannot observe them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MachineMetrics {
+    /// Memory assigned to the guest at boot.
+    pub memory_mib: u64,
+    /// Memory the balloon device currently asks the guest to keep.
+    pub balloon_target_mib: Option<u64>,
+    /// Host CPU time consumed by the process running the guest.
+    pub cpu_time: Option<Duration>,
+}
+
+impl From<MetricsResponse> for MachineMetrics {
+    fn from(response: MetricsResponse) -> Self {
+        Self {
+            memory_mib: response.memory_mib,
+            balloon_target_mib: response.balloon_target_mib,
+            cpu_time: response.cpu_time_ms.map(Duration::from_millis),
+        }
+    }
+}
+
+/// A disk attached to a machine, as declared in its VM spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MachineDisk {
+    /// Whether the disk holds the root filesystem or extra data.
+    pub role: MachineDiskRole,
+    /// Host path of the disk image, resolved against the machine directory.
+    pub path: PathBuf,
+    /// Whether the disk is attached read-only.
+    pub read_only: bool,
+    /// Apparent size of the disk image, or `None` when it cannot be read.
+    pub size_bytes: Option<u64>,
+}
+
+/// Role of a machine disk.
+///
+/// The first disk in the VM spec is the root disk, except on overlay machines
+/// where the read-only base rootfs comes first and the writable root disk
+/// follows it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MachineDiskRole {
+    /// The read-only base rootfs under an overlay root disk.
+    Base,
+    /// The disk the guest boots from.
+    Root,
+    /// An additional disk.
+    Data,
+}
+
+impl MachineDiskRole {
+    /// Returns a short lowercase label for display.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Base => "base",
+            Self::Root => "root",
+            Self::Data => "data",
+        }
+    }
+}
+
+/// A service reachable through a running machine's control socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MachineService {
+    /// Endpoint name, such as `shell` or `serial`.
+    pub name: String,
+    /// Whether the monitor would accept a connection to it now.
+    pub available: bool,
+    /// Why the service is unavailable.
+    pub message: Option<String>,
+    /// How the service is reached.
+    pub kind: MachineServiceKind,
+}
+
+/// Transport behind a machine service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MachineServiceKind {
+    /// The monitor's own gRPC API.
+    Api,
+    /// The guest serial console.
+    Serial,
+    /// A guest vsock port forwarded by the monitor.
+    Vsock { port: Option<u32> },
+    /// Reported by a monitor that predates service kinds.
+    Unknown,
+}
+
+impl MachineServiceKind {
+    /// Returns a short lowercase label for display.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Api => "api",
+            Self::Serial => "serial",
+            Self::Vsock { .. } => "vsock",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl From<ServiceDescriptor> for MachineService {
+    fn from(descriptor: ServiceDescriptor) -> Self {
+        let kind = match descriptor.kind() {
+            ServiceKind::Api => MachineServiceKind::Api,
+            ServiceKind::Serial => MachineServiceKind::Serial,
+            ServiceKind::Vsock => MachineServiceKind::Vsock {
+                port: descriptor.vsock_port,
+            },
+            ServiceKind::Unspecified => MachineServiceKind::Unknown,
+        };
+        Self {
+            name: descriptor.name,
+            available: descriptor.available,
+            message: (!descriptor.message.is_empty()).then_some(descriptor.message),
+            kind,
+        }
+    }
+}
+
+/// A stored machine whose configuration can no longer be read.
+///
+/// Broken machines are left out of `Runtime::list_machines` and reported by
+/// `Runtime::list_broken_machines` so callers can surface them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BrokenMachine {
+    /// Stable machine ID.
+    pub id: String,
+    /// Human-readable machine name.
+    pub name: String,
+    /// Why the stored configuration could not be decoded.
+    pub reason: String,
+}
+
 impl MachineData {
     pub(crate) fn from_models_with_status(
         config: MachineConfig,
@@ -65,6 +214,7 @@ impl MachineData {
         last_error: Option<String>,
         updated_at: i64,
     ) -> Self {
+        let guest_mac = guest_mac_address(&config);
         Self {
             id: config.id.to_string(),
             name: config.name,
@@ -77,8 +227,11 @@ impl MachineData {
             labels: config.labels,
             metadata: config.metadata,
             network: config.network.into(),
+            guest_mac,
+            guest_addresses: Vec::new(),
             status,
             started_at,
+            vmmon_pid: None,
             last_error,
             updated_at,
         }
@@ -89,6 +242,49 @@ impl MachineData {
         self.status.is_running()
     }
 
+    /// Returns true when the machine boots from a read-only base rootfs with an
+    /// overlay root disk.
+    pub fn has_overlay_root_disk(&self) -> bool {
+        has_overlay_root_disk(&self.spec)
+    }
+
+    /// Returns the spec disks in attach order.
+    ///
+    /// Sizes are read from the disk images on the host when this is called.
+    pub fn disks(&self) -> Vec<MachineDisk> {
+        let disks = self
+            .spec
+            .storage
+            .as_ref()
+            .map(|storage| storage.disks.as_slice())
+            .unwrap_or_default();
+        let base = overlay_base_disk_index(disks);
+        let root = base.map_or(0, |base| base + 1);
+        disks
+            .iter()
+            .enumerate()
+            .map(|(index, disk)| {
+                let path = if disk.path.is_absolute() {
+                    disk.path.clone()
+                } else {
+                    self.machine_dir.join(&disk.path)
+                };
+                MachineDisk {
+                    role: if Some(index) == base {
+                        MachineDiskRole::Base
+                    } else if index == root {
+                        MachineDiskRole::Root
+                    } else {
+                        MachineDiskRole::Data
+                    },
+                    size_bytes: std::fs::metadata(&path).ok().map(|metadata| metadata.len()),
+                    path,
+                    read_only: disk.read_only,
+                }
+            })
+            .collect()
+    }
+
     /// Returns the runtime trace log path for this machine.
     pub fn trace_log_path(&self) -> PathBuf {
         crate::paths::vmmon_trace_log_path_in(&self.machine_dir)
@@ -118,6 +314,11 @@ pub enum MachineStatus {
         /// Optional human-readable status detail.
         message: Option<String>,
     },
+    /// The guest is paused and its vCPUs are not running.
+    Paused {
+        /// Optional human-readable status detail.
+        message: Option<String>,
+    },
     /// vmmon is stopping.
     Stopping {
         /// Optional human-readable status detail.
@@ -150,7 +351,6 @@ impl MachineStatus {
     }
 
     pub(crate) fn from_protocol(response: InspectResponse) -> Self {
-        let message = non_empty_message(Some(response.summary));
         let guest_ready = response.ready
             && matches!(
                 LifecycleState::try_from(response.guest_state)
@@ -158,13 +358,27 @@ impl MachineStatus {
                 LifecycleState::Running
             );
 
-        match LifecycleState::try_from(response.vm_state).unwrap_or(LifecycleState::Unspecified) {
+        Self::from_lifecycle(
+            LifecycleState::try_from(response.vm_state).unwrap_or(LifecycleState::Unspecified),
+            guest_ready,
+            Some(response.summary),
+        )
+    }
+
+    pub(crate) fn from_lifecycle(
+        state: LifecycleState,
+        guest_ready: bool,
+        message: Option<String>,
+    ) -> Self {
+        let message = non_empty_message(message);
+        match state {
             LifecycleState::Stopped => Self::Stopped,
             LifecycleState::Starting => Self::Starting { message },
             LifecycleState::Running | LifecycleState::Unspecified => Self::Running {
                 guest_ready,
                 message,
             },
+            LifecycleState::Paused => Self::Paused { message },
             LifecycleState::Stopping => Self::Stopping { message },
             LifecycleState::Error => Self::Error { message },
         }
@@ -204,6 +418,7 @@ impl MachineStatus {
             Self::Stopped => "stopped",
             Self::Starting { .. } => "starting",
             Self::Running { .. } => "running",
+            Self::Paused { .. } => "paused",
             Self::Stopping { .. } => "stopping",
             Self::Error { .. } => "error",
         }
@@ -215,6 +430,7 @@ impl MachineStatus {
             Self::Stopped => None,
             Self::Starting { message }
             | Self::Running { message, .. }
+            | Self::Paused { message }
             | Self::Stopping { message }
             | Self::Error { message } => message.as_deref(),
         }
@@ -232,7 +448,7 @@ impl From<MachineStatus> for MachineRuntimeState {
         match value {
             MachineStatus::Stopped => Self::Stopped,
             MachineStatus::Starting { .. } => Self::Starting,
-            MachineStatus::Running { .. } => Self::Running,
+            MachineStatus::Running { .. } | MachineStatus::Paused { .. } => Self::Running,
             MachineStatus::Stopping { .. } => Self::Stopping,
             MachineStatus::Error { .. } => Self::Error,
         }
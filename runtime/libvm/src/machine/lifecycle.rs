@@ -5,8 +5,8 @@ use tokio::time::{sleep, Instant};
 use uuid::Uuid;
 
 use crate::machine::{
-    Machine, MachineData, MachineExit, MachineExitOutcome, MachineKillOptions, MachineStartOptions,
-    MachineStopOptions, MachineWaitOptions,
+    Machine, MachineData, MachineExit, MachineExitOutcome, MachineKillOptions, MachineMetrics,
+    MachineService, MachineStartOptions, MachineStopOptions, MachineWaitOptions, Memory,
 };
 use crate::runtime::core::{
     interrupt_monitor, kill_monitor_process_group, monitor_started_at, pid_file_mtime,
@@ -63,6 +63,8 @@ impl Machine {
 
             reconcile_root_disk_size(&config)?;
             runtime.remove_vmmon_exit_status(&config)?;
+            runtime.remove_stale_vmmon_files(&config)?;
+            runtime.prepare_vmmon_run_dir(&config)?;
             let run_id = Uuid::new_v4().to_string();
 
             let resolved_network = runtime.prepare_machine_network(&config).await?;
@@ -84,7 +86,10 @@ impl Machine {
                 metadata_config: &metadata_config_path,
                 run_id: &run_id,
                 exit_command: options.exit_command.as_ref(),
-                wait_for_registration: crate::vmmon::DEFAULT_GUEST_READINESS_TIMEOUT,
+                wait_for_registration: options
+                    .boot_timeout
+                    .unwrap_or(crate::vmmon::DEFAULT_GUEST_READINESS_TIMEOUT),
+                idle_timeout: options.idle_timeout,
             };
             if let Err(err) = vmmon.spawn(&launch).await {
                 runtime
@@ -166,32 +171,26 @@ impl Machine {
                         forced: false,
                     }
                 }
-                Some(pid) => {
-                    if interrupt_monitor(pid)? {
-                        let generation = VmmonRunIdentity {
-                            pid,
-                            started_at: Some(
-                                status
-                                    .started_at
-                                    .unwrap_or_else(|| pid_file_mtime(&pid_path)),
-                            ),
-                            run_id: status.run_id.clone(),
-                        };
-                        runtime.request_machine_stop(config.id, &generation).await?;
-
-                        WaitTarget {
-                            config,
-                            generation,
-                            stop_requested: true,
-                            forced: false,
-                        }
-                    } else {
-                        runtime.mark_machine_stopped(config.id, None).await?;
-                        runtime.cleanup_machine_resources_locked(&config).await?;
-                        return runtime.machine_inspect_data(config).await;
+                Some(pid) if interrupt_monitor(pid)? => {
+                    let generation = VmmonRunIdentity {
+                        pid,
+                        started_at: Some(
+                            status
+                                .started_at
+                                .unwrap_or_else(|| pid_file_mtime(&pid_path)),
+                        ),
+                        run_id: status.run_id.clone(),
+                    };
+                    runtime.request_machine_stop(config.id, &generation).await?;
+
+                    WaitTarget {
+                        config,
+                        generation,
+                        stop_requested: true,
+                        forced: false,
                     }
                 }
-                None => {
+                Some(_) | None => {
                     runtime.mark_machine_stopped(config.id, None).await?;
                     runtime.cleanup_machine_resources_locked(&config).await?;
                     return runtime.machine_inspect_data(config).await;
@@ -204,6 +203,95 @@ impl Machine {
             .map(|exit| exit.machine)
     }
 
+    /// Pauses the guest vCPUs and returns the updated inspect data.
+    ///
+    /// Pausing a machine that is already paused is a no-op.
+    pub async fn pause(&self) -> Result<MachineData, LibVmError> {
+        let config = self.running_config().await?;
+        self.runtime()
+            .vmmon()
+            .client(self.machine_id())
+            .pause()
+            .await
+            .map_err(|message| LibVmError::MonitorProtocol {
+                reference: config.name.clone(),
+                message,
+            })?;
+        self.runtime().machine_inspect_data(config).await
+    }
+
+    /// Resumes a paused machine and returns the updated inspect data.
+    ///
+    /// Resuming a machine that is already running is a no-op.
+    pub async fn resume(&self) -> Result<MachineData, LibVmError> {
+        let config = self.running_config().await?;
+        self.runtime()
+            .vmmon()
+            .client(self.machine_id())
+            .resume()
+            .await
+            .map_err(|message| LibVmError::MonitorProtocol {
+                reference: config.name.clone(),
+                message,
+            })?;
+        self.runtime().machine_inspect_data(config).await
+    }
+
+    /// Reads current resource usage from the machine's monitor.
+    pub async fn metrics(&self) -> Result<MachineMetrics, LibVmError> {
+        let config = self.running_config().await?;
+        self.runtime()
+            .vmmon()
+            .client(self.machine_id())
+            .metrics()
+            .await
+            .map(MachineMetrics::from)
+            .map_err(|message| LibVmError::MonitorProtocol {
+                reference: config.name.clone(),
+                message,
+            })
+    }
+
+    /// Moves the memory balloon target of a running machine.
+    ///
+    /// The monitor clamps `target` to the machine's minimum memory and memory size and
+    /// returns the target it applied.
+    pub async fn set_balloon_target(&self, target: Memory) -> Result<Memory, LibVmError> {
+        let config = self.running_config().await?;
+        let target_mib = target.to_update_mebibytes(&config.name)?;
+        self.runtime()
+            .vmmon()
+            .client(self.machine_id())
+            .set_balloon_target(u64::from(target_mib))
+            .await
+            .map(Memory::mebibytes)
+            .map_err(|message| LibVmError::MonitorProtocol {
+                reference: config.name.clone(),
+                message,
+            })
+    }
+
+    /// Lists the services the machine's monitor serves on its control socket.
+    pub async fn services(&self) -> Result<Vec<MachineService>, LibVmError> {
+        let config = self.running_config().await?;
+        self.runtime()
+            .vmmon()
+            .client(self.machine_id())
+            .list_services()
+            .await
+            .map(|response| {
+                response
+                    .services
+                    .into_iter()
+                    .map(MachineService::from)
+                    .collect()
+            })
+            .map_err(|message| LibVmError::MonitorProtocol {
+                reference: config.name.clone(),
+                message,
+            })
+    }
+
     /// Waits for the current machine run to exit without sending a stop signal.
     pub async fn wait(&self) -> Result<MachineExit, LibVmError> {
         self.wait_with(MachineWaitOptions::default()).await
@@ -353,6 +441,7 @@ impl Machine {
             Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
             Err(err) => return Err(err.into()),
         }
+        runtime.remove_machine_run_dir(&config)?;
 
         runtime.remove_machine_records(&config).await
     }
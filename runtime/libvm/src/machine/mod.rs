@@ -10,12 +10,16 @@ mod name_generator;
 mod reference;
 pub(crate) mod root_disk;
 mod start;
+mod status_watch;
 mod streams;
 mod update;
 
 pub use builder::MachineBuilder;
 pub use handle::Machine;
-pub use inspect::{MachineData, MachineStatus};
+pub use inspect::{
+    BrokenMachine, MachineData, MachineDisk, MachineDiskRole, MachineMetrics, MachineService,
+    MachineServiceKind, MachineStatus,
+};
 pub use lifecycle_options::{
     MachineExit, MachineExitOutcome, MachineKillOptions, MachineStopOptions, MachineWaitOptions,
     DEFAULT_MACHINE_WAIT_TIMEOUT,
@@ -23,7 +27,9 @@ pub use lifecycle_options::{
 pub use memory::Memory;
 pub use mounts::resolve_mount_location;
 pub use reference::MachineRef;
+pub use root_disk::RootDiskClone;
 pub use start::{MachineExitCommand, MachineStartOptions};
+pub use status_watch::{MachineStatusSource, MachineStatusUpdate, MachineStatusWatch};
 pub use update::MachineUpdate;
 
 pub(crate) use name_generator::generate_machine_name;
@@ -5,13 +5,16 @@ use std::path::{Path, PathBuf};
 use thiserror::Error;
 use utils::format_storage_size;
 
+/// How the base rootfs became a machine's root disk.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum CloneDiskMethod {
-    #[cfg(target_os = "macos")]
-    Clonefile,
-    #[cfg(target_os = "linux")]
-    Reflink,
-    Copy,
+pub enum RootDiskClone {
+    /// The root disk shares blocks with the base rootfs through clonefile or a reflink.
+    CopyOnWrite,
+    /// The base rootfs was copied in full.
+    Copied { size_bytes: u64 },
+    /// Nothing was copied: the base rootfs is attached read-only next to an empty
+    /// overlay disk of `size_bytes`.
+    Overlay { size_bytes: u64 },
 }
 
 #[derive(Debug, Error)]
@@ -33,35 +36,85 @@ pub(crate) enum RootDiskError {
         requested_size: u64,
     },
 
+    #[error("overlay template {path} is not a regular file")]
+    OverlayTemplateNotFile { path: PathBuf },
+
+    #[error("an overlay root disk needs an overlay template disk")]
+    OverlayTemplateMissing,
+
     #[error("I/O failure")]
     Io(#[from] io::Error),
 }
 
+/// Clones `source` into `destination`, falling back to a full copy when the
+/// filesystem cannot share blocks. `force_copy` skips the clone attempt.
 pub(crate) fn clone_or_copy_root_disk(
     source: &Path,
     destination: &Path,
-) -> Result<CloneDiskMethod, RootDiskError> {
+    force_copy: bool,
+) -> Result<RootDiskClone, RootDiskError> {
     validate_base_rootfs(source)?;
+    clone_or_copy(source, destination, force_copy)
+}
+
+fn clone_or_copy(
+    source: &Path,
+    destination: &Path,
+    force_copy: bool,
+) -> Result<RootDiskClone, RootDiskError> {
     if let Some(parent) = destination.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    #[cfg(target_os = "macos")]
-    {
-        if try_clonefile(source, destination).is_ok() {
-            return Ok(CloneDiskMethod::Clonefile);
+    if !force_copy {
+        #[cfg(target_os = "macos")]
+        match try_clonefile(source, destination) {
+            Ok(()) => {
+                tracing::debug!(source = %source.display(), destination = %destination.display(), "cloned root disk with clonefile");
+                return Ok(RootDiskClone::CopyOnWrite);
+            }
+            Err(err) => {
+                tracing::debug!(source = %source.display(), error = %err, "clonefile failed, copying root disk");
+            }
         }
-    }
 
-    #[cfg(target_os = "linux")]
-    {
-        if try_reflink(source, destination).is_ok() {
-            return Ok(CloneDiskMethod::Reflink);
+        #[cfg(target_os = "linux")]
+        match try_reflink(source, destination) {
+            Ok(()) => {
+                tracing::debug!(source = %source.display(), destination = %destination.display(), "cloned root disk with reflink");
+                return Ok(RootDiskClone::CopyOnWrite);
+            }
+            Err(err) => {
+                tracing::debug!(source = %source.display(), error = %err, "reflink failed, copying root disk");
+            }
         }
     }
 
-    fs::copy(source, destination)?;
-    Ok(CloneDiskMethod::Copy)
+    let size_bytes = fs::copy(source, destination)?;
+    tracing::debug!(source = %source.display(), destination = %destination.display(), size_bytes, force_copy, "copied root disk");
+    Ok(RootDiskClone::Copied { size_bytes })
+}
+
+/// Clones the empty filesystem in `template` into `destination` and grows it to
+/// at least `size_bytes` for the guest init to layer over the read-only base
+/// rootfs `source`. The guest agent grows the filesystem to the disk on boot.
+pub(crate) fn create_overlay_disk(
+    source: &Path,
+    template: &Path,
+    destination: &Path,
+    size_bytes: u64,
+) -> Result<RootDiskClone, RootDiskError> {
+    validate_base_rootfs(source)?;
+    if !fs::metadata(template)?.is_file() {
+        return Err(RootDiskError::OverlayTemplateNotFile {
+            path: template.to_path_buf(),
+        });
+    }
+    clone_or_copy(template, destination, false)?;
+    let size_bytes = size_bytes.max(fs::metadata(destination)?.len());
+    resize_raw_disk(destination, size_bytes)?;
+    tracing::debug!(source = %source.display(), destination = %destination.display(), size_bytes, "created root disk overlay");
+    Ok(RootDiskClone::Overlay { size_bytes })
 }
 
 pub(crate) fn resize_raw_disk(path: &Path, size_bytes: u64) -> Result<(), RootDiskError> {
@@ -144,7 +197,9 @@ fn try_reflink(source: &Path, destination: &Path) -> io::Result<()> {
 mod tests {
     use std::fs;
 
-    use crate::machine::root_disk::{clone_or_copy_root_disk, resize_raw_disk, RootDiskError};
+    use crate::machine::root_disk::{
+        clone_or_copy_root_disk, create_overlay_disk, resize_raw_disk, RootDiskClone, RootDiskError,
+    };
 
     #[test]
     fn clone_or_copy_root_disk_copies_contents() {
@@ -153,17 +208,53 @@ mod tests {
         let destination = temp.path().join("instance/rootfs.img");
         fs::write(&source, b"disk").expect("write source");
 
-        clone_or_copy_root_disk(&source, &destination).expect("clone or copy root disk");
+        clone_or_copy_root_disk(&source, &destination, false).expect("clone or copy root disk");
+
+        assert_eq!(fs::read(destination).expect("read destination"), b"disk");
+    }
+
+    #[test]
+    fn clone_or_copy_root_disk_force_copy_reports_copied_size() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let source = temp.path().join("base.ext4");
+        let destination = temp.path().join("instance/rootfs.img");
+        fs::write(&source, b"disk").expect("write source");
+
+        let clone = clone_or_copy_root_disk(&source, &destination, true).expect("copy root disk");
 
+        assert_eq!(clone, RootDiskClone::Copied { size_bytes: 4 });
         assert_eq!(fs::read(destination).expect("read destination"), b"disk");
     }
 
+    #[test]
+    fn create_overlay_disk_leaves_base_untouched() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let source = temp.path().join("base.ext4");
+        let destination = temp.path().join("instance/rootfs.img");
+        fs::write(&source, b"disk").expect("write source");
+
+        let template = temp.path().join("overlay-template.img");
+        fs::write(&template, b"ext4").expect("write template");
+
+        let clone =
+            create_overlay_disk(&source, &template, &destination, 1024).expect("create overlay");
+
+        assert_eq!(clone, RootDiskClone::Overlay { size_bytes: 1024 });
+        let overlay = fs::read(destination).expect("read overlay");
+        assert_eq!(overlay.len(), 1024);
+        assert!(overlay.starts_with(b"ext4"));
+        assert!(overlay[4..].iter().all(|byte| *byte == 0));
+        assert_eq!(fs::read(template).expect("read template"), b"ext4");
+        assert_eq!(fs::read(source).expect("read source"), b"disk");
+    }
+
     #[test]
     fn clone_or_copy_root_disk_rejects_missing_source() {
         let temp = tempfile::tempdir().expect("create temp dir");
         let err = clone_or_copy_root_disk(
             &temp.path().join("missing.ext4"),
             &temp.path().join("rootfs.img"),
+            false,
         )
         .expect_err("missing source should fail");
 
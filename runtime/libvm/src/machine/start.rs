@@ -1,5 +1,6 @@
 use std::ffi::OsString;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// Optional settings for starting a machine.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -10,6 +11,14 @@ pub struct MachineStartOptions {
     /// When unset, no exit command is registered. The command is passed as
     /// structured argv and is never interpreted by a shell.
     pub exit_command: Option<MachineExitCommand>,
+    /// How long vmmon waits for the guest to register before giving up.
+    ///
+    /// When unset, `DEFAULT_GUEST_READINESS_TIMEOUT` is used.
+    pub boot_timeout: Option<Duration>,
+    /// How long vmmon keeps the machine running with no control socket clients.
+    ///
+    /// When unset, the machine runs until it is stopped.
+    pub idle_timeout: Option<Duration>,
 }
 
 /// Structured command to run after the machine runtime exits.
@@ -47,4 +56,16 @@ impl MachineStartOptions {
         self.exit_command = Some(exit_command);
         self
     }
+
+    /// Sets how long vmmon waits for the guest to register after boot.
+    pub fn boot_timeout(mut self, timeout: Duration) -> Self {
+        self.boot_timeout = Some(timeout);
+        self
+    }
+
+    /// Stops the machine once no client has been connected for `timeout`.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
 }
@@ -0,0 +1,127 @@
+use protocol::v1::{LifecycleState, StatusSource, StatusUpdate};
+use tonic::Streaming;
+
+use crate::machine::{Machine, MachineStatus};
+use crate::LibVmError;
+
+/// Component a status update describes.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MachineStatusSource {
+    /// The virtual machine run by vmmon.
+    Vm,
+    /// The guest agent inside the machine.
+    Guest,
+}
+
+/// Lifecycle transition reported by a running machine's vmmon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MachineStatusUpdate {
+    /// Component that changed state.
+    pub source: MachineStatusSource,
+    /// New state of that component.
+    pub status: MachineStatus,
+    /// Unix timestamp in milliseconds for when vmmon recorded the transition.
+    pub timestamp_unix_ms: i64,
+}
+
+impl MachineStatusUpdate {
+    fn from_protocol(update: StatusUpdate) -> Self {
+        let source = match StatusSource::try_from(update.source) {
+            Ok(StatusSource::Guest) => MachineStatusSource::Guest,
+            _ => MachineStatusSource::Vm,
+        };
+        let state = LifecycleState::try_from(update.state).unwrap_or(LifecycleState::Unspecified);
+        let guest_ready = source == MachineStatusSource::Guest && state == LifecycleState::Running;
+        Self {
+            source,
+            status: MachineStatus::from_lifecycle(state, guest_ready, Some(update.message)),
+            timestamp_unix_ms: update.timestamp_unix_ms,
+        }
+    }
+}
+
+/// Live stream of lifecycle transitions from a running machine.
+///
+/// The first updates describe the current state. A subscriber that falls
+/// behind is sent the latest state instead of every missed transition.
+pub struct MachineStatusWatch {
+    reference: String,
+    updates: Streaming<StatusUpdate>,
+}
+
+impl MachineStatusWatch {
+    /// Waits for the next update, or returns `None` once vmmon closes the stream.
+    pub async fn next(&mut self) -> Result<Option<MachineStatusUpdate>, LibVmError> {
+        let update = self
+            .updates
+            .message()
+            .await
+            .map_err(|err| LibVmError::MonitorProtocol {
+                reference: self.reference.clone(),
+                message: format!("watch_status stream failed: {err}"),
+            })?;
+        Ok(update.map(MachineStatusUpdate::from_protocol))
+    }
+}
+
+impl Machine {
+    /// Subscribes to lifecycle transitions of the running machine.
+    pub async fn watch_status(&self) -> Result<MachineStatusWatch, LibVmError> {
+        let config = self.running_config().await?;
+        let updates = self
+            .runtime()
+            .vmmon()
+            .client(self.machine_id())
+            .watch_status()
+            .await
+            .map_err(|message| LibVmError::MonitorProtocol {
+                reference: config.name.clone(),
+                message,
+            })?;
+        Ok(MachineStatusWatch {
+            reference: config.name,
+            updates,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protocol::v1::{LifecycleState, StatusSource, StatusUpdate};
+
+    use crate::machine::status_watch::{MachineStatusSource, MachineStatusUpdate};
+    use crate::machine::MachineStatus;
+
+    #[test]
+    fn guest_running_update_marks_guest_ready() {
+        let update = MachineStatusUpdate::from_protocol(StatusUpdate {
+            source: StatusSource::Guest as i32,
+            state: LifecycleState::Running as i32,
+            message: String::new(),
+            timestamp_unix_ms: 42,
+        });
+
+        assert_eq!(update.source, MachineStatusSource::Guest);
+        assert!(update.status.guest_ready());
+        assert_eq!(update.timestamp_unix_ms, 42);
+    }
+
+    #[test]
+    fn vm_stopping_update_keeps_message() {
+        let update = MachineStatusUpdate::from_protocol(StatusUpdate {
+            source: StatusSource::Vm as i32,
+            state: LifecycleState::Stopping as i32,
+            message: "shutdown requested".to_string(),
+            timestamp_unix_ms: 0,
+        });
+
+        assert_eq!(update.source, MachineStatusSource::Vm);
+        assert_eq!(
+            update.status,
+            MachineStatus::Stopping {
+                message: Some("shutdown requested".to_string())
+            }
+        );
+    }
+}
@@ -35,6 +35,21 @@ impl Machine {
             })
     }
 
+    /// Checks that the machine's vmmon still answers, failing when no reply
+    /// arrives within `timeout`.
+    pub async fn ping(&self, timeout: Duration) -> Result<(), LibVmError> {
+        let config = self.running_config().await?;
+        self.runtime()
+            .vmmon()
+            .client(self.machine_id())
+            .ping(timeout)
+            .await
+            .map_err(|message| LibVmError::MonitorProtocol {
+                reference: config.name,
+                message,
+            })
+    }
+
     /// Opens the machine serial stream.
     pub async fn open_serial_stream(&self) -> Result<tokio::net::UnixStream, LibVmError> {
         let config = self.running_config().await?;
@@ -82,7 +97,7 @@ impl Machine {
             })
     }
 
-    async fn running_config(&self) -> Result<MachineConfig, LibVmError> {
+    pub(crate) async fn running_config(&self) -> Result<MachineConfig, LibVmError> {
         let runtime = self.runtime();
         let machine_id = self.machine_id();
         let config = runtime
@@ -30,6 +30,14 @@ pub struct MachineUpdate {
     pub nested_virtualization: Option<bool>,
     /// New Rosetta setting.
     pub rosetta: Option<bool>,
+    /// New graphics and pointing device setting.
+    pub graphics: Option<bool>,
+    /// New USB keyboard setting.
+    pub keyboard: Option<bool>,
+    /// New entropy device setting.
+    pub entropy: Option<bool>,
+    /// New memory balloon device setting.
+    pub memory_balloon: Option<bool>,
     /// New durable network config.
     pub network: Option<MachineNetworkConfig>,
 }
@@ -76,6 +84,30 @@ impl MachineUpdate {
         self
     }
 
+    /// Sets whether the graphics and pointing devices are attached.
+    pub fn graphics(mut self, graphics: bool) -> Self {
+        self.graphics = Some(graphics);
+        self
+    }
+
+    /// Sets whether the USB keyboard is attached.
+    pub fn keyboard(mut self, keyboard: bool) -> Self {
+        self.keyboard = Some(keyboard);
+        self
+    }
+
+    /// Sets whether the entropy device is attached.
+    pub fn entropy(mut self, entropy: bool) -> Self {
+        self.entropy = Some(entropy);
+        self
+    }
+
+    /// Sets whether the memory balloon device is attached.
+    pub fn memory_balloon(mut self, memory_balloon: bool) -> Self {
+        self.memory_balloon = Some(memory_balloon);
+        self
+    }
+
     /// Sets the durable machine network config.
     pub fn network(mut self, network: MachineNetworkConfig) -> Self {
         self.network = Some(network);
@@ -90,6 +122,10 @@ impl MachineUpdate {
             && self.root_disk_size.is_none()
             && self.nested_virtualization.is_none()
             && self.rosetta.is_none()
+            && self.graphics.is_none()
+            && self.keyboard.is_none()
+            && self.entropy.is_none()
+            && self.memory_balloon.is_none()
             && self.network.is_none()
     }
 }
@@ -260,6 +260,19 @@ pub(super) async fn remove_attached_network(
     Ok(())
 }
 
+/// Returns the MAC address a machine's network device is given, if it has one.
+///
+/// Every driver derives the MAC from the machine ID, so it is stable across
+/// restarts and can be used to find the guest's DHCP lease.
+pub(crate) fn guest_mac_address(metadata: &MachineConfig) -> Option<String> {
+    match metadata.network {
+        ModelMachineNetworkConfig::None => None,
+        ModelMachineNetworkConfig::Private { .. } | ModelMachineNetworkConfig::Named { .. } => {
+            Some(utils::format_mac(mac_from_machine_id(metadata.id)))
+        }
+    }
+}
+
 pub(crate) fn mac_from_machine_id(machine_id: MachineId) -> [u8; 6] {
     let id = machine_id.to_string();
     let bytes = id.as_bytes();
@@ -1,9 +1,11 @@
 use std::fs;
 
+use utils::format_mac;
+
 use crate::LibVmError;
 
 use super::core::{NetworkAttachmentRequest, NetworkDriverBackend, NetworkDriverContext};
-use super::{remove_attached_network, VmmonNetworkAttachment, DRIVER_VZNAT};
+use super::{mac_from_machine_id, remove_attached_network, VmmonNetworkAttachment, DRIVER_VZNAT};
 
 pub(super) struct VzNatDriver;
 
@@ -34,7 +36,9 @@ impl NetworkDriverBackend for VzNatDriver {
         remove_attached_network(ctx.paths, ctx.store, ctx.metadata.id).await?;
         let runtime_dir = ctx.paths.machine(ctx.metadata.id).network_link();
         fs::create_dir_all(&runtime_dir)?;
-        Ok(VmmonNetworkAttachment::VzNat { mac: None })
+        Ok(VmmonNetworkAttachment::VzNat {
+            mac: Some(format_mac(mac_from_machine_id(ctx.metadata.id))),
+        })
     }
 }
 
@@ -43,13 +47,15 @@ mod tests {
     use std::collections::BTreeMap;
     use std::path::Path;
 
+    use utils::format_mac;
+
     use super::VzNatDriver;
     use crate::lock_manager::LockId;
     use crate::network::core::{
         NetworkAttachmentRequest, NetworkDriverBackend, NetworkDriverContext,
     };
     use crate::network::NetworkDriverKind;
-    use crate::network::VmmonNetworkAttachment;
+    use crate::network::{mac_from_machine_id, VmmonNetworkAttachment};
     use crate::paths::LocalPaths;
     use crate::store::models::MachineId;
     use crate::store::models::{MachineConfig, MachineNetworkConfig};
@@ -142,6 +148,11 @@ mod tests {
             .await
             .expect("prepare vznat runtime");
 
-        assert_eq!(prepared, VmmonNetworkAttachment::VzNat { mac: None });
+        assert_eq!(
+            prepared,
+            VmmonNetworkAttachment::VzNat {
+                mac: Some(format_mac(mac_from_machine_id(machine_id)))
+            }
+        );
     }
 }
@@ -1,24 +1,74 @@
 use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt};
 use std::path::{Path, PathBuf};
 
+use nix::unistd::Uid;
+
 use crate::LibVmError;
 
 const APP_DIR_NAME: &str = "bento";
 
+/// Resolves the Bento data directory.
+///
+/// `BENTO_DATA_HOME` names the directory itself and wins over everything else.
+/// Otherwise it is `bento` under `XDG_DATA_HOME`, then under `$HOME/.local/share`.
 pub(crate) fn resolve_default_data_dir() -> Result<PathBuf, LibVmError> {
-    let home = env_absolute_path("HOME")?;
-    let data_home = env_absolute_path("XDG_DATA_HOME")?
-        .or_else(|| home.as_ref().map(|path| path.join(".local/share")));
+    data_dir_from(std::env::var_os)
+}
+
+fn data_dir_from(lookup: impl Fn(&'static str) -> Option<OsString>) -> Result<PathBuf, LibVmError> {
+    let path = |name| {
+        lookup(name)
+            .map(|value| absolute_path(name, value))
+            .transpose()
+    };
+    if let Some(data_dir) = path("BENTO_DATA_HOME")? {
+        return Ok(data_dir);
+    }
+    let home = path("HOME")?;
+    let data_home =
+        path("XDG_DATA_HOME")?.or_else(|| home.as_ref().map(|path| path.join(".local/share")));
 
     data_home
         .map(|path| path.join(APP_DIR_NAME))
         .ok_or(LibVmError::DataDirUnavailable)
 }
 
-pub(crate) fn resolve_default_run_dir(data_root: &Path) -> Result<PathBuf, LibVmError> {
-    env_absolute_path("XDG_RUNTIME_DIR")
-        .map(|runtime_dir| runtime_dir.map(|path| path.join(APP_DIR_NAME)))
-        .map(|runtime_dir| runtime_dir.unwrap_or_else(|| data_root.join("run")))
+/// Resolves the run root for control sockets, pid files and locks.
+///
+/// It is `bento` under `XDG_RUNTIME_DIR`. Without one it is a per-user
+/// directory under `/tmp`, which keeps socket paths short and is cleared on
+/// reboot. That directory is created owner-only and refused when another user
+/// could have planted it.
+///
+/// This only seeds a new state database. An existing install keeps the run root
+/// recorded in its database, so locks, network state and sockets of machines
+/// started by an earlier release stay where that release put them.
+pub(crate) fn resolve_default_run_dir() -> Result<PathBuf, LibVmError> {
+    if let Some(runtime_dir) = env_absolute_path("XDG_RUNTIME_DIR")? {
+        return Ok(runtime_dir.join(APP_DIR_NAME));
+    }
+    let uid = Uid::current();
+    let run_dir = Path::new("/tmp").join(format!("{APP_DIR_NAME}-{uid}"));
+    ensure_private_dir(&run_dir, uid)?;
+    Ok(run_dir)
+}
+
+fn ensure_private_dir(path: &Path, uid: Uid) -> Result<(), LibVmError> {
+    match fs::DirBuilder::new().mode(0o700).create(path) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(err) => return Err(err.into()),
+    }
+    let metadata = fs::symlink_metadata(path)?;
+    if !metadata.is_dir() || metadata.uid() != uid.as_raw() || metadata.mode() & 0o077 != 0 {
+        return Err(LibVmError::InsecureRunDir {
+            path: path.to_path_buf(),
+        });
+    }
+    Ok(())
 }
 
 fn env_absolute_path(name: &'static str) -> Result<Option<PathBuf>, LibVmError> {
@@ -42,9 +92,76 @@ mod tests {
     use std::ffi::OsString;
     use std::path::Path;
 
-    use super::absolute_path;
+    use nix::unistd::Uid;
+
+    use crate::paths::defaults::{absolute_path, data_dir_from, ensure_private_dir};
     use crate::LibVmError;
 
+    #[test]
+    fn bento_data_home_overrides_xdg_and_home() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let bento_home = temp.path().join("bento-home");
+        let env = |name: &str| match name {
+            "BENTO_DATA_HOME" => Some(bento_home.clone().into_os_string()),
+            "XDG_DATA_HOME" => Some(OsString::from("/xdg")),
+            "HOME" => Some(OsString::from("/home/dev")),
+            _ => None,
+        };
+
+        assert_eq!(data_dir_from(env).expect("data dir"), bento_home);
+        assert_eq!(
+            data_dir_from(|name| env(name).filter(|_| name != "BENTO_DATA_HOME"))
+                .expect("data dir"),
+            Path::new("/xdg/bento")
+        );
+        assert_eq!(
+            data_dir_from(|name| (name == "HOME").then(|| OsString::from("/home/dev")))
+                .expect("data dir"),
+            Path::new("/home/dev/.local/share/bento")
+        );
+        assert!(matches!(
+            data_dir_from(|name| (name == "BENTO_DATA_HOME").then(|| OsString::from("rel"))),
+            Err(LibVmError::RelativeEnvironmentPath {
+                name: "BENTO_DATA_HOME",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn private_run_dir_is_created_owner_only_and_shared_dirs_are_refused() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::tempdir().expect("tempdir");
+        let run_dir = temp.path().join("bento-run");
+
+        ensure_private_dir(&run_dir, Uid::current()).expect("create run dir");
+        ensure_private_dir(&run_dir, Uid::current()).expect("reuse run dir");
+        assert_eq!(
+            std::fs::metadata(&run_dir)
+                .expect("run dir metadata")
+                .permissions()
+                .mode()
+                & 0o777,
+            0o700
+        );
+
+        std::fs::set_permissions(&run_dir, std::fs::Permissions::from_mode(0o755))
+            .expect("widen run dir");
+        assert!(matches!(
+            ensure_private_dir(&run_dir, Uid::current()),
+            Err(LibVmError::InsecureRunDir { path }) if path == run_dir
+        ));
+
+        let link = temp.path().join("bento-link");
+        std::fs::create_dir(temp.path().join("target")).expect("create link target");
+        std::os::unix::fs::symlink(temp.path().join("target"), &link).expect("link run dir");
+        assert!(matches!(
+            ensure_private_dir(&link, Uid::current()),
+            Err(LibVmError::InsecureRunDir { .. })
+        ));
+    }
+
     #[test]
     fn absolute_path_rejects_relative_env_values() {
         let err = absolute_path("XDG_DATA_HOME", OsString::from("relative"))
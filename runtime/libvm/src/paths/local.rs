@@ -42,7 +42,7 @@ impl LocalRoots {
 
     pub(crate) fn from_env() -> Result<Self, LibVmError> {
         let data_root = resolve_default_data_dir()?;
-        let run_root = resolve_default_run_dir(&data_root)?;
+        let run_root = resolve_default_run_dir()?;
         let image_root = data_root.join(IMAGES_DIR_NAME);
         Ok(Self::with_roots(data_root, run_root, image_root))
     }
@@ -71,6 +71,10 @@ impl LocalRoots {
         self.data_root.join(MACHINES_DIR_NAME)
     }
 
+    pub(crate) fn machines_run_dir(&self) -> PathBuf {
+        self.run_root().join(MACHINES_DIR_NAME)
+    }
+
     pub(crate) fn images_dir(&self) -> PathBuf {
         self.image_root().to_path_buf()
     }
@@ -89,6 +93,7 @@ pub(crate) struct LocalPaths {
     roots: LocalRoots,
     state_db_path: PathBuf,
     machines_dir: PathBuf,
+    machines_run_dir: PathBuf,
     images_dir: PathBuf,
     net_dir: PathBuf,
     locks_dir: PathBuf,
@@ -107,6 +112,7 @@ impl LocalPaths {
     pub(crate) fn from_roots(roots: LocalRoots) -> Self {
         let state_db_path = roots.state_db_path();
         let machines_dir = roots.machines_dir();
+        let machines_run_dir = roots.machines_run_dir();
         let images_dir = roots.images_dir();
         let net_dir = roots.net_dir();
         let locks_dir = roots.locks_dir();
@@ -115,6 +121,7 @@ impl LocalPaths {
             roots,
             state_db_path,
             machines_dir,
+            machines_run_dir,
             images_dir,
             net_dir,
             locks_dir,
@@ -151,7 +158,11 @@ impl LocalPaths {
     }
 
     pub(crate) fn machine(&self, machine_id: MachineId) -> MachinePaths {
-        MachinePaths::new(self.machines_dir().join(machine_id.to_string()))
+        let machine_id = machine_id.to_string();
+        MachinePaths::new(
+            self.machines_dir().join(&machine_id),
+            self.machines_run_dir.join(&machine_id),
+        )
     }
 
     pub(crate) fn network(&self, network_id: &str) -> NetworkPaths {
@@ -191,6 +202,10 @@ mod tests {
         );
         assert_eq!(roots.state_db_path(), PathBuf::from("/tmp/bento/state.db"));
         assert_eq!(roots.machines_dir(), PathBuf::from("/tmp/bento/machines"));
+        assert_eq!(
+            roots.machines_run_dir(),
+            PathBuf::from("/tmp/bento/run/machines")
+        );
         assert_eq!(roots.images_dir(), PathBuf::from("/tmp/bento/images"));
         assert_eq!(roots.net_dir(), PathBuf::from("/tmp/bento/run/net"));
         assert_eq!(roots.locks_dir(), PathBuf::from("/tmp/bento/run/locks"));
@@ -237,6 +252,10 @@ mod tests {
             machine.dir(),
             PathBuf::from("/tmp/bento/machines").join(machine_id.to_string())
         );
+        assert_eq!(
+            machine.run_dir(),
+            PathBuf::from("/tmp/bento/run/machines").join(machine_id.to_string())
+        );
         assert_eq!(paths.locks_dir(), PathBuf::from("/tmp/bento/run/locks"));
         assert_eq!(network.dir(), PathBuf::from("/tmp/bento/run/net/net123"));
     }
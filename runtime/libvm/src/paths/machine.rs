@@ -7,23 +7,37 @@ const VMMON_TRACE_LOG_FILE_NAME: &str = "vm.trace.log";
 const VMMON_EXIT_STATUS_FILE_NAME: &str = "vm.exit.json";
 const SERIAL_LOG_FILE_NAME: &str = "serial.log";
 const ROOT_DISK_FILE_NAME: &str = "rootfs.img";
+const ROOT_DISK_REBUILD_FILE_NAME: &str = "rootfs.img.rebuild";
 const METADATA_CONFIG_FILE_NAME: &str = "metadata.json";
 const NETWORK_LINK_NAME: &str = "net";
 
+/// Files for one machine.
+///
+/// Persistent files live in `dir` under the data root. The control socket lives
+/// in `run_dir` under the run root, which keeps its path short and lets the
+/// host clear it on reboot.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct MachinePaths {
     dir: PathBuf,
+    run_dir: PathBuf,
 }
 
 impl MachinePaths {
-    pub(crate) fn new(dir: impl Into<PathBuf>) -> Self {
-        Self { dir: dir.into() }
+    pub(crate) fn new(dir: impl Into<PathBuf>, run_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            run_dir: run_dir.into(),
+        }
     }
 
     pub(crate) fn dir(&self) -> &Path {
         &self.dir
     }
 
+    pub(crate) fn run_dir(&self) -> &Path {
+        &self.run_dir
+    }
+
     pub(crate) fn vm_spec_path(&self) -> PathBuf {
         vm_spec_path_in(&self.dir)
     }
@@ -32,18 +46,31 @@ impl MachinePaths {
         metadata_config_path_in(&self.dir)
     }
 
-    pub(crate) fn root_disk_path(&self) -> PathBuf {
-        self.dir.join(ROOT_DISK_FILE_NAME)
-    }
-
     pub(crate) fn vmmon_pid_path(&self) -> PathBuf {
         self.dir.join(VMMON_PID_FILE_NAME)
     }
 
     pub(crate) fn vmmon_socket_path(&self) -> PathBuf {
+        self.run_dir.join(VMMON_SOCKET_FILE_NAME)
+    }
+
+    /// Where monitors started before control sockets moved to the run root
+    /// listen. They keep that socket until they exit.
+    pub(crate) fn legacy_vmmon_socket_path(&self) -> PathBuf {
         self.dir.join(VMMON_SOCKET_FILE_NAME)
     }
 
+    /// The socket to connect to: the run root socket, unless only a monitor
+    /// started by an older release is listening in the machine directory.
+    pub(crate) fn vmmon_client_socket_path(&self) -> PathBuf {
+        let socket_path = self.vmmon_socket_path();
+        let legacy_path = self.legacy_vmmon_socket_path();
+        if !socket_path.exists() && legacy_path.exists() {
+            return legacy_path;
+        }
+        socket_path
+    }
+
     pub(crate) fn vmmon_trace_log_path(&self) -> PathBuf {
         vmmon_trace_log_path_in(&self.dir)
     }
@@ -65,6 +92,15 @@ pub(crate) fn root_disk_relative_path() -> PathBuf {
     PathBuf::from(ROOT_DISK_FILE_NAME)
 }
 
+pub(crate) fn root_disk_path_in(dir: &Path) -> PathBuf {
+    dir.join(ROOT_DISK_FILE_NAME)
+}
+
+/// Scratch path a rebuilt root disk is cloned to before it replaces the old one.
+pub(crate) fn root_disk_rebuild_path_in(dir: &Path) -> PathBuf {
+    dir.join(ROOT_DISK_REBUILD_FILE_NAME)
+}
+
 pub(crate) fn vm_spec_path_in(dir: &Path) -> PathBuf {
     dir.join(VM_SPEC_FILE_NAME)
 }
@@ -81,11 +117,30 @@ pub(crate) fn vmmon_trace_log_path_in(dir: &Path) -> PathBuf {
 mod tests {
     use std::path::PathBuf;
 
-    use crate::paths::{root_disk_relative_path, MachinePaths};
+    use crate::paths::{root_disk_path_in, root_disk_relative_path, MachinePaths};
+
+    #[test]
+    fn vmmon_client_socket_falls_back_to_legacy_machine_dir_socket() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let paths = MachinePaths::new(temp.path().join("machine"), temp.path().join("run"));
+        std::fs::create_dir_all(paths.dir()).expect("create machine dir");
+        std::fs::create_dir_all(paths.run_dir()).expect("create run dir");
+
+        assert_eq!(paths.vmmon_client_socket_path(), paths.vmmon_socket_path());
+
+        std::fs::write(paths.legacy_vmmon_socket_path(), b"").expect("write legacy socket");
+        assert_eq!(
+            paths.vmmon_client_socket_path(),
+            paths.legacy_vmmon_socket_path()
+        );
+
+        std::fs::write(paths.vmmon_socket_path(), b"").expect("write socket");
+        assert_eq!(paths.vmmon_client_socket_path(), paths.vmmon_socket_path());
+    }
 
     #[test]
     fn machine_paths_use_expected_filenames() {
-        let paths = MachinePaths::new("/tmp/bento/machines/test");
+        let paths = MachinePaths::new("/tmp/bento/machines/test", "/run/bento/machines/test");
 
         assert_eq!(
             paths.vm_spec_path(),
@@ -96,7 +151,7 @@ mod tests {
             PathBuf::from("/tmp/bento/machines/test/metadata.json")
         );
         assert_eq!(
-            paths.root_disk_path(),
+            root_disk_path_in(paths.dir()),
             PathBuf::from("/tmp/bento/machines/test/rootfs.img")
         );
         assert_eq!(
@@ -105,7 +160,7 @@ mod tests {
         );
         assert_eq!(
             paths.vmmon_socket_path(),
-            PathBuf::from("/tmp/bento/machines/test/vm.sock")
+            PathBuf::from("/run/bento/machines/test/vm.sock")
         );
         assert_eq!(
             paths.vmmon_trace_log_path(),
@@ -6,5 +6,6 @@ mod network;
 pub(crate) use defaults::{resolve_default_data_dir, resolve_default_run_dir};
 pub(crate) use local::{LocalPaths, LocalRoots};
 pub(crate) use machine::{
-    root_disk_relative_path, vm_spec_path_in, vmmon_trace_log_path_in, MachinePaths,
+    root_disk_path_in, root_disk_rebuild_path_in, root_disk_relative_path, vm_spec_path_in,
+    vmmon_trace_log_path_in, MachinePaths,
 };
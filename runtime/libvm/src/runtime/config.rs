@@ -69,7 +69,7 @@ impl RuntimeConfig {
     pub(crate) fn resolve_roots(&self) -> Result<LocalRoots, LibVmError> {
         let data_root = self.bootstrap_data_root()?;
         let run_root = match &self.run_root {
-            PathChoice::Default => resolve_default_run_dir(&data_root)?,
+            PathChoice::Default => resolve_default_run_dir()?,
             PathChoice::Explicit(path) => path.clone(),
         };
         let image_root = match &self.image_root {
@@ -261,6 +261,8 @@ pub struct RuntimeNetworkingConfig {
     pub policy_config_dir: Option<PathBuf>,
     /// netd-specific runtime configuration.
     pub netd: NetdRuntimeConfig,
+    /// vznat-specific runtime configuration.
+    pub vznat: VzNatRuntimeConfig,
 }
 
 impl Default for RuntimeNetworkingConfig {
@@ -269,6 +271,7 @@ impl Default for RuntimeNetworkingConfig {
             private_driver: NetworkDriverKind::Netd,
             policy_config_dir: None,
             netd: NetdRuntimeConfig::default(),
+            vznat: VzNatRuntimeConfig::default(),
         }
     }
 }
@@ -296,6 +299,12 @@ impl RuntimeNetworkingConfig {
         self.netd = netd;
         self
     }
+
+    /// Sets vznat-specific defaults.
+    pub fn with_vznat(mut self, vznat: VzNatRuntimeConfig) -> Self {
+        self.vznat = vznat;
+        self
+    }
 }
 
 /// Configuration for the netd network driver.
@@ -349,6 +358,31 @@ impl NetdRuntimeConfig {
     }
 }
 
+/// Configuration for the Virtualization.framework NAT network driver.
+///
+/// `VZNATNetworkDeviceAttachment` has no settings of its own. macOS picks the
+/// NAT subnet from its shared vmnet preferences, so only the guest side of the
+/// link can be configured here.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct VzNatRuntimeConfig {
+    /// Whether the guest configures IPv6 on the NAT interface.
+    pub ipv6: bool,
+}
+
+impl VzNatRuntimeConfig {
+    /// Creates vznat config with defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables IPv6 on the guest NAT interface.
+    pub fn with_ipv6(mut self, ipv6: bool) -> Self {
+        self.ipv6 = ipv6;
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::paths::LocalRoots;
@@ -377,6 +411,26 @@ mod tests {
         assert_eq!(roots, expected_roots);
     }
 
+    #[test]
+    fn existing_install_keeps_its_recorded_run_root_for_locks_and_net_state() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let data_root = temp.path().join("bento");
+        let legacy_run_root = data_root.join("run");
+        let stored = DbConfig::from_roots(&LocalRoots::with_roots(
+            &data_root,
+            &legacy_run_root,
+            data_root.join("images"),
+        ));
+
+        let roots = RuntimeConfig::local(&data_root)
+            .resolve_store_roots(&stored, &data_root.join("state.db"))
+            .expect("resolve roots from stored contract");
+
+        assert_eq!(roots.run_root(), legacy_run_root);
+        assert_eq!(roots.locks_dir(), legacy_run_root.join("locks"));
+        assert_eq!(roots.net_dir(), legacy_run_root.join("net"));
+    }
+
     #[test]
     fn db_config_rejects_explicit_root_mismatch() {
         let temp = tempfile::tempdir().expect("create temp dir");
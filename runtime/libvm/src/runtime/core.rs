@@ -9,18 +9,19 @@ use eyre::Context;
 use crate::guest_agent::{self, GuestAgentConfigInput};
 use crate::lock_manager::{LockGuard, LockId, LockManager, ManagedLock};
 use crate::machine::root_disk::resize_raw_disk;
-use crate::paths::{vm_spec_path_in, LocalPaths, MachinePaths};
+use crate::paths::{root_disk_path_in, vm_spec_path_in, LocalPaths, MachinePaths};
 use crate::runtime::{RuntimeConfig, RuntimeNetworkingConfig};
 use nix::{
     errno::Errno,
     sys::signal::{kill, Signal},
     unistd::Pid,
 };
+use protocol::socket::validate_socket_path;
 use utils::format_storage_size;
 use vm_spec::{Hardware, VmSpec};
 
 use crate::machine::{
-    Machine, MachineBuilder, MachineData, MachineRef, MachineRefKind, MachineStatus,
+    BrokenMachine, Machine, MachineBuilder, MachineData, MachineRef, MachineRefKind, MachineStatus,
 };
 use crate::network::{
     prepare_network_runtime, reconcile_network_runtime, validate_network_name, NetworkBuilder,
@@ -206,6 +207,56 @@ impl Runtime {
             .collect())
     }
 
+    /// Lists stored machines whose configuration can no longer be decoded.
+    pub async fn list_broken_machines(&self) -> Result<Vec<BrokenMachine>, LibVmError> {
+        Ok(self
+            .store
+            .list_broken_machine_configs()
+            .await?
+            .into_iter()
+            .map(|broken| BrokenMachine {
+                id: broken.id,
+                name: broken.name,
+                reason: broken.reason,
+            })
+            .collect())
+    }
+
+    /// Finds the broken machine `machine` refers to, if its stored configuration
+    /// can no longer be decoded.
+    pub async fn get_broken_machine(
+        &self,
+        machine: &MachineRef,
+    ) -> Result<Option<BrokenMachine>, LibVmError> {
+        Ok(self
+            .list_broken_machines()
+            .await?
+            .into_iter()
+            .find(|broken| match machine.kind() {
+                MachineRefKind::Id(id) => broken.id == id.to_string(),
+                MachineRefKind::IdPrefix(prefix) => broken.id.starts_with(prefix.as_str()),
+                MachineRefKind::Name(name) => broken.name == *name,
+            }))
+    }
+
+    /// Names of machines whose VM spec attaches `disk` directly, such as overlay
+    /// machines that boot from a cached base rootfs.
+    pub async fn machines_attaching_disk(&self, disk: &Path) -> Result<Vec<String>, LibVmError> {
+        let disk = match fs::canonicalize(disk) {
+            Ok(disk) => disk,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(self
+            .store
+            .list_machine_configs()
+            .await?
+            .into_iter()
+            .filter(|config| spec_attaches_disk(&config.spec, &disk))
+            .map(|config| config.name)
+            .collect())
+    }
+
     pub(crate) async fn list_machine_configs(&self) -> Result<Vec<MachineConfig>, LibVmError> {
         let machines = self.store.list_machine_configs().await?;
         for config in &machines {
@@ -730,6 +781,44 @@ impl Runtime {
         Ok(())
     }
 
+    /// Removes the pid file and control socket left behind by a vmmon that
+    /// died without cleaning up. Callers must have established that no live
+    /// monitor owns them.
+    pub(crate) fn remove_stale_vmmon_files(
+        &self,
+        config: &MachineConfig,
+    ) -> Result<(), LibVmError> {
+        let machine_paths = self.machine_paths(config.id);
+        for path in [
+            machine_paths.vmmon_pid_path(),
+            machine_paths.vmmon_socket_path(),
+            machine_paths.legacy_vmmon_socket_path(),
+        ] {
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the machine's run directory and checks its control socket path fits.
+    pub(crate) fn prepare_vmmon_run_dir(&self, config: &MachineConfig) -> Result<(), LibVmError> {
+        let machine_paths = self.machine_paths(config.id);
+        validate_socket_path(&machine_paths.vmmon_socket_path())?;
+        fs::create_dir_all(machine_paths.run_dir())?;
+        Ok(())
+    }
+
+    pub(crate) fn remove_machine_run_dir(&self, config: &MachineConfig) -> Result<(), LibVmError> {
+        match fs::remove_dir_all(self.machine_paths(config.id).run_dir()) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
     pub(crate) async fn save_machine_config(
         &self,
         config: &MachineConfig,
@@ -778,9 +867,13 @@ impl Runtime {
     ) -> Result<MachineData, LibVmError> {
         let runtime_status = self.reconcile_machine_runtime_best_effort(&config).await?;
         let state = self.machine_state(config.id).await?;
+        let mut guest_addresses = Vec::new();
         let status = if runtime_status.is_running() {
             match self.vmmon.client(config.id).inspect().await {
-                Ok(response) => MachineStatus::from_protocol(response),
+                Ok(mut response) => {
+                    guest_addresses = std::mem::take(&mut response.guest_addresses);
+                    MachineStatus::from_protocol(response)
+                }
                 Err(message) => {
                     MachineStatus::running_with_message(format!("vmmon inspect failed: {message}"))
                 }
@@ -789,13 +882,18 @@ impl Runtime {
             MachineStatus::from_machine_state(state.status, state.last_error.clone())
         };
 
-        Ok(MachineData::from_models_with_status(
+        let mut data = MachineData::from_models_with_status(
             config,
             status,
             state.started_at,
             state.last_error,
             state.updated_at,
-        ))
+        );
+        data.guest_addresses = guest_addresses;
+        if runtime_status.is_active() {
+            data.vmmon_pid = state.vmmon_pid;
+        }
+        Ok(data)
     }
 }
 
@@ -817,8 +915,13 @@ pub(crate) fn empty_hardware() -> Hardware {
     Hardware {
         cpus: None,
         memory: None,
+        min_memory: None,
         nested_virtualization: None,
         rosetta: None,
+        graphics: None,
+        keyboard: None,
+        entropy: None,
+        memory_balloon: None,
     }
 }
 
@@ -826,7 +929,7 @@ pub(crate) fn validate_root_disk_growth(
     config: &MachineConfig,
     desired_size: u64,
 ) -> Result<(), LibVmError> {
-    let root_disk_path = MachinePaths::new(&config.machine_dir).root_disk_path();
+    let root_disk_path = root_disk_path_in(&config.machine_dir);
     let current_size = fs::metadata(&root_disk_path)?.len();
     if desired_size < current_size {
         return Err(LibVmError::InvalidMachineUpdate {
@@ -846,7 +949,7 @@ pub(crate) fn reconcile_root_disk_size(config: &MachineConfig) -> Result<(), Lib
         return Ok(());
     };
 
-    let root_disk_path = MachinePaths::new(&config.machine_dir).root_disk_path();
+    let root_disk_path = root_disk_path_in(&config.machine_dir);
     resize_raw_disk(&root_disk_path, desired_size)?;
     Ok(())
 }
@@ -1042,16 +1145,30 @@ pub(crate) fn stopped_machine_state(
     }
 }
 
+/// Whether `spec` attaches the canonical path `disk`. Relative disk paths live
+/// inside the machine directory and never point into shared storage.
+fn spec_attaches_disk(spec: &VmSpec, disk: &Path) -> bool {
+    spec.storage
+        .iter()
+        .flat_map(|storage| &storage.disks)
+        .any(|attached| {
+            attached.path.is_absolute()
+                && fs::canonicalize(&attached.path).unwrap_or_else(|_| attached.path.clone())
+                    == disk
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::lock_manager::LockId;
-    use crate::paths::{LocalPaths, MachinePaths};
+    use crate::paths::{root_disk_path_in, LocalPaths};
     use crate::runtime::core::{
-        read_monitor_pid, stopped_machine_state, write_machine_config, Runtime,
+        read_monitor_pid, spec_attaches_disk, stopped_machine_state, write_machine_config, Runtime,
         STALE_STARTING_TIMEOUT,
     };
     use crate::store::models::{
-        MachineConfig, MachineId, MachineNetworkConfig, MachineRuntimeState, MachineState,
+        BrokenMachineConfig, MachineConfig, MachineId, MachineNetworkConfig, MachineRuntimeState,
+        MachineState,
     };
     use crate::store::MockDataStore;
     use crate::utils::now_unix;
@@ -1064,7 +1181,7 @@ mod tests {
     use std::os::unix::process::CommandExt;
     use std::sync::Arc;
     use std::time::Duration;
-    use vm_spec::{Boot, Guest, GuestOs, Hardware, Kernel, VmSpec};
+    use vm_spec::{Boot, Disk, Guest, GuestOs, Hardware, Kernel, Storage, VmSpec};
 
     fn sample_vm_spec() -> VmSpec {
         VmSpec {
@@ -1082,8 +1199,13 @@ mod tests {
             hardware: Some(Hardware {
                 cpus: Some(4),
                 memory: Some(4096),
+                min_memory: None,
                 nested_virtualization: Some(false),
                 rosetta: Some(false),
+                graphics: Some(false),
+                keyboard: Some(false),
+                entropy: Some(true),
+                memory_balloon: Some(true),
             }),
             ..VmSpec::current()
         }
@@ -1164,7 +1286,7 @@ mod tests {
 
             let spec = sample_vm_spec();
             write_machine_config(&machine_dir, &self.name, &spec)?;
-            std::fs::write(MachinePaths::new(&machine_dir).root_disk_path(), b"disk")?;
+            std::fs::write(root_disk_path_in(&machine_dir), b"disk")?;
 
             let lock = match runtime.allocate_machine_lock() {
                 Ok(lock) => lock,
@@ -1377,6 +1499,46 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn get_broken_machine_matches_name_and_id_prefix() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let paths = LocalPaths::new(temp.path().join("bento"));
+        let id = MachineId::new();
+        let mut store = MockDataStore::new();
+        expect_empty_refresh(&mut store);
+        let broken_id = id.to_string();
+        store
+            .expect_list_broken_machine_configs()
+            .times(3)
+            .returning(move || {
+                Ok(vec![BrokenMachineConfig {
+                    id: broken_id.clone(),
+                    name: "devbox".to_string(),
+                    reason: "missing field `spec`".to_string(),
+                }])
+            });
+        let runtime = runtime_with_mock_store(paths, store).await;
+
+        let by_name = runtime
+            .get_broken_machine(&MachineRef::parse("devbox").expect("valid machine ref"))
+            .await
+            .expect("lookup by name")
+            .expect("broken machine by name");
+        let by_prefix = runtime
+            .get_broken_machine(&MachineRef::parse(&id.to_string()[..8]).expect("valid id prefix"))
+            .await
+            .expect("lookup by prefix");
+        let other = runtime
+            .get_broken_machine(&MachineRef::parse("other").expect("valid machine ref"))
+            .await
+            .expect("lookup other");
+
+        assert_eq!(by_name.id, id.to_string());
+        assert_eq!(by_name.reason, "missing field `spec`");
+        assert_eq!(by_prefix, Some(by_name));
+        assert_eq!(other, None);
+    }
+
     #[tokio::test]
     async fn resolve_machine_config_handles_id_prefix_store_results() {
         let temp = tempfile::tempdir().expect("create temp dir");
@@ -1507,6 +1669,151 @@ mod tests {
         assert_eq!(data.status.message(), None);
     }
 
+    #[tokio::test]
+    async fn inspect_disks_resolve_against_machine_dir_root_first() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let runtime = Runtime::open(
+            LocalPaths::new(temp.path().join("bento")),
+            RuntimeNetworkingConfig::default(),
+        )
+        .await
+        .expect("create runtime");
+        let machine = create_pending_sample(&runtime, "devbox")
+            .await
+            .expect("create pending machine")
+            .commit(&runtime)
+            .await
+            .expect("commit machine");
+        let missing = temp.path().join("missing.img");
+
+        let mut data = machine_handle(&runtime, machine.id)
+            .inspect()
+            .await
+            .expect("inspect machine");
+        data.spec.storage = Some(vm_spec::Storage {
+            disks: vec![
+                vm_spec::Disk {
+                    path: crate::paths::root_disk_relative_path(),
+                    read_only: false,
+                },
+                vm_spec::Disk {
+                    path: missing.clone(),
+                    read_only: true,
+                },
+            ],
+        });
+        let disks = data.disks();
+
+        assert_eq!(disks.len(), 2);
+        assert_eq!(disks[0].role, crate::MachineDiskRole::Root);
+        assert_eq!(disks[0].path, root_disk_path_in(&machine.machine_dir));
+        assert_eq!(disks[0].size_bytes, Some(4));
+        assert_eq!(disks[1].role, crate::MachineDiskRole::Data);
+        assert_eq!(disks[1].path, missing);
+        assert!(disks[1].read_only);
+        assert_eq!(disks[1].size_bytes, None);
+    }
+
+    #[tokio::test]
+    async fn inspect_disks_label_overlay_base_before_root() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let runtime = Runtime::open(
+            LocalPaths::new(temp.path().join("bento")),
+            RuntimeNetworkingConfig::default(),
+        )
+        .await
+        .expect("create runtime");
+        let machine = create_pending_sample(&runtime, "devbox")
+            .await
+            .expect("create pending machine")
+            .commit(&runtime)
+            .await
+            .expect("commit machine");
+        let base = temp.path().join("base.img");
+
+        let mut data = machine_handle(&runtime, machine.id)
+            .inspect()
+            .await
+            .expect("inspect machine");
+        data.spec.storage = Some(vm_spec::Storage {
+            disks: vec![
+                vm_spec::Disk {
+                    path: base.clone(),
+                    read_only: true,
+                },
+                vm_spec::Disk {
+                    path: crate::paths::root_disk_relative_path(),
+                    read_only: false,
+                },
+                vm_spec::Disk {
+                    path: std::path::PathBuf::from("data.img"),
+                    read_only: false,
+                },
+            ],
+        });
+        let disks = data.disks();
+
+        assert_eq!(disks.len(), 3);
+        assert_eq!(disks[0].role, crate::MachineDiskRole::Base);
+        assert_eq!(disks[0].path, base);
+        assert!(disks[0].read_only);
+        assert_eq!(disks[1].role, crate::MachineDiskRole::Root);
+        assert_eq!(disks[1].path, root_disk_path_in(&machine.machine_dir));
+        assert_eq!(disks[2].role, crate::MachineDiskRole::Data);
+    }
+
+    #[tokio::test]
+    async fn dead_monitor_pid_reports_stopped_and_stale_files_are_removed() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let runtime = Runtime::open(
+            LocalPaths::new(temp.path().join("bento")),
+            RuntimeNetworkingConfig::default(),
+        )
+        .await
+        .expect("create runtime");
+        let machine = create_pending_sample(&runtime, "devbox")
+            .await
+            .expect("create pending machine")
+            .commit(&runtime)
+            .await
+            .expect("commit machine");
+        // No kernel hands out pids this large, so this process can never exist.
+        let dead_pid = i32::MAX;
+        runtime
+            .set_machine_state(
+                machine.id,
+                MachineRuntimeState::Running,
+                Some(dead_pid),
+                Some(42),
+                Some("run-1".to_string()),
+                None,
+            )
+            .await
+            .expect("set running state");
+        let machine_paths = runtime.paths.machine(machine.id);
+        runtime
+            .prepare_vmmon_run_dir(&machine)
+            .expect("prepare run dir");
+        std::fs::write(machine_paths.vmmon_pid_path(), dead_pid.to_string())
+            .expect("write stale pid file");
+        std::fs::write(machine_paths.vmmon_socket_path(), "").expect("write stale socket");
+
+        let data = machine_handle(&runtime, machine.id)
+            .inspect()
+            .await
+            .expect("inspect machine");
+        assert_eq!(data.status, MachineStatus::Stopped);
+
+        runtime
+            .remove_stale_vmmon_files(&machine)
+            .expect("remove stale files");
+        assert!(!machine_paths.vmmon_pid_path().exists());
+        assert!(!machine_paths.vmmon_socket_path().exists());
+        runtime
+            .remove_stale_vmmon_files(&machine)
+            .expect("removing missing files is a no-op");
+    }
+
     #[tokio::test]
     async fn inspect_and_list_use_name_and_id_lookup() {
         let temp = tempfile::tempdir().expect("create temp dir");
@@ -2431,6 +2738,75 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn rebuild_root_disk_replaces_disk_and_records_metadata() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let runtime = Runtime::open(
+            LocalPaths::new(temp.path().join("bento")),
+            RuntimeNetworkingConfig::default(),
+        )
+        .await
+        .expect("create runtime");
+        let machine = create_pending_sample(&runtime, "devbox")
+            .await
+            .expect("create pending machine")
+            .commit(&runtime)
+            .await
+            .expect("commit machine");
+        let base_rootfs_path = temp.path().join("base.img");
+        std::fs::write(&base_rootfs_path, b"fresh base").expect("write base rootfs");
+
+        let (data, _) = machine_handle(&runtime, machine.id)
+            .rebuild_root_disk(
+                &base_rootfs_path,
+                None,
+                [("bento.image.id".to_string(), "sha256:new".to_string())].into(),
+            )
+            .await
+            .expect("rebuild root disk");
+
+        assert_eq!(
+            std::fs::read(root_disk_path_in(&machine.machine_dir)).expect("read root disk"),
+            b"fresh base"
+        );
+        assert_eq!(data.id, machine.id.to_string());
+        assert_eq!(data.root_disk_size, Some(10));
+        assert_eq!(
+            data.metadata.get("bento.image.id").map(String::as_str),
+            Some("sha256:new")
+        );
+    }
+
+    #[test]
+    fn spec_attaches_disk_matches_absolute_disks_through_symlinks() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let base = temp.path().join("base.img");
+        std::fs::write(&base, b"base").expect("write base rootfs");
+        let link = temp.path().join("base-link.img");
+        std::os::unix::fs::symlink(&base, &link).expect("link base rootfs");
+        let base = base.canonicalize().expect("canonical base");
+        let spec_with_disks = |paths: &[&std::path::Path]| VmSpec {
+            storage: Some(Storage {
+                disks: paths
+                    .iter()
+                    .map(|path| Disk {
+                        path: path.to_path_buf(),
+                        read_only: true,
+                    })
+                    .collect(),
+            }),
+            ..sample_vm_spec()
+        };
+
+        assert!(spec_attaches_disk(&spec_with_disks(&[&base]), &base));
+        assert!(spec_attaches_disk(&spec_with_disks(&[&link]), &base));
+        assert!(!spec_attaches_disk(
+            &spec_with_disks(&[std::path::Path::new("base.img")]),
+            &base
+        ));
+        assert!(!spec_attaches_disk(&sample_vm_spec(), &base));
+    }
+
     #[tokio::test]
     async fn update_rejects_duplicate_machine_name() {
         let temp = tempfile::tempdir().expect("create temp dir");
@@ -2523,7 +2899,7 @@ mod tests {
             .commit(&runtime)
             .await
             .expect("commit machine");
-        let root_disk = MachinePaths::new(&machine.machine_dir).root_disk_path();
+        let root_disk = root_disk_path_in(&machine.machine_dir);
         let root_disk_file = std::fs::OpenOptions::new()
             .write(true)
             .open(root_disk)
@@ -4,5 +4,7 @@ pub(crate) mod core;
 mod transitions;
 
 pub use builder::RuntimeBuilder;
-pub use config::{NetdRuntimeConfig, PathChoice, RuntimeConfig, RuntimeNetworkingConfig};
+pub use config::{
+    NetdRuntimeConfig, PathChoice, RuntimeConfig, RuntimeNetworkingConfig, VzNatRuntimeConfig,
+};
 pub use core::Runtime;
@@ -1,8 +1,8 @@
 use async_trait::async_trait;
 
 use crate::store::models::MachineId;
-use crate::store::models::{MachineConfig, MachineState};
-use crate::store::row::{DbMachineConfig, DbMachineState};
+use crate::store::models::{BrokenMachineConfig, MachineConfig, MachineState};
+use crate::store::row::{DbMachineConfig, DbMachineConfigEntry, DbMachineState};
 use crate::store::{MachineStore, Store};
 use crate::LibVmError;
 
@@ -136,13 +136,20 @@ impl MachineStore for Store {
     }
 
     async fn list_machine_configs(&self) -> Result<Vec<MachineConfig>, LibVmError> {
-        let query = format!("SELECT {MACHINE_CONFIG_COLUMNS} FROM machine_config ORDER BY name");
-        let rows = sqlx::query_as::<_, DbMachineConfig>(&query)
-            .fetch_all(&self.pool)
-            .await?;
-        Ok(rows
+        Ok(self
+            .machine_config_entries()
+            .await?
             .into_iter()
-            .map(|DbMachineConfig(machine)| machine)
+            .filter_map(Result::ok)
+            .collect())
+    }
+
+    async fn list_broken_machine_configs(&self) -> Result<Vec<BrokenMachineConfig>, LibVmError> {
+        Ok(self
+            .machine_config_entries()
+            .await?
+            .into_iter()
+            .filter_map(Result::err)
             .collect())
     }
 
@@ -162,6 +169,19 @@ impl MachineStore for Store {
 }
 
 impl Store {
+    async fn machine_config_entries(
+        &self,
+    ) -> Result<Vec<Result<MachineConfig, BrokenMachineConfig>>, LibVmError> {
+        let query = format!("SELECT {MACHINE_CONFIG_COLUMNS} FROM machine_config ORDER BY name");
+        let rows = sqlx::query_as::<_, DbMachineConfigEntry>(&query)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|DbMachineConfigEntry(entry)| entry)
+            .collect())
+    }
+
     fn validate_machine_id_prefix(prefix: &str) -> Result<(), LibVmError> {
         let valid_length = prefix.len() >= 3 && prefix.len() <= 32;
         let normalized_hex = prefix
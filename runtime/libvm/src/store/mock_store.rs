@@ -3,7 +3,8 @@ use mockall::mock;
 
 use crate::store::models::MachineId;
 use crate::store::models::{
-    DbConfig, MachineConfig, MachineState, NetworkAttachment, NetworkDefinition, NetworkInstance,
+    BrokenMachineConfig, DbConfig, MachineConfig, MachineState, NetworkAttachment,
+    NetworkDefinition, NetworkInstance,
 };
 use crate::store::{ConfigStore, MachineStore, NetworkStore};
 use crate::LibVmError;
@@ -53,6 +54,10 @@ mock! {
 
         async fn list_machine_configs(&self) -> Result<Vec<MachineConfig>, LibVmError>;
 
+        async fn list_broken_machine_configs(
+            &self,
+        ) -> Result<Vec<BrokenMachineConfig>, LibVmError>;
+
         async fn remove_machine(&self, machine: &MachineConfig) -> Result<(), LibVmError>;
     }
 
@@ -19,6 +19,7 @@ pub(crate) struct MachineConfig {
     pub id: MachineId,
     pub lock_id: LockId,
     pub name: String,
+    #[serde(deserialize_with = "vm_spec::deserialize_migrated")]
     pub spec: VmSpec,
     #[serde(alias = "instanceDir")]
     pub machine_dir: PathBuf,
@@ -32,6 +33,16 @@ pub(crate) struct MachineConfig {
     pub network: MachineNetworkConfig,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Stored machine config row whose JSON no longer decodes into `MachineConfig`.
+///
+/// Only the indexed columns are trusted; `reason` carries the decode error.
+pub(crate) struct BrokenMachineConfig {
+    pub id: String,
+    pub name: String,
+    pub reason: String,
+}
+
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
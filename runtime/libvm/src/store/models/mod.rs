@@ -12,7 +12,7 @@ mod machine_id;
 mod network;
 
 pub(crate) use db_config::DbConfig;
-pub(crate) use machine::{MachineConfig, MachineRuntimeState, MachineState};
+pub(crate) use machine::{BrokenMachineConfig, MachineConfig, MachineRuntimeState, MachineState};
 pub(crate) use machine_id::{looks_like_id_prefix, MachineId};
 pub(crate) use network::{
     MachineNetworkConfig, NetworkDefinition, NetworkDriverPreference, NetworkInstanceState,
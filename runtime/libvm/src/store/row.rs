@@ -6,11 +6,12 @@ use sqlx::{FromRow, Row};
 
 use crate::store::models::MachineId;
 use crate::store::models::{
-    MachineConfig, MachineRuntimeState, MachineState, NetworkAttachment, NetworkDefinition,
-    NetworkDriverPreference, NetworkInstance, NetworkInstanceState,
+    BrokenMachineConfig, MachineConfig, MachineRuntimeState, MachineState, NetworkAttachment,
+    NetworkDefinition, NetworkDriverPreference, NetworkInstance, NetworkInstanceState,
 };
 
 pub(crate) struct DbMachineConfig(pub(crate) MachineConfig);
+pub(crate) struct DbMachineConfigEntry(pub(crate) Result<MachineConfig, BrokenMachineConfig>);
 pub(crate) struct DbMachineState(pub(crate) MachineState);
 pub(crate) struct DbNetworkAttachment(pub(crate) NetworkAttachment);
 pub(crate) struct DbNetworkInstance(pub(crate) NetworkInstance);
@@ -45,6 +46,22 @@ impl<'row> FromRow<'row, SqliteRow> for DbMachineConfig {
     }
 }
 
+impl<'row> FromRow<'row, SqliteRow> for DbMachineConfigEntry {
+    fn from_row(row: &'row SqliteRow) -> sqlx::Result<Self> {
+        let id: String = row.try_get("id")?;
+        let name: String = row.try_get("name")?;
+        Ok(Self(
+            DbMachineConfig::from_row(row)
+                .map(|DbMachineConfig(config)| config)
+                .map_err(|err| BrokenMachineConfig {
+                    id,
+                    name,
+                    reason: err.to_string(),
+                }),
+        ))
+    }
+}
+
 impl<'row> FromRow<'row, SqliteRow> for DbMachineState {
     fn from_row(row: &'row SqliteRow) -> sqlx::Result<Self> {
         let id_str: String = row.try_get("machine_id")?;
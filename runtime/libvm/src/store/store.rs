@@ -101,8 +101,13 @@ mod tests {
             hardware: Some(Hardware {
                 cpus: Some(2),
                 memory: Some(1024),
+                min_memory: None,
                 nested_virtualization: Some(false),
                 rosetta: Some(false),
+                graphics: Some(false),
+                keyboard: Some(false),
+                entropy: Some(true),
+                memory_balloon: Some(true),
             }),
             ..VmSpec::current()
         }
@@ -462,6 +467,38 @@ mod tests {
         assert_eq!(list[1].name, "bravo");
     }
 
+    #[tokio::test]
+    async fn list_reports_undecodable_configs_separately() {
+        let (_dir, paths) = temp_paths();
+        let db = Store::new(&paths).await.expect("open db");
+        let good_id = MachineId::new();
+        let broken_id = MachineId::new();
+        let good = machine_from_path(good_id, "good".to_string(), paths.machine(good_id).dir());
+        let broken = machine_from_path(
+            broken_id,
+            "broken".to_string(),
+            paths.machine(broken_id).dir(),
+        );
+        seed_machine(&db, &good).await;
+        seed_machine(&db, &broken).await;
+
+        sqlx::query("UPDATE machine_config SET config_json = jsonb(?1) WHERE id = ?2")
+            .bind(r#"{"name":"broken"}"#)
+            .bind(broken_id.to_string())
+            .execute(&db.pool)
+            .await
+            .expect("corrupt stored config");
+
+        let list = db.list_machine_configs().await.expect("list");
+        let broken_list = db.list_broken_machine_configs().await.expect("list broken");
+
+        assert_eq!(list, vec![good]);
+        assert_eq!(broken_list.len(), 1);
+        assert_eq!(broken_list[0].id, broken_id.to_string());
+        assert_eq!(broken_list[0].name, "broken");
+        assert!(broken_list[0].reason.contains("machine_config.config_json"));
+    }
+
     #[tokio::test]
     async fn remove_machine() {
         let (_dir, paths) = temp_paths();
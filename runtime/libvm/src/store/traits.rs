@@ -2,7 +2,8 @@ use async_trait::async_trait;
 
 use crate::store::models::MachineId;
 use crate::store::models::{
-    DbConfig, MachineConfig, MachineState, NetworkAttachment, NetworkDefinition, NetworkInstance,
+    BrokenMachineConfig, DbConfig, MachineConfig, MachineState, NetworkAttachment,
+    NetworkDefinition, NetworkInstance,
 };
 use crate::LibVmError;
 
@@ -73,8 +74,15 @@ pub(crate) trait MachineStore: std::fmt::Debug + Send + Sync {
     ) -> Result<Vec<MachineConfig>, LibVmError>;
 
     /// Lists all machine configs sorted by machine name.
+    ///
+    /// Rows whose config no longer decodes are skipped here and reported by
+    /// `list_broken_machine_configs` instead, so one bad row does not hide
+    /// every other machine.
     async fn list_machine_configs(&self) -> Result<Vec<MachineConfig>, LibVmError>;
 
+    /// Lists machine config rows that fail to decode, sorted by machine name.
+    async fn list_broken_machine_configs(&self) -> Result<Vec<BrokenMachineConfig>, LibVmError>;
+
     /// Removes a machine config and runtime state.
     ///
     /// Network attachments are removed by the database foreign-key cascade.
@@ -0,0 +1,54 @@
+use std::process::Command;
+
+use serde::Deserialize;
+
+use crate::vmmon::vmmon_executable;
+use crate::LibVmError;
+
+/// Optional hypervisor features available on this host.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[non_exhaustive]
+pub struct HostCapabilities {
+    /// Guests may run their own hypervisor.
+    pub nested_virtualization: bool,
+}
+
+/// Asks vmmon which optional hypervisor features this host supports.
+///
+/// vmmon owns the hypervisor bindings, so this runs a short-lived probe
+/// instead of linking them into every libvm consumer.
+pub fn host_capabilities() -> Result<HostCapabilities, LibVmError> {
+    let output = Command::new(vmmon_executable()?)
+        .arg("--host-capabilities")
+        .output()?;
+    if !output.status.success() {
+        return Err(LibVmError::HostCapabilities {
+            message: format!(
+                "vmmon exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+    parse_host_capabilities(&output.stdout)
+}
+
+fn parse_host_capabilities(stdout: &[u8]) -> Result<HostCapabilities, LibVmError> {
+    serde_json::from_slice(stdout).map_err(|err| LibVmError::HostCapabilities {
+        message: format!("decode vmmon output: {err}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vmmon::capabilities::parse_host_capabilities;
+
+    #[test]
+    fn parses_probe_output_and_ignores_new_fields() {
+        let capabilities = parse_host_capabilities(br#"{"nested_virtualization":true,"future":1}"#)
+            .expect("parse capabilities");
+
+        assert!(capabilities.nested_virtualization);
+        assert!(parse_host_capabilities(b"not json").is_err());
+    }
+}
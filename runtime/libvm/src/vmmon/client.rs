@@ -4,17 +4,27 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use hyper_util::rt::TokioIo;
-use protocol::negotiate::{ClientUpgradeStreamError, Negotiate, RejectCode, Upgrade};
+use protocol::negotiate::{
+    ClientUpgradeStreamError, Negotiate, RejectCode, Upgrade, NEGOTIATE_STREAM_TIMEOUT,
+};
+use protocol::services::VM_MONITOR_API_VERSION;
+use protocol::socket::validate_socket_path;
 use protocol::v1::vm_monitor_service_client::VmMonitorServiceClient;
 use protocol::v1::{
-    InspectRequest, InspectResponse, PingRequest, PingResponse, WatchStatusRequest,
+    InspectRequest, InspectResponse, ListServicesRequest, ListServicesResponse, MetricsRequest,
+    MetricsResponse, PauseRequest, PingRequest, PingResponse, ResumeRequest,
+    SetBalloonTargetRequest, StatusUpdate, WatchStatusRequest,
 };
 use tokio::net::UnixStream;
 use tokio::sync::Mutex;
-use tonic::transport::Endpoint;
+use tonic::transport::{Channel, Endpoint};
 use tower::service_fn;
 
 pub const DEFAULT_GUEST_READINESS_TIMEOUT: Duration = Duration::from_secs(60 * 5);
+/// How long connecting and negotiating on the control socket may take.
+const CONTROL_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a shell upgrade may wait while vmmon connects to the guest sshd.
+const SHELL_NEGOTIATE_TIMEOUT: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Clone)]
 pub(crate) struct VmmonClient {
@@ -28,6 +38,24 @@ impl VmmonClient {
         }
     }
 
+    /// Opens a negotiated vm monitor API connection on the control socket.
+    async fn api_client(&self) -> Result<VmMonitorServiceClient<Channel>, String> {
+        let stream = tokio::time::timeout(
+            CONTROL_CONNECT_TIMEOUT,
+            connect_vm_monitor_stream(&self.socket_path),
+        )
+        .await
+        .map_err(|_| {
+            format!(
+                "timed out after {CONTROL_CONNECT_TIMEOUT:?} connecting to control socket {}",
+                self.socket_path.display()
+            )
+        })??;
+        vm_monitor_client(stream)
+            .await
+            .map_err(|err| format!("connect vm monitor rpc client: {err}"))
+    }
+
     pub(crate) async fn wait_for_shell_with_timeout(
         &self,
         timeout: Duration,
@@ -53,17 +81,17 @@ impl VmmonClient {
         }
     }
 
-    pub(crate) async fn wait_for_guest_running(&self, timeout: Duration) -> Result<(), String> {
-        let stream = connect_vm_monitor_stream(&self.socket_path).await?;
-        let mut client = vm_monitor_client(stream)
-            .await
-            .map_err(|err| format!("connect vm monitor rpc client: {err}"))?;
-
-        let mut updates = client
+    pub(crate) async fn watch_status(&self) -> Result<tonic::Streaming<StatusUpdate>, String> {
+        let mut client = self.api_client().await?;
+        client
             .watch_status(WatchStatusRequest {})
             .await
-            .map_err(|err| format!("vm monitor watch_status rpc failed: {err}"))?
-            .into_inner();
+            .map(tonic::Response::into_inner)
+            .map_err(|err| format!("vm monitor watch_status rpc failed: {err}"))
+    }
+
+    pub(crate) async fn wait_for_guest_running(&self, timeout: Duration) -> Result<(), String> {
+        let mut updates = self.watch_status().await?;
 
         let deadline = Instant::now() + timeout;
         let mut vm_running_seen = false;
@@ -119,10 +147,7 @@ impl VmmonClient {
     }
 
     pub(crate) async fn inspect(&self) -> Result<InspectResponse, String> {
-        let stream = connect_vm_monitor_stream(&self.socket_path).await?;
-        let mut client = vm_monitor_client(stream)
-            .await
-            .map_err(|err| format!("connect vm monitor rpc client: {err}"))?;
+        let mut client = self.api_client().await?;
 
         let response = client
             .inspect(InspectRequest {})
@@ -132,12 +157,100 @@ impl VmmonClient {
         Ok(response.into_inner())
     }
 
+    /// Sends one ping RPC and fails unless vmmon answers with `ok` within `timeout`.
+    pub(crate) async fn ping(&self, timeout: Duration) -> Result<(), String> {
+        let response = tokio::time::timeout(timeout, async {
+            let mut client = self.api_client().await?;
+            client
+                .ping(PingRequest {})
+                .await
+                .map(tonic::Response::into_inner)
+                .map_err(|err| format!("vm monitor ping rpc failed: {err}"))
+        })
+        .await
+        .map_err(|_| format!("timed out after {timeout:?} waiting for vm monitor ping"))??;
+
+        if response.ok {
+            Ok(())
+        } else if response.message.is_empty() {
+            Err("vm monitor ping failed".to_string())
+        } else {
+            Err(response.message)
+        }
+    }
+
+    pub(crate) async fn pause(&self) -> Result<(), String> {
+        let mut client = self.api_client().await?;
+
+        client
+            .pause(PauseRequest {})
+            .await
+            .map_err(|status| render_lifecycle_status("pause", status))?;
+        Ok(())
+    }
+
+    pub(crate) async fn resume(&self) -> Result<(), String> {
+        let mut client = self.api_client().await?;
+
+        client
+            .resume(ResumeRequest {})
+            .await
+            .map_err(|status| render_lifecycle_status("resume", status))?;
+        Ok(())
+    }
+
+    pub(crate) async fn metrics(&self) -> Result<MetricsResponse, String> {
+        let mut client = self.api_client().await?;
+
+        let response = client
+            .metrics(MetricsRequest {})
+            .await
+            .map_err(|status| render_lifecycle_status("metrics", status))?;
+
+        Ok(response.into_inner())
+    }
+
+    /// Asks vmmon to move the balloon target and returns the target it applied in MiB.
+    pub(crate) async fn set_balloon_target(&self, target_mib: u64) -> Result<u64, String> {
+        let mut client = self.api_client().await?;
+
+        let response = client
+            .set_balloon_target(SetBalloonTargetRequest { target_mib })
+            .await
+            .map_err(|status| render_lifecycle_status("set balloon target", status))?;
+
+        Ok(response.into_inner().target_mib)
+    }
+
+    pub(crate) async fn list_services(&self) -> Result<ListServicesResponse, String> {
+        let mut client = self.api_client().await?;
+
+        let response = client
+            .list_services(ListServicesRequest {})
+            .await
+            .map_err(|status| render_lifecycle_status("list services", status))?;
+
+        Ok(response.into_inner())
+    }
+
     pub(crate) async fn open_serial_stream(&self) -> Result<UnixStream, String> {
-        connect_upgrade_stream(&self.socket_path, Upgrade::Serial, "serial").await
+        connect_upgrade_stream(
+            &self.socket_path,
+            Upgrade::Serial,
+            "serial",
+            NEGOTIATE_STREAM_TIMEOUT,
+        )
+        .await
     }
 
     pub(crate) async fn open_shell_stream(&self) -> Result<UnixStream, String> {
-        connect_upgrade_stream(&self.socket_path, Upgrade::Shell, "shell").await
+        connect_upgrade_stream(
+            &self.socket_path,
+            Upgrade::Shell,
+            "shell",
+            SHELL_NEGOTIATE_TIMEOUT,
+        )
+        .await
     }
 }
 
@@ -145,23 +258,11 @@ async fn connect_upgrade_stream(
     socket_path: &Path,
     upgrade: Upgrade,
     label: &str,
+    timeout: Duration,
 ) -> Result<UnixStream, String> {
-    let stream = UnixStream::connect(socket_path).await.map_err(|err| {
-        if err.kind() == io::ErrorKind::NotFound {
-            format!(
-                "vmmon_unreachable: control socket {} is missing, make sure the VM is running",
-                socket_path.display()
-            )
-        } else {
-            format!(
-                "connect control socket failed: {} ({})",
-                err,
-                socket_path.display()
-            )
-        }
-    })?;
+    let stream = connect_control_socket(socket_path).await?;
 
-    match Negotiate::client_upgrade_stream_v1(stream, upgrade).await {
+    match Negotiate::client_upgrade_stream_v1_with_timeout(stream, upgrade, timeout).await {
         Ok(stream) => Ok(stream),
         Err(ClientUpgradeStreamError::Reject(reject)) => {
             Err(render_reject_error(reject.code, &reject.message))
@@ -178,11 +279,19 @@ enum ProbeError {
 }
 
 async fn probe_shell_once(socket_path: &Path) -> Result<(), ProbeError> {
+    validate_socket_path(socket_path).map_err(|err| ProbeError::Fatal(err.to_string()))?;
     let stream = UnixStream::connect(socket_path)
         .await
         .map_err(|err| classify_io_error("connect Negotiate socket", err))?;
 
-    match Negotiate::client_upgrade_stream_v1(stream, Upgrade::Api { api_version: 1 }).await {
+    match Negotiate::client_upgrade_stream_v1(
+        stream,
+        Upgrade::Api {
+            api_version: VM_MONITOR_API_VERSION,
+        },
+    )
+    .await
+    {
         Ok(stream) => {
             let ping = call_vm_monitor_ping(stream).await?;
             if ping.ok {
@@ -200,7 +309,7 @@ async fn probe_shell_once(socket_path: &Path) -> Result<(), ProbeError> {
             Err(classify_io_error("negotiate api stream", err))
         }
         Err(ClientUpgradeStreamError::Reject(reject)) => match reject.code {
-            RejectCode::ServiceStarting | RejectCode::ServiceUnavailable => {
+            RejectCode::ServiceStarting | RejectCode::ServiceUnavailable | RejectCode::Timeout => {
                 Err(ProbeError::Retryable(format!(
                     "{}: {}",
                     reject_code_label(reject.code),
@@ -229,26 +338,48 @@ async fn call_vm_monitor_ping(stream: UnixStream) -> Result<PingResponse, ProbeE
     Ok(response.into_inner())
 }
 
+/// Connects to the control socket, explaining a missing socket as a stopped VM.
+async fn connect_control_socket(socket_path: &Path) -> Result<UnixStream, String> {
+    validate_socket_path(socket_path).map_err(|err| err.to_string())?;
+    UnixStream::connect(socket_path).await.map_err(|err| {
+        if err.kind() == io::ErrorKind::NotFound {
+            format!(
+                "vmmon_unreachable: control socket {} is missing, make sure the VM is running",
+                socket_path.display()
+            )
+        } else {
+            format!(
+                "connect control socket failed: {} ({})",
+                err,
+                socket_path.display()
+            )
+        }
+    })
+}
+
 async fn connect_vm_monitor_stream(socket_path: &Path) -> Result<UnixStream, String> {
-    let stream = UnixStream::connect(socket_path)
-        .await
-        .map_err(|err| format!("connect Negotiate socket failed: {err}"))?;
+    let stream = connect_control_socket(socket_path).await?;
 
-    Negotiate::client_upgrade_stream_v1(stream, Upgrade::Api { api_version: 1 })
-        .await
-        .map_err(|err| match err {
-            ClientUpgradeStreamError::Io(io_err) => {
-                format!("negotiate api stream failed: {io_err}")
-            }
-            ClientUpgradeStreamError::Reject(reject) => {
-                format!("{}: {}", reject_code_label(reject.code), reject.message)
-            }
-        })
+    Negotiate::client_upgrade_stream_v1(
+        stream,
+        Upgrade::Api {
+            api_version: VM_MONITOR_API_VERSION,
+        },
+    )
+    .await
+    .map_err(|err| match err {
+        ClientUpgradeStreamError::Io(io_err) => {
+            format!("negotiate api stream failed: {io_err}")
+        }
+        ClientUpgradeStreamError::Reject(reject) => {
+            render_reject_error(reject.code, &reject.message)
+        }
+    })
 }
 
 async fn vm_monitor_client(
     stream: UnixStream,
-) -> Result<VmMonitorServiceClient<tonic::transport::Channel>, tonic::transport::Error> {
+) -> Result<VmMonitorServiceClient<Channel>, tonic::transport::Error> {
     let stream_slot = Arc::new(Mutex::new(Some(stream)));
     let connector = service_fn(move |_| {
         let stream_slot = Arc::clone(&stream_slot);
@@ -298,6 +429,16 @@ fn is_retryable_io_kind(kind: io::ErrorKind) -> bool {
     )
 }
 
+fn render_lifecycle_status(operation: &str, status: tonic::Status) -> String {
+    match status.code() {
+        tonic::Code::Unimplemented => {
+            format!("{operation} is not supported by this machine's backend")
+        }
+        tonic::Code::FailedPrecondition => status.message().to_string(),
+        _ => format!("vm monitor {operation} rpc failed: {status}"),
+    }
+}
+
 fn reject_code_label(code: RejectCode) -> &'static str {
     match code {
         RejectCode::UnsupportedProtocol => "unsupported_protocol",
@@ -308,6 +449,8 @@ fn reject_code_label(code: RejectCode) -> &'static str {
         RejectCode::PermissionDenied => "permission_denied",
         RejectCode::AuthFailed => "auth_failed",
         RejectCode::Internal => "internal_error",
+        RejectCode::Timeout => "timeout",
+        RejectCode::UnsupportedApiVersion => "unsupported_api_version",
     }
 }
 
@@ -327,5 +470,58 @@ fn render_reject_error(code: RejectCode, message: &str) -> String {
         RejectCode::PermissionDenied => format!("permission_denied: {message}"),
         RejectCode::AuthFailed => format!("auth_failed: {message}"),
         RejectCode::Internal => format!("internal_error: {message}"),
+        RejectCode::Timeout => format!(
+            "timeout: {message}. the guest service never answered; check `bento logs` for boot errors"
+        ),
+        RejectCode::UnsupportedApiVersion => format!(
+            "unsupported_api_version: {message}. the machine was started by a different bento release; restart it with `bento restart`"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protocol::negotiate::{Negotiate, Reject, RejectCode, Response, Upgrade};
+    use protocol::services::VM_MONITOR_API_VERSION;
+    use tokio::net::UnixListener;
+
+    use crate::vmmon::client::VmmonClient;
+
+    #[tokio::test]
+    async fn api_version_rejection_asks_for_a_restart() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let socket_path = temp.path().join("vmmon.sock");
+        let listener = UnixListener::bind(&socket_path).expect("bind control socket");
+
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.expect("accept client");
+            let request = Negotiate::read_from(&mut stream)
+                .await
+                .expect("read negotiate request");
+            Response::Reject(Reject {
+                request_id: request.request_id,
+                code: RejectCode::UnsupportedApiVersion,
+                message: "this monitor speaks vm monitor api version 1".to_string(),
+                retry_after_ms: None,
+            })
+            .write_to(&mut stream)
+            .await
+            .expect("write reject response");
+            request.upgrade
+        });
+
+        let err = VmmonClient::new(&socket_path)
+            .pause()
+            .await
+            .expect_err("mismatched monitor rejects the api upgrade");
+
+        assert_eq!(
+            server.await.expect("server task join"),
+            Upgrade::Api {
+                api_version: VM_MONITOR_API_VERSION,
+            }
+        );
+        assert!(err.starts_with("unsupported_api_version:"), "{err}");
+        assert!(err.contains("restart"), "{err}");
     }
 }
@@ -1,10 +1,14 @@
+use std::ffi::OsString;
 use std::io::{self, BufRead, Write};
 use std::os::fd::{AsRawFd, OwnedFd};
+use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
 
 use nix::unistd::pipe;
+use protocol::{ENV_BENTO_MACHINE, ENV_BENTO_MACHINE_DIR, VMMON_START_TIMEOUT};
+use tokio::sync::mpsc;
 
 use crate::machine::MachineExitCommand;
 use crate::network::VmmonNetworkAttachment;
@@ -15,6 +19,9 @@ use crate::LibVmError;
 const ENV_VM_STARTPIPE: &str = "_VM_STARTPIPE";
 const ENV_VM_SYNCPIPE: &str = "_VM_SYNCPIPE";
 const VMMON_LAUNCHER_EXIT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long vmmon may go without a syncpipe heartbeat before it is treated as hung.
+const VMMON_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(10);
+const SYNCPIPE_HEARTBEAT: &str = "heartbeat\n";
 
 pub(crate) struct VmmonLaunch<'a> {
     pub(crate) machine_id: MachineId,
@@ -31,6 +38,7 @@ pub(crate) struct VmmonLaunch<'a> {
     pub(crate) run_id: &'a str,
     pub(crate) exit_command: Option<&'a MachineExitCommand>,
     pub(crate) wait_for_registration: Duration,
+    pub(crate) idle_timeout: Option<Duration>,
 }
 
 impl Vmmon {
@@ -38,7 +46,7 @@ impl Vmmon {
         let (start_read, start_write) = pipe().map_err(|err| io::Error::other(err.to_string()))?;
         let (sync_read, sync_write) = pipe().map_err(|err| io::Error::other(err.to_string()))?;
 
-        let mut command = Command::new(resolve_vmmon_executable()?);
+        let mut command = Command::new(vmmon_executable()?);
         command
             .arg("--id")
             .arg(launch.machine_id.to_string())
@@ -66,9 +74,15 @@ impl Vmmon {
             .arg(launch.run_id)
             .arg("--wait-for-registration")
             .arg(launch.wait_for_registration.as_secs().to_string());
+        if let Some(idle_timeout) = launch.idle_timeout {
+            command
+                .arg("--idle-timeout")
+                .arg(idle_timeout.as_secs().max(1).to_string());
+        }
         if let Some(exit_command) = launch.exit_command {
             append_exit_command_args(&mut command, exit_command);
         }
+        apply_machine_env(&mut command, launch.name, launch.machine_dir);
         command
             .env(ENV_VM_STARTPIPE, start_read.as_raw_fd().to_string())
             .env(ENV_VM_SYNCPIPE, sync_write.as_raw_fd().to_string());
@@ -93,6 +107,12 @@ impl Vmmon {
     }
 }
 
+fn apply_machine_env(command: &mut Command, name: &str, machine_dir: &Path) {
+    command
+        .env(ENV_BENTO_MACHINE, name)
+        .env(ENV_BENTO_MACHINE_DIR, machine_dir);
+}
+
 fn append_exit_command_args(command: &mut Command, exit_command: &MachineExitCommand) {
     command.arg("--exit-command").arg(&exit_command.command);
     for arg in &exit_command.args {
@@ -137,22 +157,40 @@ fn wait_for_vmmon_launcher_blocking(mut child: std::process::Child) -> io::Resul
     )))
 }
 
-fn resolve_vmmon_executable() -> Result<PathBuf, LibVmError> {
+/// Locates the vmmon binary next to the current executable or on `PATH`.
+///
+/// Fails before spawning when the binary that would run is not an executable file.
+pub fn vmmon_executable() -> Result<PathBuf, LibVmError> {
     let current_exe = std::env::current_exe()?;
+    vmmon_executable_from(&current_exe, std::env::var_os("PATH"))
+}
+
+fn vmmon_executable_from(
+    current_exe: &Path,
+    path_var: Option<OsString>,
+) -> Result<PathBuf, LibVmError> {
     let expected_path = current_exe
         .parent()
         .map(|parent| parent.join("vmmon"))
         .unwrap_or_else(|| PathBuf::from("vmmon"));
 
     if expected_path.exists() {
+        if !is_executable_file(&expected_path) {
+            return Err(LibVmError::VmMonExecutableNotExecutable {
+                path: expected_path,
+            });
+        }
         return Ok(expected_path);
     }
 
-    if let Some(path) = std::env::var_os("PATH") {
-        if std::env::split_paths(&path)
+    if let Some(path) = path_var {
+        if let Some(candidate) = std::env::split_paths(&path)
             .map(|path| path.join("vmmon"))
-            .any(|candidate| candidate.exists())
+            .find(|candidate| candidate.exists())
         {
+            if !is_executable_file(&candidate) {
+                return Err(LibVmError::VmMonExecutableNotExecutable { path: candidate });
+            }
             return Ok(PathBuf::from("vmmon"));
         }
     }
@@ -160,25 +198,39 @@ fn resolve_vmmon_executable() -> Result<PathBuf, LibVmError> {
     Err(LibVmError::VmMonExecutableNotFound { expected_path })
 }
 
+fn is_executable_file(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
 async fn wait_for_start(syncpipe: OwnedFd, trace_path: &Path) -> Result<(), LibVmError> {
-    let deadline_duration = Duration::from_secs(30);
-    let trace_path = trace_path.to_path_buf();
-    let result = tokio::time::timeout(
-        deadline_duration,
-        tokio::task::spawn_blocking(move || read_syncpipe(syncpipe)),
-    )
-    .await
-    .map_err(|_| {
-        io::Error::new(
-            io::ErrorKind::TimedOut,
-            format!(
-                "vmmon syncpipe did not report readiness in {:?} (hint: see {})",
-                deadline_duration,
-                trace_path.display(),
-            ),
-        )
-    })?
-    .map_err(|err| io::Error::other(format!("join vmmon syncpipe wait task: {err}")))??;
+    let (heartbeat_tx, mut heartbeat_rx) = mpsc::unbounded_channel();
+    let mut reader = tokio::task::spawn_blocking(move || read_syncpipe(syncpipe, &heartbeat_tx));
+    let deadline = tokio::time::Instant::now() + VMMON_START_TIMEOUT;
+
+    let result = loop {
+        let quiet_deadline = deadline.min(tokio::time::Instant::now() + VMMON_HEARTBEAT_TIMEOUT);
+        tokio::select! {
+            joined = &mut reader => {
+                break joined.map_err(|err| {
+                    io::Error::other(format!("join vmmon syncpipe wait task: {err}"))
+                })??;
+            }
+            Some(()) = heartbeat_rx.recv() => continue,
+            () = tokio::time::sleep_until(quiet_deadline) => {
+                let reason = if quiet_deadline >= deadline {
+                    format!("did not report readiness in {VMMON_START_TIMEOUT:?}")
+                } else {
+                    format!("sent no heartbeat for {VMMON_HEARTBEAT_TIMEOUT:?}")
+                };
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("vmmon syncpipe {reason} (hint: see {})", trace_path.display()),
+                )
+                .into());
+            }
+        }
+    };
 
     match result {
         StartupResult::Started => Ok(()),
@@ -192,10 +244,21 @@ fn release_startpipe(startpipe: OwnedFd) -> io::Result<()> {
     file.flush()
 }
 
-fn read_syncpipe(syncpipe: OwnedFd) -> io::Result<StartupResult> {
+/// Reads syncpipe messages until vmmon reports a result, forwarding heartbeats.
+fn read_syncpipe(
+    syncpipe: OwnedFd,
+    heartbeats: &mpsc::UnboundedSender<()>,
+) -> io::Result<StartupResult> {
+    let mut reader = std::io::BufReader::new(std::fs::File::from(syncpipe));
     let mut input = String::new();
-    let mut file = std::fs::File::from(syncpipe);
-    std::io::BufReader::new(&mut file).read_line(&mut input)?;
+    loop {
+        input.clear();
+        reader.read_line(&mut input)?;
+        if input != SYNCPIPE_HEARTBEAT {
+            break;
+        }
+        let _ = heartbeats.send(());
+    }
 
     if input == "started\n" {
         return Ok(StartupResult::Started);
@@ -237,13 +300,45 @@ enum StartupResult {
 mod tests {
     use std::ffi::OsString;
     use std::io::{Read, Write};
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
     use std::process::Command;
 
     use nix::unistd::pipe;
+    use tokio::sync::mpsc;
 
     use crate::machine::MachineExitCommand;
 
-    use super::{append_exit_command_args, read_syncpipe, release_startpipe, StartupResult};
+    use super::{
+        append_exit_command_args, apply_machine_env, read_syncpipe, release_startpipe,
+        vmmon_executable_from, StartupResult,
+    };
+    use crate::LibVmError;
+
+    #[test]
+    fn vmmon_executable_rejects_sibling_without_execute_permission() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let bento = dir.path().join("bento");
+        let vmmon = dir.path().join("vmmon");
+        std::fs::write(&vmmon, b"#!/bin/sh\n").expect("write vmmon");
+
+        std::fs::set_permissions(&vmmon, std::fs::Permissions::from_mode(0o644))
+            .expect("chmod 644");
+        let err = vmmon_executable_from(&bento, None).expect_err("non-executable vmmon");
+        std::fs::set_permissions(&vmmon, std::fs::Permissions::from_mode(0o755))
+            .expect("chmod 755");
+        let found = vmmon_executable_from(&bento, None).expect("executable vmmon");
+
+        assert!(matches!(
+            err,
+            LibVmError::VmMonExecutableNotExecutable { ref path } if path == &vmmon
+        ));
+        assert_eq!(found, vmmon);
+        assert!(matches!(
+            vmmon_executable_from(&dir.path().join("missing/bento"), None),
+            Err(LibVmError::VmMonExecutableNotFound { .. })
+        ));
+    }
 
     #[test]
     fn release_startpipe_writes_one_byte() {
@@ -265,7 +360,7 @@ mod tests {
         drop(write_file);
 
         assert!(matches!(
-            read_syncpipe(read_fd).expect("read syncpipe"),
+            read_syncpipe(read_fd, &mpsc::unbounded_channel().0).expect("read syncpipe"),
             StartupResult::Started
         ));
     }
@@ -280,11 +375,29 @@ mod tests {
         drop(write_file);
 
         assert!(matches!(
-            read_syncpipe(read_fd).expect("read syncpipe"),
+            read_syncpipe(read_fd, &mpsc::unbounded_channel().0).expect("read syncpipe"),
             StartupResult::Failed(message) if message == "krun exploded"
         ));
     }
 
+    #[test]
+    fn read_syncpipe_forwards_heartbeats_before_result() {
+        let (read_fd, write_fd) = pipe().expect("create pipe");
+        let mut write_file = std::fs::File::from(write_fd);
+        write_file
+            .write_all(b"heartbeat\nheartbeat\nstarted\n")
+            .expect("write messages");
+        drop(write_file);
+        let (heartbeat_tx, mut heartbeat_rx) = mpsc::unbounded_channel();
+
+        let result = read_syncpipe(read_fd, &heartbeat_tx).expect("read syncpipe");
+
+        assert!(matches!(result, StartupResult::Started));
+        assert_eq!(heartbeat_rx.try_recv(), Ok(()));
+        assert_eq!(heartbeat_rx.try_recv(), Ok(()));
+        assert!(heartbeat_rx.try_recv().is_err());
+    }
+
     #[test]
     fn append_exit_command_args_preserves_structured_argv() {
         let mut command = Command::new("vmmon");
@@ -323,4 +436,33 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn apply_machine_env_exports_name_and_dir() {
+        let mut command = Command::new("vmmon");
+
+        apply_machine_env(
+            &mut command,
+            "devbox",
+            Path::new("/tmp/bento/machines/devbox"),
+        );
+
+        let envs = command
+            .get_envs()
+            .map(|(key, value)| (key.to_os_string(), value.map(|value| value.to_os_string())))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            envs,
+            vec![
+                (
+                    OsString::from("BENTO_MACHINE"),
+                    Some(OsString::from("devbox"))
+                ),
+                (
+                    OsString::from("BENTO_MACHINE_DIR"),
+                    Some(OsString::from("/tmp/bento/machines/devbox"))
+                ),
+            ]
+        );
+    }
 }
@@ -111,8 +111,13 @@ mod tests {
             hardware: Some(Hardware {
                 cpus: Some(4),
                 memory: Some(4096),
+                min_memory: None,
                 nested_virtualization: Some(false),
                 rosetta: Some(false),
+                graphics: Some(false),
+                keyboard: Some(false),
+                entropy: Some(true),
+                memory_balloon: Some(true),
             }),
             storage: Some(Storage { disks: Vec::new() }),
             mounts: Vec::new(),
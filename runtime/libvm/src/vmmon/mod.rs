@@ -9,14 +9,17 @@
 use crate::paths::LocalPaths;
 use crate::store::models::MachineId;
 
+mod capabilities;
 mod client;
 pub(crate) mod exit_status;
 mod launch;
 mod launch_spec;
 pub(crate) mod process;
 
+pub use capabilities::{host_capabilities, HostCapabilities};
 pub(crate) use client::VmmonClient;
 pub use client::DEFAULT_GUEST_READINESS_TIMEOUT;
+pub use launch::vmmon_executable;
 pub(crate) use launch::VmmonLaunch;
 pub(crate) use launch_spec::{prepare_launch_spec, write_launch_spec, LaunchSpecInput};
 
@@ -33,6 +36,6 @@ impl Vmmon {
     }
 
     pub(crate) fn client(&self, machine_id: MachineId) -> VmmonClient {
-        VmmonClient::new(self.paths.machine(machine_id).vmmon_socket_path())
+        VmmonClient::new(self.paths.machine(machine_id).vmmon_client_socket_path())
     }
 }
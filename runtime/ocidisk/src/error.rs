@@ -60,6 +60,26 @@ pub enum OciDiskError {
         actual: u64,
     },
 
+    #[error("registry blob {digest} for image {reference:?} has digest sha256:{actual}")]
+    BlobDigestMismatch {
+        reference: String,
+        digest: String,
+        actual: String,
+    },
+
+    #[error(
+        "registry blob {digest} for image {reference:?} has {actual} bytes; expected {expected}"
+    )]
+    BlobSizeMismatch {
+        reference: String,
+        digest: String,
+        expected: u64,
+        actual: u64,
+    },
+
+    #[error("invalid image platform {platform:?}: {message}")]
+    InvalidPlatform { platform: String, message: String },
+
     #[error("image {reference:?} does not provide {requested}; available platforms: {available}")]
     MissingPlatform {
         reference: String,
@@ -90,6 +110,34 @@ pub enum OciDiskError {
         reason: &'static str,
     },
 
+    #[error("image or tag {reference:?} not found in the image cache")]
+    TagNotFound { reference: String },
+
+    #[error("image {reference:?} is still tagged as {}; pass --force to remove it with its tags", tags.join(", "))]
+    ImageInUse {
+        reference: String,
+        tags: Vec<String>,
+    },
+
+    #[error("image {reference:?} is the read-only base disk of {}; remove those VMs first", machines.join(", "))]
+    ImageBacksMachines {
+        reference: String,
+        machines: Vec<String>,
+    },
+
+    #[error("image id prefix {reference:?} matches several images: {}; use a longer prefix", candidates.join(", "))]
+    AmbiguousReference {
+        reference: String,
+        candidates: Vec<String>,
+    },
+
+    #[error("image index at {path} has version {version}, newer than supported version {supported}; upgrade bento to read it")]
+    UnsupportedIndexVersion {
+        path: PathBuf,
+        version: u32,
+        supported: u32,
+    },
+
     #[error("cache entry at {path} is corrupt: {reason}")]
     CorruptCacheEntry { path: PathBuf, reason: String },
 
@@ -5,12 +5,14 @@ use std::path::{Component, Path, PathBuf};
 
 use containerregistry_image::{ImageConfig, ImageIndex, Manifest, MediaType};
 
+use crate::store::ImageProvenance;
 use crate::{OciDiskError, OciDiskResult, Platform};
 
 pub(crate) struct LocalOciImage {
     pub(crate) manifest_digest: String,
     pub(crate) config_digest: String,
     pub(crate) layers: Vec<LocalOciLayer>,
+    pub(crate) provenance: ImageProvenance,
 }
 
 pub(crate) struct LocalOciLayer {
@@ -73,10 +75,15 @@ pub(crate) fn read_oci_archive(
             })
             .collect::<OciDiskResult<Vec<_>>>()?;
 
+        let annotations = match &manifest {
+            Manifest::Oci(manifest) => manifest.annotations.clone(),
+            Manifest::Docker(_) => BTreeMap::new(),
+        };
         return Ok(LocalOciImage {
             manifest_digest,
             config_digest,
             layers,
+            provenance: ImageProvenance::from_annotations(&annotations, config.created.as_deref()),
         });
     }
 
@@ -1,4 +1,5 @@
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
@@ -30,15 +31,26 @@ impl Platform {
     }
 
     pub fn host() -> OciDiskResult<Self> {
-        match std::env::consts::ARCH {
-            "x86_64" | "amd64" => Ok(Self::linux_amd64()),
-            "aarch64" | "arm64" => Ok(Self::linux_arm64()),
-            other => Err(OciDiskError::UnsupportedHostArchitecture {
-                arch: other.to_string(),
+        match Self::canonical_architecture(std::env::consts::ARCH) {
+            Some("amd64") => Ok(Self::linux_amd64()),
+            Some("arm64") => Ok(Self::linux_arm64()),
+            _ => Err(OciDiskError::UnsupportedHostArchitecture {
+                arch: std::env::consts::ARCH.to_string(),
             }),
         }
     }
 
+    /// Maps an architecture name to its OCI spelling, for example `aarch64` to `arm64`.
+    ///
+    /// Returns `None` for architectures Bento cannot run.
+    pub fn canonical_architecture(architecture: &str) -> Option<&'static str> {
+        match architecture.to_ascii_lowercase().as_str() {
+            "amd64" | "x86_64" | "x86-64" | "x64" => Some("amd64"),
+            "arm64" | "aarch64" => Some("arm64"),
+            _ => None,
+        }
+    }
+
     pub(crate) fn cache_key(&self) -> String {
         let mut key = format!("{}-{}", self.os, self.architecture);
         if let Some(variant) = &self.variant {
@@ -74,6 +86,40 @@ impl Display for Platform {
     }
 }
 
+impl FromStr for Platform {
+    type Err = OciDiskError;
+
+    /// Parses `os/architecture[/variant]`, for example `linux/arm64/v8`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = |message: &str| OciDiskError::InvalidPlatform {
+            platform: value.to_string(),
+            message: message.to_string(),
+        };
+        let mut parts = value.split('/');
+        let (Some(os), Some(architecture)) = (parts.next(), parts.next()) else {
+            return Err(invalid("expected os/architecture[/variant]"));
+        };
+        let variant = parts.next();
+        if parts.next().is_some() {
+            return Err(invalid("expected os/architecture[/variant]"));
+        }
+        if os.is_empty() || architecture.is_empty() || variant.is_some_and(str::is_empty) {
+            return Err(invalid("platform components must not be empty"));
+        }
+        let Some(architecture) = Self::canonical_architecture(architecture) else {
+            return Err(invalid(&format!(
+                "unsupported architecture {architecture:?}; expected amd64 or arm64"
+            )));
+        };
+
+        Ok(Self {
+            os: os.to_string(),
+            architecture: architecture.to_string(),
+            variant: variant.map(str::to_string),
+        })
+    }
+}
+
 pub(crate) fn sanitize_component(value: &str) -> String {
     value
         .chars()
@@ -98,4 +144,49 @@ mod tests {
         assert_eq!(platform.to_string(), "linux/arm64");
         assert_eq!(platform.cache_key(), "linux-arm64");
     }
+
+    #[test]
+    fn platform_parses_from_oci_names() {
+        let platform = "linux/arm64/v8"
+            .parse::<Platform>()
+            .expect("parse platform");
+
+        assert_eq!(platform.to_string(), "linux/arm64/v8");
+        assert_eq!(
+            "linux/amd64".parse::<Platform>().expect("parse platform"),
+            Platform::linux_amd64()
+        );
+        assert!("linux".parse::<Platform>().is_err());
+        assert!("linux/aarch46".parse::<Platform>().is_err());
+        assert!("linux//v8".parse::<Platform>().is_err());
+        assert!("linux/arm64/v8/extra".parse::<Platform>().is_err());
+    }
+
+    #[test]
+    fn canonical_architecture_normalizes_aliases() {
+        for (input, expected) in [
+            ("amd64", Some("amd64")),
+            ("x86_64", Some("amd64")),
+            ("x86-64", Some("amd64")),
+            ("X86_64", Some("amd64")),
+            ("arm64", Some("arm64")),
+            ("aarch64", Some("arm64")),
+            ("riscv64", None),
+            ("", None),
+        ] {
+            assert_eq!(Platform::canonical_architecture(input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn platform_parse_normalizes_architecture() {
+        assert_eq!(
+            "linux/aarch64".parse::<Platform>().expect("parse platform"),
+            Platform::linux_arm64()
+        );
+        assert_eq!(
+            "linux/x86_64".parse::<Platform>().expect("parse platform"),
+            Platform::linux_amd64()
+        );
+    }
 }
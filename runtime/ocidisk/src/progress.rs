@@ -64,6 +64,10 @@ pub enum ImageProgress {
         total: usize,
         digest: Option<String>,
     },
+    /// The rootfs was linked from a cached image built from the same layers.
+    ReusingRootfs {
+        image_id: String,
+    },
     WritingExt4,
     SavingBaseImage,
     Complete,
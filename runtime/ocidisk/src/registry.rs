@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use futures_util::TryStreamExt;
@@ -7,6 +8,7 @@ use oci_client::secrets::RegistryAuth;
 use oci_client::{Client, Reference};
 use serde::Deserialize;
 
+use crate::store::{digest_path_components, sha256_bytes, ImageProvenance};
 use crate::{OciDiskError, OciDiskResult, Platform};
 
 #[derive(Clone)]
@@ -19,6 +21,7 @@ pub(crate) struct ResolvedManifest {
     pub(crate) manifest_digest: String,
     pub(crate) config_digest: String,
     pub(crate) layers: Vec<ResolvedLayer>,
+    pub(crate) provenance: ImageProvenance,
 }
 
 #[derive(Debug, Clone)]
@@ -35,6 +38,8 @@ struct ImageConfig {
     os: String,
     #[serde(default)]
     variant: Option<String>,
+    #[serde(default)]
+    created: Option<String>,
     rootfs: RootfsConfig,
 }
 
@@ -119,12 +124,17 @@ impl RegistryClient {
         }
 
         let layers = resolved_layers(&requested_ref, &manifest, &config)?;
+        let provenance = ImageProvenance::from_annotations(
+            manifest.annotations.as_ref().unwrap_or(&BTreeMap::new()),
+            config.created.as_deref(),
+        );
 
         Ok(ResolvedManifest {
             reference: manifest_reference,
             manifest_digest,
             config_digest,
             layers,
+            provenance,
         })
     }
 
@@ -171,10 +181,41 @@ impl RegistryClient {
         while let Some(chunk) = stream.try_next().await? {
             bytes.extend_from_slice(&chunk);
         }
+        verify_blob_bytes(requested_ref, descriptor, &bytes)?;
         Ok(bytes)
     }
 }
 
+/// Checks an in-memory blob against the size and digest its descriptor declares.
+fn verify_blob_bytes(
+    reference: &str,
+    descriptor: &OciDescriptor,
+    bytes: &[u8],
+) -> OciDiskResult<()> {
+    let actual_size = bytes.len() as u64;
+    if let Ok(expected) = u64::try_from(descriptor.size) {
+        if actual_size != expected {
+            return Err(OciDiskError::BlobSizeMismatch {
+                reference: reference.to_string(),
+                digest: descriptor.digest.clone(),
+                expected,
+                actual: actual_size,
+            });
+        }
+    }
+
+    let (_, expected_digest) = digest_path_components(&descriptor.digest)?;
+    let actual_digest = sha256_bytes(bytes);
+    if actual_digest != expected_digest {
+        return Err(OciDiskError::BlobDigestMismatch {
+            reference: reference.to_string(),
+            digest: descriptor.digest.clone(),
+            actual: actual_digest,
+        });
+    }
+    Ok(())
+}
+
 fn resolved_layers(
     reference: &str,
     manifest: &OciImageManifest,
@@ -276,3 +317,55 @@ fn validate_config_platform(
         actual: actual.to_string(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use oci_client::manifest::OciDescriptor;
+
+    use crate::registry::verify_blob_bytes;
+    use crate::store::sha256_bytes;
+    use crate::OciDiskError;
+
+    fn descriptor_for(bytes: &[u8]) -> OciDescriptor {
+        OciDescriptor {
+            media_type: "application/vnd.oci.image.config.v1+json".to_string(),
+            digest: format!("sha256:{}", sha256_bytes(bytes)),
+            size: bytes.len() as i64,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn verify_blob_bytes_accepts_matching_blob() {
+        let descriptor = descriptor_for(b"{}");
+
+        verify_blob_bytes("alpine:latest", &descriptor, b"{}").expect("blob should verify");
+    }
+
+    #[test]
+    fn verify_blob_bytes_rejects_size_mismatch() {
+        let descriptor = descriptor_for(b"{}");
+
+        let err = verify_blob_bytes("alpine:latest", &descriptor, b"{ }")
+            .expect_err("size mismatch should fail");
+
+        assert!(matches!(
+            err,
+            OciDiskError::BlobSizeMismatch {
+                expected: 2,
+                actual: 3,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn verify_blob_bytes_rejects_digest_mismatch() {
+        let descriptor = descriptor_for(b"{}");
+
+        let err = verify_blob_bytes("alpine:latest", &descriptor, b"[]")
+            .expect_err("digest mismatch should fail");
+
+        assert!(matches!(err, OciDiskError::BlobDigestMismatch { .. }));
+    }
+}
@@ -1,7 +1,8 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::fs;
 use std::io::{BufReader, Cursor, Read};
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -27,12 +28,24 @@ use crate::{OciDiskError, OciDiskResult, Platform};
 const BLOBS_DIR_NAME: &str = "blobs";
 const METADATA_VERSION: u32 = 1;
 const DEFAULT_ROOTFS_SIZE_BYTES: u64 = 512 * 1024 * 1024;
+const IMPORTED_DISK_ID_PREFIX: &str = "disk-sha256";
 const INDEX_FILE_NAME: &str = "index.json";
+const INDEX_LOCK_FILE_NAME: &str = "index.lock";
+/// Every index this store has written is version 1.
 const INDEX_VERSION: u32 = 1;
+/// Index upgrade steps; entry `n - 1` moves an index from version `n` to
+/// `n + 1`. Empty until the index format changes.
+const INDEX_MIGRATIONS: &[IndexMigration] = &[];
 const MANIFESTS_DIR_NAME: &str = "manifests";
 const METADATA_FILE_NAME: &str = "metadata.json";
+const OVERLAY_TEMPLATE_FILE_NAME: &str = "overlay-template.img";
+const OVERLAY_TEMPLATE_LOCK_FILE_NAME: &str = "overlay-template.lock";
+const OVERLAY_TEMPLATE_SIZE_BYTES: u64 = 64 * 1024 * 1024;
 const PROGRESS_STEP_BYTES: u64 = 256 * 1024;
 const ROOTFS_FILE_NAME: &str = "rootfs.img";
+const ANNOTATION_CREATED: &str = "org.opencontainers.image.created";
+const ANNOTATION_SOURCE: &str = "org.opencontainers.image.source";
+const ANNOTATION_REVISION: &str = "org.opencontainers.image.revision";
 const ROOTFS_FILESYSTEM: &str = "ext4";
 const STAGING_DIR_NAME: &str = ".staging";
 const TMP_DIR_NAME: &str = "tmp";
@@ -90,6 +103,123 @@ impl Display for RootfsImageSource {
     }
 }
 
+/// Cached rootfs image as reported by [`ImageStore::list`] and [`ImageStore::inspect`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImageRecord {
+    pub image_id: String,
+    pub image_ref: String,
+    pub tags: Vec<String>,
+    pub source: RootfsImageSource,
+    pub platform: Platform,
+    pub manifest_digest: Option<String>,
+    pub config_digest: Option<String>,
+    pub layers: Vec<ImageLayerMetadata>,
+    pub filesystem: String,
+    pub rootfs_path: PathBuf,
+    /// Apparent size of the rootfs file.
+    pub size_bytes: u64,
+    /// Blocks actually allocated for the sparse rootfs file.
+    pub disk_usage_bytes: u64,
+    pub created_at_unix: i64,
+    pub provenance: ImageProvenance,
+}
+
+/// Outcome of [`ImageStore::remove`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageRemoval {
+    /// Only the tag went away; the image is still cached under its other tags.
+    Untagged {
+        tag: String,
+        image_id: String,
+        remaining_tags: Vec<String>,
+    },
+    /// The cached image and every tag pointing at it were deleted.
+    Removed { image_id: String, tags: Vec<String> },
+}
+
+/// Where an image was built from, taken from its OCI annotations.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageProvenance {
+    /// `org.opencontainers.image.created`, falling back to the config `created` time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    /// `org.opencontainers.image.source`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// `org.opencontainers.image.revision`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+}
+
+impl ImageProvenance {
+    pub(crate) fn from_annotations(
+        annotations: &BTreeMap<String, String>,
+        config_created: Option<&str>,
+    ) -> Self {
+        let annotation = |key: &str| {
+            annotations
+                .get(key)
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            created: annotation(ANNOTATION_CREATED).or_else(|| config_created.map(str::to_string)),
+            source: annotation(ANNOTATION_SOURCE),
+            revision: annotation(ANNOTATION_REVISION),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.created.is_none() && self.source.is_none() && self.revision.is_none()
+    }
+}
+
+/// Disk space used by the image store, as reported by [`ImageStore::disk_usage`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StoreDiskUsage {
+    pub images: Vec<ImageDiskUsage>,
+    /// Layer blobs, resolved manifests, and partial downloads shared by all images.
+    pub layer_cache_bytes: u64,
+    pub index_bytes: u64,
+    /// Leftovers that [`ImageStore::gc_orphans`] would remove.
+    pub orphans: Vec<OrphanDiskUsage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImageDiskUsage {
+    pub image_ref: String,
+    pub image_id: String,
+    pub platform: Platform,
+    /// Blocks allocated for the image rootfs.
+    pub bytes: u64,
+    /// Whether the rootfs is a hard link to one already counted for an earlier
+    /// image, in which case it adds nothing to [`StoreDiskUsage::total_bytes`].
+    pub shared: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrphanDiskUsage {
+    pub path: PathBuf,
+    pub bytes: u64,
+}
+
+impl StoreDiskUsage {
+    pub fn total_bytes(&self) -> u64 {
+        self.images
+            .iter()
+            .filter(|image| !image.shared)
+            .map(|image| image.bytes)
+            .sum::<u64>()
+            + self.layer_cache_bytes
+            + self.index_bytes
+            + self.orphans.iter().map(|orphan| orphan.bytes).sum::<u64>()
+    }
+}
+
+/// One index upgrade step, applied to the raw JSON document.
+type IndexMigration = fn(&mut serde_json::Map<String, serde_json::Value>);
+
 #[derive(Debug, Clone)]
 pub struct ImageStore {
     root: PathBuf,
@@ -135,14 +265,16 @@ struct ImageMetadata {
     filesystem: String,
     rootfs_file: String,
     created_at_unix: i64,
+    #[serde(default, skip_serializing_if = "ImageProvenance::is_empty")]
+    provenance: ImageProvenance,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ImageLayerMetadata {
-    digest: String,
-    media_type: String,
-    size_bytes: u64,
-    diff_id: String,
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageLayerMetadata {
+    pub digest: String,
+    pub media_type: String,
+    pub size_bytes: u64,
+    pub diff_id: String,
 }
 
 impl From<&ResolvedLayer> for ImageLayerMetadata {
@@ -192,6 +324,7 @@ struct ImageMetadataInput<'a> {
     config_digest: Option<&'a str>,
     layers: &'a [ImageLayerMetadata],
     platform: &'a Platform,
+    provenance: &'a ImageProvenance,
 }
 
 struct LayerStreamWrite<'a> {
@@ -218,6 +351,7 @@ impl ImageStore {
         options: RootfsOptions,
         progress: Option<ImageProgressSender>,
     ) -> OciDiskResult<RootfsImage> {
+        validate_platform(&options.platform)?;
         match ImageSource::parse(image_ref)? {
             ImageSource::RemoteOci(image_ref) => {
                 self.get_or_create_remote_oci(&image_ref, options, progress.as_ref())
@@ -235,6 +369,427 @@ impl ImageStore {
         }
     }
 
+    /// Resolves `image_ref` like [`ImageStore::get_or_create`] without pulling,
+    /// converting or writing anything.
+    ///
+    /// Local disks are used in place. Other references resolve only when the
+    /// image is already cached for `options.platform`.
+    pub fn get_cached(
+        &self,
+        image_ref: &str,
+        options: RootfsOptions,
+    ) -> OciDiskResult<Option<RootfsImage>> {
+        validate_platform(&options.platform)?;
+        if let ImageSource::LocalDisk(path) = ImageSource::parse(image_ref)? {
+            return self.local_disk(image_ref, path, options, None).map(Some);
+        }
+        let Some(record) = self.inspect(image_ref)? else {
+            return Ok(None);
+        };
+        if record.platform != options.platform {
+            return Ok(None);
+        }
+        Ok(Some(RootfsImage {
+            path: record.rootfs_path,
+            image_ref: record.image_ref,
+            image_id: record.image_id,
+            platform: record.platform,
+            source: record.source,
+        }))
+    }
+
+    /// Lists every cached rootfs image, sorted by reference and platform.
+    ///
+    /// Entries with missing or unreadable metadata are skipped.
+    pub fn list(&self) -> OciDiskResult<Vec<ImageRecord>> {
+        let index = self.read_index()?;
+        let mut records = Vec::new();
+        for image_dir in image_id_dirs(&self.root)? {
+            for entry in fs::read_dir(&image_dir)? {
+                let platform_dir = entry?.path();
+                if !platform_dir.is_dir() {
+                    continue;
+                }
+                match image_record(&platform_dir, &index) {
+                    Ok(Some(record)) => records.push(record),
+                    Ok(None) => {}
+                    Err(err) => {
+                        tracing::debug!(path = %platform_dir.display(), error = %err, "skipping unreadable image cache entry");
+                    }
+                }
+            }
+        }
+        records.sort_by(|left, right| {
+            (&left.image_ref, left.platform.cache_key())
+                .cmp(&(&right.image_ref, right.platform.cache_key()))
+        });
+        Ok(records)
+    }
+
+    /// Finds a cached image by tag, image reference, image id, or image id prefix.
+    ///
+    /// Exact matches win over id prefixes. A prefix shared by several image ids
+    /// fails with [`OciDiskError::AmbiguousReference`]. When the image is cached
+    /// for several platforms the host platform wins.
+    pub fn inspect(&self, reference: &str) -> OciDiskResult<Option<ImageRecord>> {
+        let canonical = canonical_image_ref(reference);
+        let records = self.list()?;
+        let (mut matches, prefixed) = records.into_iter().fold(
+            (Vec::new(), Vec::new()),
+            |(mut exact, mut prefixed), record| {
+                if record_matches(&record, reference, canonical.as_deref()) {
+                    exact.push(record);
+                } else if image_id_has_prefix(&record.image_id, reference) {
+                    prefixed.push(record);
+                }
+                (exact, prefixed)
+            },
+        );
+        if matches.is_empty() {
+            let mut candidates = prefixed
+                .iter()
+                .map(|record| record.image_id.clone())
+                .collect::<Vec<_>>();
+            candidates.sort();
+            candidates.dedup();
+            if candidates.len() > 1 {
+                return Err(OciDiskError::AmbiguousReference {
+                    reference: reference.to_string(),
+                    candidates,
+                });
+            }
+            matches = prefixed;
+        }
+        let host = Platform::host().ok();
+        if let Some(position) =
+            host.and_then(|host| matches.iter().position(|record| record.platform == host))
+        {
+            return Ok(Some(matches.swap_remove(position)));
+        }
+        Ok(matches.into_iter().next())
+    }
+
+    /// Copies a raw ext4 disk image into the cache and tags it as `name`.
+    ///
+    /// zstd-compressed disks are detected by their magic bytes and decompressed.
+    /// The image id hashes the source file, so importing the same file again
+    /// only moves the tag. Pulling `name` afterwards resolves to the imported disk
+    /// without contacting a registry.
+    pub fn import_disk(
+        &self,
+        path: &Path,
+        name: &str,
+        platform: &Platform,
+    ) -> OciDiskResult<ImageRecord> {
+        validate_platform(platform)?;
+        let reference = format!("disk:{}", path.display());
+        let path = canonical_local_file(&reference, path)?;
+        let tag = RegistryClient::parse_reference(name)?.to_string();
+        fs::create_dir_all(&self.root)?;
+        let image_id = format!("{IMPORTED_DISK_ID_PREFIX}:{}", sha256_file(&path)?);
+
+        let _image_lock = FileLock::exclusive(&self.image_lock_path(&image_id, platform)?)?;
+        let cached = self.cached_image(&tag, &image_id, platform, RootfsImageSource::Disk)?;
+        if cached.is_none() {
+            let final_dir = self.image_dir(&image_id, platform)?;
+            let staging = StagingDir::create(&self.root)?;
+            let stage_rootfs = staging.path().join(ROOTFS_FILE_NAME);
+            if is_zstd_file(&path)? {
+                let mut decoder = zstd::Decoder::new(fs::File::open(&path)?)?;
+                let mut rootfs = fs::File::create(&stage_rootfs)?;
+                std::io::copy(&mut decoder, &mut rootfs)?;
+                rootfs.sync_all()?;
+            } else {
+                fs::copy(&path, &stage_rootfs)?;
+            }
+            self.write_metadata(
+                staging.path(),
+                ImageMetadataInput {
+                    image_ref: &tag,
+                    image_id: &image_id,
+                    source: RootfsImageSource::Disk,
+                    manifest_digest: None,
+                    config_digest: None,
+                    layers: &[],
+                    platform,
+                    provenance: &ImageProvenance::default(),
+                },
+            )?;
+            if let Some(parent) = final_dir.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(staging.path(), &final_dir)?;
+            staging.disarm();
+        }
+        self.update_tag_mapping(&tag, platform, &image_id)?;
+        self.inspect(&image_id)?
+            .ok_or(OciDiskError::TagNotFound { reference: tag })
+    }
+
+    /// Returns an empty ext4 disk to clone as the writable layer of an overlay
+    /// root disk, formatting it on first use.
+    ///
+    /// The template is small; the guest grows the filesystem to the size of the
+    /// disk it was cloned into.
+    pub fn overlay_template(&self) -> OciDiskResult<PathBuf> {
+        let path = self.overlay_template_path();
+        if path.is_file() {
+            return Ok(path);
+        }
+        let tmp_dir = self.root.join(TMP_DIR_NAME);
+        let _lock = FileLock::exclusive(&tmp_dir.join(OVERLAY_TEMPLATE_LOCK_FILE_NAME))?;
+        if path.is_file() {
+            return Ok(path);
+        }
+        let temp_path = tmp_dir.join(format!("{OVERLAY_TEMPLATE_FILE_NAME}.part"));
+        Ext4Writer::create(&temp_path, OVERLAY_TEMPLATE_SIZE_BYTES)?.finish()?;
+        fs::rename(temp_path, &path)?;
+        Ok(path)
+    }
+
+    /// Where [`ImageStore::overlay_template`] keeps the template, whether or not
+    /// it exists yet.
+    pub fn overlay_template_path(&self) -> PathBuf {
+        self.root.join(OVERLAY_TEMPLATE_FILE_NAME)
+    }
+
+    /// Removes cache directories that no longer hold a usable image.
+    ///
+    /// This covers image directories left without metadata by a crashed
+    /// conversion, and staging directories whose owning process has exited.
+    /// Image directories with unreadable metadata are kept. Each image
+    /// directory is re-checked under its image lock before it is deleted, so
+    /// a concurrent pull or removal of the same image is never raced. Returns
+    /// the removed paths. Never runs implicitly.
+    pub fn gc_orphans(&self) -> OciDiskResult<Vec<PathBuf>> {
+        let mut removed = Vec::new();
+        for path in self.orphan_dirs()? {
+            let _image_lock = match self.image_dir_lock_path(&path) {
+                Some(lock_path) => {
+                    let lock = FileLock::exclusive(&lock_path)?;
+                    if !is_orphan_image_dir(&path, &self.read_index()?) {
+                        continue;
+                    }
+                    Some(lock)
+                }
+                None => None,
+            };
+            match fs::remove_dir_all(&path) {
+                Ok(()) => removed.push(path),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        for image_dir in image_id_dirs(&self.root)? {
+            if fs::read_dir(&image_dir)?.next().is_some() {
+                continue;
+            }
+            match fs::remove_dir(&image_dir) {
+                Ok(()) => removed.push(image_dir),
+                Err(err)
+                    if matches!(
+                        err.kind(),
+                        std::io::ErrorKind::NotFound | std::io::ErrorKind::DirectoryNotEmpty
+                    ) => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Reports how much disk each cached image, the shared layer cache, the
+    /// index, and every orphaned directory [`ImageStore::gc_orphans`] would
+    /// remove take up.
+    pub fn disk_usage(&self) -> OciDiskResult<StoreDiskUsage> {
+        let mut rootfs_inodes = HashSet::new();
+        let images = self
+            .list()?
+            .into_iter()
+            .map(|record| {
+                let rootfs = fs::metadata(&record.rootfs_path)?;
+                Ok(ImageDiskUsage {
+                    image_ref: record.image_ref,
+                    image_id: record.image_id,
+                    platform: record.platform,
+                    bytes: record.disk_usage_bytes,
+                    shared: !rootfs_inodes.insert((rootfs.dev(), rootfs.ino())),
+                })
+            })
+            .collect::<OciDiskResult<Vec<_>>>()?;
+        let mut layer_cache_bytes = 0;
+        for name in [
+            BLOBS_DIR_NAME,
+            MANIFESTS_DIR_NAME,
+            TMP_DIR_NAME,
+            OVERLAY_TEMPLATE_FILE_NAME,
+        ] {
+            layer_cache_bytes += path_disk_usage(&self.root.join(name))?;
+        }
+        let orphans = self
+            .orphan_dirs()?
+            .into_iter()
+            .map(|path| {
+                let bytes = path_disk_usage(&path)?;
+                Ok(OrphanDiskUsage { path, bytes })
+            })
+            .collect::<OciDiskResult<Vec<_>>>()?;
+
+        Ok(StoreDiskUsage {
+            images,
+            layer_cache_bytes,
+            index_bytes: path_disk_usage(&self.root.join(INDEX_FILE_NAME))?,
+            orphans,
+        })
+    }
+
+    /// Image directories without metadata, and staging directories whose
+    /// owning process has exited.
+    fn orphan_dirs(&self) -> OciDiskResult<Vec<PathBuf>> {
+        let index = self.read_index()?;
+        let mut orphans = Vec::new();
+        for image_dir in image_id_dirs(&self.root)? {
+            for entry in fs::read_dir(&image_dir)? {
+                let platform_dir = entry?.path();
+                if platform_dir.is_dir() && is_orphan_image_dir(&platform_dir, &index) {
+                    orphans.push(platform_dir);
+                }
+            }
+        }
+
+        let staging_root = self.root.join(STAGING_DIR_NAME);
+        let entries = match fs::read_dir(&staging_root) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(orphans),
+            Err(err) => return Err(err.into()),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if staging_owner_is_alive(&path) {
+                continue;
+            }
+            orphans.push(path);
+        }
+        Ok(orphans)
+    }
+
+    /// Points `new_tag` at the image `existing` resolves to.
+    ///
+    /// Tags are normalized like registry references, so `dev` is stored as
+    /// `docker.io/library/dev:latest`. Re-tagging replaces the previous target.
+    pub fn tag(&self, existing: &str, new_tag: &str) -> OciDiskResult<ImageRecord> {
+        let record = self
+            .inspect(existing)?
+            .ok_or_else(|| OciDiskError::TagNotFound {
+                reference: existing.to_string(),
+            })?;
+        let tag = RegistryClient::parse_reference(new_tag)?.to_string();
+        self.update_tag_mapping(&tag, &record.platform, &record.image_id)?;
+        self.inspect(&record.image_id)?
+            .ok_or_else(|| OciDiskError::TagNotFound {
+                reference: existing.to_string(),
+            })
+    }
+
+    /// Removes `tag` for every platform while leaving the cached image in place.
+    pub fn untag(&self, tag: &str) -> OciDiskResult<()> {
+        let canonical = canonical_image_ref(tag);
+        let _index_lock = self.lock_index()?;
+        let mut index = self.read_index()?;
+        let before = index.tags.len();
+        index.tags.retain(|_, record| {
+            record.image_ref != tag && canonical.as_deref() != Some(record.image_ref.as_str())
+        });
+        if index.tags.len() == before {
+            return Err(OciDiskError::TagNotFound {
+                reference: tag.to_string(),
+            });
+        }
+        self.write_index(&index)
+    }
+
+    /// Removes a cached image or one of its tags.
+    ///
+    /// Removing a tag that shares its image with other tags only drops that tag.
+    /// Removing the last tag deletes the image. Removing by image id while tags
+    /// still point at it fails with [`OciDiskError::ImageInUse`] unless `force`
+    /// is set, which also deletes the image behind a shared tag.
+    pub fn remove(&self, reference: &str, force: bool) -> OciDiskResult<ImageRemoval> {
+        self.remove_unless_attached(reference, force, &[])
+    }
+
+    /// Removes like [`ImageStore::remove`], but fails with
+    /// [`OciDiskError::ImageBacksMachines`] instead of deleting the image while
+    /// `machines` still attach its rootfs. Dropping one of several tags still
+    /// succeeds because the image itself stays.
+    pub fn remove_unless_attached(
+        &self,
+        reference: &str,
+        force: bool,
+        machines: &[String],
+    ) -> OciDiskResult<ImageRemoval> {
+        let record = self
+            .inspect(reference)?
+            .ok_or_else(|| OciDiskError::TagNotFound {
+                reference: reference.to_string(),
+            })?;
+        let canonical = canonical_image_ref(reference);
+        let tag = record
+            .tags
+            .iter()
+            .find(|tag| *tag == reference || canonical.as_deref() == Some(tag.as_str()))
+            .cloned();
+
+        if !force {
+            match &tag {
+                Some(tag) if record.tags.len() > 1 => {
+                    let _index_lock = self.lock_index()?;
+                    let mut index = self.read_index()?;
+                    index.tags.remove(&tag_key(tag, &record.platform));
+                    self.write_index(&index)?;
+                    return Ok(ImageRemoval::Untagged {
+                        tag: tag.clone(),
+                        image_id: record.image_id,
+                        remaining_tags: record.tags.into_iter().filter(|t| t != tag).collect(),
+                    });
+                }
+                None if !record.tags.is_empty() => {
+                    return Err(OciDiskError::ImageInUse {
+                        reference: reference.to_string(),
+                        tags: record.tags,
+                    });
+                }
+                _ => {}
+            }
+        }
+        if !machines.is_empty() {
+            return Err(OciDiskError::ImageBacksMachines {
+                reference: reference.to_string(),
+                machines: machines.to_vec(),
+            });
+        }
+
+        let _image_lock =
+            FileLock::exclusive(&self.image_lock_path(&record.image_id, &record.platform)?)?;
+        let index_lock = self.lock_index()?;
+        let mut index = self.read_index()?;
+        index.tags.retain(|_, tag| {
+            tag.manifest_digest != record.image_id || tag.platform != record.platform
+        });
+        self.write_index(&index)?;
+        drop(index_lock);
+        let dir = self.image_dir(&record.image_id, &record.platform)?;
+        fs::remove_dir_all(&dir)?;
+        if let Some(parent) = dir.parent() {
+            if fs::read_dir(parent)?.next().is_none() {
+                fs::remove_dir(parent)?;
+            }
+        }
+        Ok(ImageRemoval::Removed {
+            image_id: record.image_id,
+            tags: record.tags,
+        })
+    }
+
     async fn get_or_create_remote_oci(
         &self,
         image_ref: &str,
@@ -246,6 +801,17 @@ impl ImageStore {
         let reference = RegistryClient::parse_reference(image_ref)?;
         let canonical_ref = reference.to_string();
         let maps_to_tag = reference.digest().is_none();
+        if maps_to_tag {
+            if let Some(image) = self.imported_disk(&canonical_ref, &options.platform)? {
+                emit_progress(
+                    progress,
+                    ImageProgress::CacheHit {
+                        image_ref: canonical_ref,
+                    },
+                );
+                return Ok(image);
+            }
+        }
         let registry = RegistryClient::new()?;
         emit_progress(
             progress,
@@ -341,29 +907,31 @@ impl ImageStore {
             }
         }
 
-        let blobs = self
-            .ensure_layer_blobs(registry, image_ref, resolved, progress)
-            .await?;
         let staging = StagingDir::create(&self.root)?;
         let stage_rootfs = staging.path().join(ROOTFS_FILE_NAME);
-        let mut writer = Ext4Writer::create(&stage_rootfs, options.disk_size_bytes)?;
+        if !self.link_shared_rootfs(&resolved.layers, options, &stage_rootfs, progress)? {
+            let blobs = self
+                .ensure_layer_blobs(registry, image_ref, resolved, progress)
+                .await?;
+            let mut writer = Ext4Writer::create(&stage_rootfs, options.disk_size_bytes)?;
+
+            let total = blobs.len();
+            for blob in &blobs {
+                emit_progress(
+                    progress,
+                    ImageProgress::ApplyingLayer {
+                        index: blob.index,
+                        total,
+                        digest: Some(blob.layer.digest.clone()),
+                    },
+                );
+                let reader = layer_reader_from_path(&blob.layer.media_type, &blob.path)?;
+                apply_layer(reader, &mut writer)?;
+            }
 
-        let total = blobs.len();
-        for blob in &blobs {
-            emit_progress(
-                progress,
-                ImageProgress::ApplyingLayer {
-                    index: blob.index,
-                    total,
-                    digest: Some(blob.layer.digest.clone()),
-                },
-            );
-            let reader = layer_reader_from_path(&blob.layer.media_type, &blob.path)?;
-            apply_layer(reader, &mut writer)?;
+            emit_progress(progress, ImageProgress::WritingExt4);
+            writer.finish()?;
         }
-
-        emit_progress(progress, ImageProgress::WritingExt4);
-        writer.finish()?;
         emit_progress(progress, ImageProgress::SavingBaseImage);
         let layers = resolved
             .layers
@@ -380,6 +948,7 @@ impl ImageStore {
                 config_digest: Some(&resolved.config_digest),
                 layers: &layers,
                 platform: &options.platform,
+                provenance: &resolved.provenance,
             },
         )?;
 
@@ -414,6 +983,63 @@ impl ImageStore {
         })
     }
 
+    /// Hard-links the rootfs of a cached image built from the same layers into `stage_rootfs`.
+    ///
+    /// Cached rootfs files are never modified in place, so images whose manifests
+    /// differ only outside the layer list can share one file. Returns false when
+    /// no such image exists or linking fails, leaving the caller to build it.
+    fn link_shared_rootfs(
+        &self,
+        layers: &[ResolvedLayer],
+        options: &RootfsOptions,
+        stage_rootfs: &Path,
+        progress: Option<&ImageProgressSender>,
+    ) -> OciDiskResult<bool> {
+        let Some(shared) =
+            self.image_with_layers(layers, &options.platform, options.disk_size_bytes)?
+        else {
+            return Ok(false);
+        };
+        match fs::hard_link(&shared.rootfs_path, stage_rootfs) {
+            Ok(()) => {
+                emit_progress(
+                    progress,
+                    ImageProgress::ReusingRootfs {
+                        image_id: shared.image_id,
+                    },
+                );
+                Ok(true)
+            }
+            Err(err) => {
+                tracing::debug!(path = %shared.rootfs_path.display(), error = %err, "could not link shared rootfs, rebuilding it");
+                Ok(false)
+            }
+        }
+    }
+
+    /// Finds a cached registry image with exactly `layers`, in order, for `platform`.
+    fn image_with_layers(
+        &self,
+        layers: &[ResolvedLayer],
+        platform: &Platform,
+        disk_size_bytes: u64,
+    ) -> OciDiskResult<Option<ImageRecord>> {
+        if layers.is_empty() {
+            return Ok(None);
+        }
+        Ok(self.list()?.into_iter().find(|record| {
+            record.source == RootfsImageSource::OciRegistry
+                && &record.platform == platform
+                && record.size_bytes == disk_size_bytes
+                && record.layers.len() == layers.len()
+                && record
+                    .layers
+                    .iter()
+                    .zip(layers)
+                    .all(|(cached, layer)| cached.digest == layer.digest)
+        }))
+    }
+
     fn local_disk(
         &self,
         image_ref: &str,
@@ -448,9 +1074,9 @@ impl ImageStore {
         options: RootfsOptions,
         progress: Option<&ImageProgressSender>,
     ) -> OciDiskResult<RootfsImage> {
-        fs::create_dir_all(&self.root)?;
         let path = canonical_local_file(image_ref, &path)?;
         reject_known_tar_compression(image_ref, &path)?;
+        fs::create_dir_all(&self.root)?;
         emit_progress(
             progress,
             ImageProgress::HashingSource {
@@ -513,6 +1139,7 @@ impl ImageStore {
                 config_digest: None,
                 layers: &[],
                 platform: &options.platform,
+                provenance: &ImageProvenance::default(),
             },
         )?;
 
@@ -554,8 +1181,8 @@ impl ImageStore {
         options: RootfsOptions,
         progress: Option<&ImageProgressSender>,
     ) -> OciDiskResult<RootfsImage> {
-        fs::create_dir_all(&self.root)?;
         let path = canonical_local_file(image_ref, &path)?;
+        fs::create_dir_all(&self.root)?;
         emit_progress(
             progress,
             ImageProgress::ReadingArchive {
@@ -622,6 +1249,7 @@ impl ImageStore {
                 config_digest: Some(&archive.config_digest),
                 layers: &[],
                 platform: &options.platform,
+                provenance: &archive.provenance,
             },
         )?;
 
@@ -927,6 +1555,30 @@ impl ImageStore {
         }))
     }
 
+    /// Returns the disk `import_disk` tagged as `image_ref`, if any.
+    fn imported_disk(
+        &self,
+        image_ref: &str,
+        platform: &Platform,
+    ) -> OciDiskResult<Option<RootfsImage>> {
+        let index = self.read_index()?;
+        let Some(record) = index.tags.get(&tag_key(image_ref, platform)) else {
+            return Ok(None);
+        };
+        if !record
+            .manifest_digest
+            .starts_with(&format!("{IMPORTED_DISK_ID_PREFIX}:"))
+        {
+            return Ok(None);
+        }
+        self.cached_image(
+            image_ref,
+            &record.manifest_digest,
+            platform,
+            RootfsImageSource::Disk,
+        )
+    }
+
     fn image_dir(&self, image_id: &str, platform: &Platform) -> OciDiskResult<PathBuf> {
         Ok(self
             .root
@@ -942,6 +1594,22 @@ impl ImageStore {
         )))
     }
 
+    /// The image lock guarding `dir` when it is an `<image id>/<platform>` cache
+    /// directory, derived the same way as [`ImageStore::image_lock_path`].
+    fn image_dir_lock_path(&self, dir: &Path) -> Option<PathBuf> {
+        let image_dir = dir.parent()?;
+        if image_dir.parent()? != self.root {
+            return None;
+        }
+        let image_id = image_dir.file_name()?.to_str()?;
+        let platform = dir.file_name()?.to_str()?;
+        Some(
+            self.root
+                .join(TMP_DIR_NAME)
+                .join(format!("image-{image_id}-{platform}.lock")),
+        )
+    }
+
     fn blob_path(&self, digest: &str) -> OciDiskResult<PathBuf> {
         let (algorithm, encoded) = digest_path_components(digest)?;
         Ok(self.root.join(BLOBS_DIR_NAME).join(algorithm).join(encoded))
@@ -983,6 +1651,7 @@ impl ImageStore {
             filesystem: ROOTFS_FILESYSTEM.to_string(),
             rootfs_file: ROOTFS_FILE_NAME.to_string(),
             created_at_unix: now_unix(),
+            provenance: input.provenance.clone(),
         };
         let data = serde_json::to_vec_pretty(&metadata)?;
         fs::write(dir.join(METADATA_FILE_NAME), data)?;
@@ -1024,6 +1693,7 @@ impl ImageStore {
         platform: &Platform,
         manifest_digest: &str,
     ) -> OciDiskResult<()> {
+        let _index_lock = self.lock_index()?;
         let mut index = self.read_index()?;
         index.tags.insert(
             tag_key(image_ref, platform),
@@ -1039,28 +1709,27 @@ impl ImageStore {
 
     fn read_index(&self) -> OciDiskResult<StoreIndex> {
         let path = self.root.join(INDEX_FILE_NAME);
-        match fs::read(&path) {
-            Ok(data) => {
-                let index = serde_json::from_slice::<StoreIndex>(&data).map_err(|err| {
-                    OciDiskError::CorruptCacheEntry {
-                        path: path.clone(),
-                        reason: err.to_string(),
-                    }
-                })?;
-                if index.version != INDEX_VERSION {
-                    return Err(OciDiskError::CorruptCacheEntry {
-                        path,
-                        reason: format!(
-                            "index version {} does not match expected version {INDEX_VERSION}",
-                            index.version
-                        ),
-                    });
-                }
-                Ok(index)
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(StoreIndex::default())
             }
-            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(StoreIndex::default()),
-            Err(err) => Err(err.into()),
-        }
+            Err(err) => return Err(err.into()),
+        };
+        let corrupt = |reason: String| OciDiskError::CorruptCacheEntry {
+            path: path.clone(),
+            reason,
+        };
+        let raw = serde_json::from_slice::<serde_json::Value>(&data)
+            .map_err(|err| corrupt(err.to_string()))?;
+        let raw = migrate_index(raw, &path)?;
+        serde_json::from_value::<StoreIndex>(raw).map_err(|err| corrupt(err.to_string()))
+    }
+
+    /// Serializes index read-modify-write cycles across processes. Callers
+    /// that also hold an image lock take it first.
+    fn lock_index(&self) -> OciDiskResult<FileLock> {
+        FileLock::exclusive(&self.root.join(TMP_DIR_NAME).join(INDEX_LOCK_FILE_NAME))
     }
 
     fn write_index(&self, index: &StoreIndex) -> OciDiskResult<()> {
@@ -1073,50 +1742,252 @@ impl ImageStore {
     }
 }
 
-fn emit_progress(progress: Option<&ImageProgressSender>, event: ImageProgress) {
-    if let Some(progress) = progress {
-        progress.send(event);
+/// Returns the per-image directories under the store root, skipping store bookkeeping.
+fn image_id_dirs(root: &Path) -> OciDiskResult<Vec<PathBuf>> {
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut dirs = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name();
+        if is_reserved_store_entry(&name.to_string_lossy()) || !entry.file_type()?.is_dir() {
+            continue;
+        }
+        dirs.push(entry.path());
     }
+    Ok(dirs)
 }
 
-fn total_download_bytes(layers: &[ResolvedLayer]) -> Option<u64> {
-    layers
-        .iter()
-        .try_fold(0_u64, |total, layer| total.checked_add(layer.size_bytes))
+/// Blocks allocated under `path`, or zero when it does not exist. Symlinks are not followed.
+fn path_disk_usage(path: &Path) -> OciDiskResult<u64> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err.into()),
+    };
+    let mut bytes = metadata.blocks().saturating_mul(512);
+    if metadata.is_dir() {
+        for entry in fs::read_dir(path)? {
+            bytes = bytes.saturating_add(path_disk_usage(&entry?.path())?);
+        }
+    }
+    Ok(bytes)
 }
 
-fn layer_download_concurrency(layer_count: usize) -> usize {
-    if layer_count == 0 {
-        return 1;
+fn is_reserved_store_entry(name: &str) -> bool {
+    matches!(
+        name,
+        BLOBS_DIR_NAME
+            | MANIFESTS_DIR_NAME
+            | TMP_DIR_NAME
+            | STAGING_DIR_NAME
+            | INDEX_FILE_NAME
+            | OVERLAY_TEMPLATE_FILE_NAME
+    ) || name.starts_with(INDEX_FILE_NAME)
+}
+
+fn image_record(dir: &Path, index: &StoreIndex) -> OciDiskResult<Option<ImageRecord>> {
+    let metadata_path = dir.join(METADATA_FILE_NAME);
+    if !metadata_path.is_file() {
+        return Ok(None);
+    }
+    let metadata = read_metadata(&metadata_path)?;
+    let rootfs_path = dir.join(&metadata.rootfs_file);
+    let rootfs = fs::metadata(&rootfs_path)?;
+    let tags = index
+        .tags
+        .values()
+        .filter(|tag| tag.manifest_digest == metadata.image_id && tag.platform == metadata.platform)
+        .map(|tag| tag.image_ref.clone())
+        .collect();
+
+    Ok(Some(ImageRecord {
+        image_id: metadata.image_id,
+        image_ref: metadata.image_ref,
+        tags,
+        source: metadata.source,
+        platform: metadata.platform,
+        manifest_digest: metadata.manifest_digest,
+        config_digest: metadata.config_digest,
+        layers: metadata.layers,
+        filesystem: metadata.filesystem,
+        rootfs_path,
+        size_bytes: rootfs.len(),
+        disk_usage_bytes: rootfs.blocks().saturating_mul(512),
+        created_at_unix: metadata.created_at_unix,
+        provenance: metadata.provenance,
+    }))
+}
+
+/// Only a directory without metadata counts as an orphan. Metadata that cannot
+/// be read, parsed or understood may belong to a newer store version or be
+/// recoverable, so those directories are reported and kept.
+fn is_orphan_image_dir(dir: &Path, index: &StoreIndex) -> bool {
+    match image_record(dir, index) {
+        Ok(None) => true,
+        Ok(Some(_)) => false,
+        Err(err) => {
+            tracing::warn!(path = %dir.display(), error = %err, "keeping image cache entry with unreadable metadata");
+            false
+        }
     }
-    let host_limit = std::thread::available_parallelism()
-        .map(usize::from)
-        .unwrap_or(4)
-        .saturating_mul(2)
-        .clamp(4, 16);
-    layer_count.min(host_limit)
 }
 
-async fn write_layer_stream(request: LayerStreamWrite<'_>) -> OciDiskResult<()> {
-    let LayerStreamWrite {
-        path,
-        mut stream,
-        append,
-        start_offset,
-        layer,
-        index,
-        total,
-        progress,
-    } = request;
-    let mut file = tokio::fs::OpenOptions::new()
-        .create(true)
-        .write(true)
-        .append(append)
-        .truncate(!append)
-        .open(path)
-        .await?;
-    let mut downloaded = start_offset;
-    let mut last_progress = start_offset;
+/// Staging directories are named `<pid>-<nanos>` by [`StagingDir::create`].
+fn staging_owner_is_alive(path: &Path) -> bool {
+    let Some(pid) = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.split_once('-'))
+        .and_then(|(pid, _)| pid.parse::<i32>().ok())
+    else {
+        return false;
+    };
+    if pid == std::process::id() as i32 {
+        return true;
+    }
+    !matches!(
+        nix::sys::signal::kill(nix::unistd::Pid::from_raw(pid), None),
+        Err(nix::errno::Errno::ESRCH)
+    )
+}
+
+/// Normalizes a registry reference the same way pulls record it, so `alpine`
+/// matches `docker.io/library/alpine:latest`.
+fn canonical_image_ref(reference: &str) -> Option<String> {
+    match ImageSource::parse(reference).ok()? {
+        ImageSource::RemoteOci(image_ref) => RegistryClient::parse_reference(&image_ref)
+            .ok()
+            .map(|reference| reference.to_string()),
+        ImageSource::LocalDisk(_) | ImageSource::RootfsTar(_) | ImageSource::OciArchive(_) => None,
+    }
+}
+
+fn record_matches(record: &ImageRecord, reference: &str, canonical: Option<&str>) -> bool {
+    let matches_ref = |candidate: &str| {
+        candidate == reference || canonical.is_some_and(|canonical| candidate == canonical)
+    };
+    record.image_id == reference
+        || record.image_id.strip_prefix("sha256:") == Some(reference)
+        || matches_ref(&record.image_ref)
+        || record.tags.iter().any(|tag| matches_ref(tag))
+}
+
+/// Matches a hex prefix of the digest in ids like `sha256:<hex>` or `tar-sha256:<hex>`.
+fn image_id_has_prefix(image_id: &str, prefix: &str) -> bool {
+    !prefix.is_empty()
+        && prefix.chars().all(|ch| ch.is_ascii_hexdigit())
+        && image_id
+            .rsplit_once(':')
+            .is_some_and(|(_, digest)| digest.starts_with(prefix))
+}
+
+/// Upgrades a raw index document to [`INDEX_VERSION`] one step at a time.
+///
+/// The upgrade only happens in memory. It reaches disk with the next index
+/// write, which runs under the index lock. Indexes written by a newer release
+/// are rejected instead of being rewritten.
+fn migrate_index(raw: serde_json::Value, path: &Path) -> OciDiskResult<serde_json::Value> {
+    migrate_index_to(raw, path, INDEX_VERSION, INDEX_MIGRATIONS)
+}
+
+fn migrate_index_to(
+    mut raw: serde_json::Value,
+    path: &Path,
+    target: u32,
+    migrations: &[IndexMigration],
+) -> OciDiskResult<serde_json::Value> {
+    let Some(document) = raw.as_object_mut() else {
+        return Err(OciDiskError::CorruptCacheEntry {
+            path: path.to_path_buf(),
+            reason: "index must be a JSON object".to_string(),
+        });
+    };
+    let Some(value) = document.get("version") else {
+        return Err(OciDiskError::CorruptCacheEntry {
+            path: path.to_path_buf(),
+            reason: "index is missing its version".to_string(),
+        });
+    };
+    let mut version = value
+        .as_u64()
+        .and_then(|version| u32::try_from(version).ok())
+        .ok_or_else(|| OciDiskError::CorruptCacheEntry {
+            path: path.to_path_buf(),
+            reason: format!("index version {value} is not a valid version number"),
+        })?;
+    if version > target {
+        return Err(OciDiskError::UnsupportedIndexVersion {
+            path: path.to_path_buf(),
+            version,
+            supported: target,
+        });
+    }
+
+    while version < target {
+        let Some(step) = version
+            .checked_sub(1)
+            .and_then(|step| migrations.get(step as usize))
+        else {
+            return Err(OciDiskError::CorruptCacheEntry {
+                path: path.to_path_buf(),
+                reason: format!("no migration from index version {version}"),
+            });
+        };
+        step(document);
+        version += 1;
+    }
+    document.insert("version".to_string(), target.into());
+    Ok(raw)
+}
+
+fn emit_progress(progress: Option<&ImageProgressSender>, event: ImageProgress) {
+    if let Some(progress) = progress {
+        progress.send(event);
+    }
+}
+
+fn total_download_bytes(layers: &[ResolvedLayer]) -> Option<u64> {
+    layers
+        .iter()
+        .try_fold(0_u64, |total, layer| total.checked_add(layer.size_bytes))
+}
+
+fn layer_download_concurrency(layer_count: usize) -> usize {
+    if layer_count == 0 {
+        return 1;
+    }
+    let host_limit = std::thread::available_parallelism()
+        .map(usize::from)
+        .unwrap_or(4)
+        .saturating_mul(2)
+        .clamp(4, 16);
+    layer_count.min(host_limit)
+}
+
+async fn write_layer_stream(request: LayerStreamWrite<'_>) -> OciDiskResult<()> {
+    let LayerStreamWrite {
+        path,
+        mut stream,
+        append,
+        start_offset,
+        layer,
+        index,
+        total,
+        progress,
+    } = request;
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)
+        .await?;
+    let mut downloaded = start_offset;
+    let mut last_progress = start_offset;
 
     while let Some(chunk) = stream.try_next().await? {
         file.write_all(&chunk).await?;
@@ -1140,17 +2011,28 @@ async fn write_layer_stream(request: LayerStreamWrite<'_>) -> OciDiskResult<()>
     file.flush().await?;
     drop(file);
 
+    check_downloaded_layer_size(path, layer)
+}
+
+/// Validates the size of a finished layer transfer.
+///
+/// A short file means the server closed the stream early, so the partial file is
+/// kept and the next pull resumes from its length with a range request. An
+/// oversized file can never become valid and is discarded.
+fn check_downloaded_layer_size(path: &Path, layer: &ResolvedLayer) -> OciDiskResult<()> {
     let actual = fs::metadata(path)?.len();
-    if actual != layer.size_bytes {
+    if actual == layer.size_bytes {
+        return Ok(());
+    }
+    if actual > layer.size_bytes {
         remove_file_if_exists(path)?;
-        return Err(OciDiskError::LayerSizeMismatch {
-            digest: layer.digest.clone(),
-            path: path.to_path_buf(),
-            expected: layer.size_bytes,
-            actual,
-        });
     }
-    Ok(())
+    Err(OciDiskError::LayerSizeMismatch {
+        digest: layer.digest.clone(),
+        path: path.to_path_buf(),
+        expected: layer.size_bytes,
+        actual,
+    })
 }
 
 fn partial_download_offset(path: &Path, layer: &ResolvedLayer) -> OciDiskResult<u64> {
@@ -1284,7 +2166,7 @@ fn layer_reader_from_read(
     }
 }
 
-fn digest_path_components(digest: &str) -> OciDiskResult<(String, String)> {
+pub(crate) fn digest_path_components(digest: &str) -> OciDiskResult<(String, String)> {
     let Some((algorithm, encoded)) = digest.split_once(':') else {
         return Err(OciDiskError::InvalidDigest {
             digest: digest.to_string(),
@@ -1308,6 +2190,28 @@ fn digest_path_components(digest: &str) -> OciDiskResult<(String, String)> {
     ))
 }
 
+/// Rejects platforms with empty components, which would otherwise produce
+/// ambiguous cache keys and metadata consumers cannot match on.
+fn validate_platform(platform: &Platform) -> OciDiskResult<()> {
+    let message = if platform.os.trim().is_empty() {
+        "os must not be empty"
+    } else if platform.architecture.trim().is_empty() {
+        "architecture must not be empty"
+    } else if platform
+        .variant
+        .as_deref()
+        .is_some_and(|variant| variant.trim().is_empty())
+    {
+        "variant must not be empty when set"
+    } else {
+        return Ok(());
+    };
+    Err(OciDiskError::InvalidPlatform {
+        platform: platform.to_string(),
+        message: message.to_string(),
+    })
+}
+
 fn canonical_local_file(reference: &str, path: &Path) -> OciDiskResult<PathBuf> {
     let canonical = path
         .canonicalize()
@@ -1358,6 +2262,12 @@ fn reject_known_tar_compression(reference: &str, path: &Path) -> OciDiskResult<(
     Ok(())
 }
 
+fn is_zstd_file(path: &Path) -> OciDiskResult<bool> {
+    let mut magic = [0_u8; 4];
+    let read = fs::File::open(path)?.read(&mut magic)?;
+    Ok(read == magic.len() && magic == [0x28, 0xb5, 0x2f, 0xfd])
+}
+
 fn sha256_file(path: &Path) -> OciDiskResult<String> {
     let mut file = fs::File::open(path)?;
     let mut hasher = Sha256::new();
@@ -1372,7 +2282,7 @@ fn sha256_file(path: &Path) -> OciDiskResult<String> {
     Ok(hex_digest(&hasher.finalize()))
 }
 
-fn sha256_bytes(bytes: &[u8]) -> String {
+pub(crate) fn sha256_bytes(bytes: &[u8]) -> String {
     let mut hasher = Sha256::new();
     hasher.update(bytes);
     hex_digest(&hasher.finalize())
@@ -1460,6 +2370,7 @@ fn now_unix_nanos() -> u128 {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::io::{Cursor, Read};
 
     use ext4::Reader;
@@ -1468,11 +2379,13 @@ mod tests {
     use crate::progress::ImageProgressSender;
     use crate::registry::ResolvedLayer;
     use crate::store::{
-        digest_path_components, image_id_path_component, layer_download_concurrency, sha256_bytes,
-        verify_layer_file, ImageMetadata, ImageProgress, ImageStore, RootfsImageSource,
-        RootfsOptions, METADATA_VERSION, ROOTFS_FILESYSTEM, ROOTFS_FILE_NAME,
+        check_downloaded_layer_size, digest_path_components, image_id_path_component,
+        layer_download_concurrency, migrate_index_to, sha256_bytes, verify_layer_file,
+        ImageLayerMetadata, ImageMetadata, ImageProgress, ImageProvenance, ImageRemoval,
+        ImageStore, IndexMigration, RootfsImageSource, RootfsOptions, StoreIndex, METADATA_VERSION,
+        ROOTFS_FILESYSTEM, ROOTFS_FILE_NAME,
     };
-    use crate::{Platform, RootfsImage};
+    use crate::{OciDiskError, Platform, RootfsImage};
 
     #[test]
     fn image_id_and_platform_define_cache_path() {
@@ -1485,6 +2398,44 @@ mod tests {
         assert_eq!(path, temp.path().join("sha256-abc123/linux-amd64"));
     }
 
+    #[test]
+    fn provenance_prefers_annotations_and_tolerates_old_metadata() {
+        let annotations = BTreeMap::from([
+            (
+                "org.opencontainers.image.source".to_string(),
+                "https://github.com/example/base".to_string(),
+            ),
+            (
+                "org.opencontainers.image.revision".to_string(),
+                " ".to_string(),
+            ),
+        ]);
+
+        let provenance =
+            ImageProvenance::from_annotations(&annotations, Some("2024-01-02T03:04:05Z"));
+        let metadata = serde_json::from_value::<ImageMetadata>(serde_json::json!({
+            "version": METADATA_VERSION,
+            "image_ref": "alpine",
+            "image_id": "sha256:abc123",
+            "source": "oci-registry",
+            "platform": {"os": "linux", "architecture": "amd64"},
+            "filesystem": ROOTFS_FILESYSTEM,
+            "rootfs_file": ROOTFS_FILE_NAME,
+            "created_at_unix": 1,
+        }))
+        .expect("parse metadata without provenance");
+
+        assert_eq!(
+            provenance,
+            ImageProvenance {
+                created: Some("2024-01-02T03:04:05Z".to_string()),
+                source: Some("https://github.com/example/base".to_string()),
+                revision: None,
+            }
+        );
+        assert!(metadata.provenance.is_empty());
+    }
+
     #[test]
     fn validates_existing_cache_metadata() {
         let temp = tempfile::tempdir().expect("create temp dir");
@@ -1509,6 +2460,7 @@ mod tests {
                 filesystem: ROOTFS_FILESYSTEM.to_string(),
                 rootfs_file: ROOTFS_FILE_NAME.to_string(),
                 created_at_unix: 1,
+                provenance: Default::default(),
             })
             .expect("serialize metadata"),
         )
@@ -1584,6 +2536,7 @@ mod tests {
                 filesystem: "xfs".to_string(),
                 rootfs_file: ROOTFS_FILE_NAME.to_string(),
                 created_at_unix: 1,
+                provenance: Default::default(),
             })
             .expect("serialize metadata"),
         )
@@ -1663,6 +2616,41 @@ mod tests {
         assert!(!path.exists());
     }
 
+    #[test]
+    fn short_layer_download_keeps_partial_file_for_resume() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let path = temp.path().join("layer.part");
+        std::fs::write(&path, b"par").expect("write layer");
+        let layer = ResolvedLayer {
+            digest: format!("sha256:{}", sha256_bytes(b"partial")),
+            media_type: "application/vnd.oci.image.layer.v1.tar".to_string(),
+            size_bytes: 7,
+            diff_id: "sha256:diff".to_string(),
+        };
+
+        let err = check_downloaded_layer_size(&path, &layer).expect_err("short layer should fail");
+
+        assert!(err.to_string().contains("expected 7"));
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn oversized_layer_download_removes_partial_file() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let path = temp.path().join("layer.part");
+        std::fs::write(&path, b"too long").expect("write layer");
+        let layer = ResolvedLayer {
+            digest: format!("sha256:{}", sha256_bytes(b"short")),
+            media_type: "application/vnd.oci.image.layer.v1.tar".to_string(),
+            size_bytes: 5,
+            diff_id: "sha256:diff".to_string(),
+        };
+
+        check_downloaded_layer_size(&path, &layer).expect_err("oversized layer should fail");
+
+        assert!(!path.exists());
+    }
+
     #[test]
     fn disk_source_uses_local_file_directly_without_creating_cache() {
         let temp = tempfile::tempdir().expect("create temp dir");
@@ -1951,9 +2939,662 @@ mod tests {
             ImageProgress::ApplyingLayer { index, total, .. } => {
                 format!("apply-layer-{index}/{total}")
             }
+            ImageProgress::ReusingRootfs { .. } => "reuse-rootfs".to_string(),
             ImageProgress::WritingExt4 => "write-ext4".to_string(),
             ImageProgress::SavingBaseImage => "save-base-image".to_string(),
             ImageProgress::Complete => "complete".to_string(),
         }
     }
+
+    #[test]
+    fn list_reports_cached_images_with_tags() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let platform = Platform::linux_amd64();
+        let image_ref = "docker.io/library/alpine:latest";
+        let dir = write_cached_image(&store, "sha256:abc123", image_ref, &platform);
+        store
+            .update_tag_mapping(image_ref, &platform, "sha256:abc123")
+            .expect("tag image");
+        std::fs::create_dir_all(temp.path().join("tmp")).expect("create tmp dir");
+
+        let records = store.list().expect("list images");
+
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.image_id, "sha256:abc123");
+        assert_eq!(record.tags, vec![image_ref.to_string()]);
+        assert_eq!(record.rootfs_path, dir.join(ROOTFS_FILE_NAME));
+        assert_eq!(record.size_bytes, 4);
+    }
+
+    #[test]
+    fn inspect_resolves_short_reference_and_image_id() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let platform = Platform::host().expect("host platform");
+        write_cached_image(
+            &store,
+            "sha256:abc123",
+            "docker.io/library/alpine:latest",
+            &platform,
+        );
+
+        let by_ref = store
+            .inspect("alpine")
+            .expect("inspect by ref")
+            .expect("image by ref");
+        let by_id = store
+            .inspect("abc123")
+            .expect("inspect by id")
+            .expect("image by id");
+
+        assert_eq!(by_ref, by_id);
+        assert!(store.inspect("busybox").expect("inspect miss").is_none());
+    }
+
+    #[test]
+    fn remove_drops_shared_tag_and_requires_force_for_tagged_id() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let platform = Platform::host().expect("host platform");
+        let image_ref = "docker.io/library/alpine:latest";
+        let dir = write_cached_image(&store, "sha256:abc123", image_ref, &platform);
+        store
+            .update_tag_mapping(image_ref, &platform, "sha256:abc123")
+            .expect("tag image");
+        store.tag("alpine", "dev").expect("add second tag");
+
+        let untagged = store.remove("dev", false).expect("remove shared tag");
+        let in_use = store
+            .remove("abc123", false)
+            .expect_err("tagged image id needs force");
+        let removed = store.remove("alpine", false).expect("remove last tag");
+
+        assert_eq!(
+            untagged,
+            ImageRemoval::Untagged {
+                tag: "docker.io/library/dev:latest".to_string(),
+                image_id: "sha256:abc123".to_string(),
+                remaining_tags: vec![image_ref.to_string()],
+            }
+        );
+        assert!(matches!(in_use, OciDiskError::ImageInUse { .. }));
+        assert!(matches!(removed, ImageRemoval::Removed { .. }));
+        assert!(!dir.exists());
+        assert!(store.list().expect("list images").is_empty());
+    }
+
+    #[test]
+    fn remove_unless_attached_keeps_images_backing_machines() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let platform = Platform::linux_amd64();
+        let dir = write_cached_image(
+            &store,
+            "sha256:abc123",
+            "docker.io/library/alpine:latest",
+            &platform,
+        );
+        store
+            .update_tag_mapping(
+                "docker.io/library/alpine:latest",
+                &platform,
+                "sha256:abc123",
+            )
+            .expect("tag alpine");
+        store
+            .update_tag_mapping("docker.io/library/base:latest", &platform, "sha256:abc123")
+            .expect("tag base");
+        let machines = vec!["devbox".to_string()];
+
+        let untagged = store
+            .remove_unless_attached("base", false, &machines)
+            .expect("dropping one of two tags keeps the image");
+        let err = store
+            .remove_unless_attached("alpine", false, &machines)
+            .expect_err("last tag should not delete an attached image");
+        let forced = store
+            .remove_unless_attached("sha256:abc123", true, &machines)
+            .expect_err("force should not delete an attached image");
+
+        assert!(matches!(untagged, ImageRemoval::Untagged { .. }));
+        assert!(matches!(
+            err,
+            OciDiskError::ImageBacksMachines { ref machines, .. } if machines == &["devbox"]
+        ));
+        assert!(matches!(forced, OciDiskError::ImageBacksMachines { .. }));
+        assert!(dir.join(ROOTFS_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn image_with_layers_matches_identical_layer_stack_only() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let platform = Platform::host().expect("host platform");
+        let dir = write_cached_image(
+            &store,
+            "sha256:abc123",
+            "docker.io/library/alpine:latest",
+            &platform,
+        );
+        let layer = |digest: &str| ResolvedLayer {
+            digest: digest.to_string(),
+            media_type: "application/vnd.oci.image.layer.v1.tar+gzip".to_string(),
+            size_bytes: 1,
+            diff_id: digest.to_string(),
+        };
+        let layers = vec![layer("sha256:l1"), layer("sha256:l2")];
+        let metadata_path = dir.join("metadata.json");
+        let mut metadata: ImageMetadata =
+            serde_json::from_slice(&std::fs::read(&metadata_path).expect("read metadata"))
+                .expect("parse metadata");
+        metadata.layers = layers.iter().map(ImageLayerMetadata::from).collect();
+        std::fs::write(
+            &metadata_path,
+            serde_json::to_vec(&metadata).expect("serialize"),
+        )
+        .expect("write metadata");
+
+        let same = store
+            .image_with_layers(&layers, &platform, 4)
+            .expect("find shared image");
+        let reordered = store
+            .image_with_layers(&[layer("sha256:l2"), layer("sha256:l1")], &platform, 4)
+            .expect("find reordered");
+        let other_size = store
+            .image_with_layers(&layers, &platform, 8)
+            .expect("find other size");
+
+        assert_eq!(
+            same.map(|record| record.image_id).as_deref(),
+            Some("sha256:abc123")
+        );
+        assert!(reordered.is_none());
+        assert!(other_size.is_none());
+    }
+
+    #[test]
+    fn inspect_resolves_image_id_prefixes() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let platform = Platform::host().expect("host platform");
+        write_cached_image(
+            &store,
+            "sha256:abc123",
+            "docker.io/library/alpine:latest",
+            &platform,
+        );
+        write_cached_image(
+            &store,
+            "sha256:abd456",
+            "docker.io/library/debian:latest",
+            &platform,
+        );
+        write_cached_image(
+            &store,
+            "sha256:fe0789",
+            "docker.io/library/fe:latest",
+            &platform,
+        );
+
+        let unique = store
+            .inspect("abc")
+            .expect("inspect unique prefix")
+            .expect("image by prefix");
+        let ambiguous = store
+            .inspect("ab")
+            .expect_err("shared prefix should be ambiguous");
+        let exact_tag = store
+            .inspect("fe")
+            .expect("inspect tag")
+            .expect("image by tag");
+
+        assert_eq!(unique.image_id, "sha256:abc123");
+        assert!(matches!(
+            ambiguous,
+            OciDiskError::AmbiguousReference { ref candidates, .. }
+                if candidates == &["sha256:abc123", "sha256:abd456"]
+        ));
+        assert_eq!(exact_tag.image_id, "sha256:fe0789");
+        assert!(store.inspect("ff").expect("inspect miss").is_none());
+        assert!(store.inspect("").expect("inspect empty").is_none());
+    }
+
+    fn write_cached_image(
+        store: &ImageStore,
+        image_id: &str,
+        image_ref: &str,
+        platform: &Platform,
+    ) -> std::path::PathBuf {
+        let dir = store.image_dir(image_id, platform).expect("cache path");
+        std::fs::create_dir_all(&dir).expect("create cache dir");
+        std::fs::write(dir.join(ROOTFS_FILE_NAME), b"disk").expect("write rootfs");
+        std::fs::write(
+            dir.join("metadata.json"),
+            serde_json::to_vec_pretty(&ImageMetadata {
+                version: METADATA_VERSION,
+                image_ref: image_ref.to_string(),
+                image_id: image_id.to_string(),
+                source: RootfsImageSource::OciRegistry,
+                manifest_digest: Some(image_id.to_string()),
+                config_digest: None,
+                layers: Vec::new(),
+                platform: platform.clone(),
+                filesystem: ROOTFS_FILESYSTEM.to_string(),
+                rootfs_file: ROOTFS_FILE_NAME.to_string(),
+                created_at_unix: 1,
+                provenance: Default::default(),
+            })
+            .expect("serialize metadata"),
+        )
+        .expect("write metadata");
+        dir
+    }
+
+    #[test]
+    fn tag_adds_reference_and_untag_removes_it() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let platform = Platform::host().expect("host platform");
+        let dir = write_cached_image(
+            &store,
+            "sha256:abc123",
+            "docker.io/library/alpine:latest",
+            &platform,
+        );
+
+        let tagged = store.tag("alpine", "dev").expect("tag image");
+        assert_eq!(
+            tagged.tags,
+            vec!["docker.io/library/dev:latest".to_string()]
+        );
+        let by_tag = store
+            .inspect("dev")
+            .expect("inspect tag")
+            .expect("tagged image");
+        assert_eq!(by_tag.image_id, "sha256:abc123");
+
+        store.untag("dev").expect("untag image");
+
+        assert!(store.inspect("dev").expect("inspect tag").is_none());
+        assert!(dir.join(ROOTFS_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn tag_and_untag_report_missing_references() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+
+        let tag_err = store.tag("alpine", "dev").expect_err("missing image");
+        let untag_err = store.untag("dev").expect_err("missing tag");
+
+        assert!(matches!(tag_err, OciDiskError::TagNotFound { .. }));
+        assert!(matches!(untag_err, OciDiskError::TagNotFound { .. }));
+    }
+
+    #[tokio::test]
+    async fn missing_local_source_fails_before_creating_cache() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let cache = temp.path().join("cache");
+        let store = ImageStore::open(&cache).expect("open store");
+        let missing = temp.path().join("missing.tar");
+
+        for image_ref in [
+            format!("tar:{}", missing.display()),
+            format!("oci:{}", missing.display()),
+        ] {
+            let err = store
+                .get_or_create(
+                    &image_ref,
+                    RootfsOptions::new(Platform::linux_amd64()),
+                    None,
+                )
+                .await
+                .expect_err("missing source should fail");
+
+            assert!(matches!(err, OciDiskError::LocalImageSource { .. }));
+        }
+        assert!(!cache.exists());
+    }
+
+    #[tokio::test]
+    async fn import_disk_tags_a_copy_that_pulls_resolve_offline() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path().join("cache")).expect("open store");
+        let platform = Platform::linux_arm64();
+        let disk = temp.path().join("rootfs.img");
+        std::fs::write(&disk, b"disk").expect("write disk");
+
+        let record = store
+            .import_disk(&disk, "local/base", &platform)
+            .expect("import disk");
+        let image = store
+            .get_or_create("local/base", RootfsOptions::new(platform.clone()), None)
+            .await
+            .expect("resolve imported disk");
+
+        assert_eq!(record.source, RootfsImageSource::Disk);
+        assert_eq!(record.tags, vec!["docker.io/local/base:latest".to_string()]);
+        assert_eq!(image.image_id, record.image_id);
+        assert_eq!(std::fs::read(&image.path).expect("read rootfs"), b"disk");
+        assert_ne!(image.path, disk);
+    }
+
+    #[test]
+    fn import_disk_decompresses_zstd() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path().join("cache")).expect("open store");
+        let disk = temp.path().join("rootfs.img.zst");
+        std::fs::write(
+            &disk,
+            zstd::encode_all(&b"disk"[..], 0).expect("compress disk"),
+        )
+        .expect("write disk");
+
+        let record = store
+            .import_disk(&disk, "local/base", &Platform::linux_amd64())
+            .expect("import disk");
+
+        assert_eq!(
+            std::fs::read(record.rootfs_path).expect("read rootfs"),
+            b"disk"
+        );
+    }
+
+    #[tokio::test]
+    async fn empty_platform_components_are_rejected() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let platform = Platform {
+            os: "linux".to_string(),
+            architecture: String::new(),
+            variant: None,
+        };
+
+        let err = store
+            .get_or_create("alpine:latest", RootfsOptions::new(platform), None)
+            .await
+            .expect_err("empty architecture should fail");
+
+        assert!(matches!(err, OciDiskError::InvalidPlatform { .. }));
+    }
+
+    #[test]
+    fn gc_orphans_removes_stray_dirs_and_keeps_registered_images() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let platform = Platform::linux_amd64();
+        let kept = write_cached_image(
+            &store,
+            "sha256:abc123",
+            "docker.io/library/alpine:latest",
+            &platform,
+        );
+        let stray = store
+            .image_dir("sha256:def456", &platform)
+            .expect("cache path");
+        std::fs::create_dir_all(&stray).expect("create stray dir");
+        std::fs::write(stray.join(ROOTFS_FILE_NAME), b"partial").expect("write rootfs");
+        let stale_staging = temp.path().join(".staging").join(format!("{}-1", i32::MAX));
+        std::fs::create_dir_all(&stale_staging).expect("create staging dir");
+        std::fs::create_dir_all(temp.path().join("blobs/sha256")).expect("create blobs dir");
+
+        let removed = store.gc_orphans().expect("gc orphans");
+
+        assert!(removed.contains(&stray));
+        assert!(removed.contains(&stale_staging));
+        assert!(!stray.exists());
+        assert!(!stale_staging.exists());
+        assert!(kept.join(ROOTFS_FILE_NAME).exists());
+        assert!(temp.path().join("blobs/sha256").exists());
+    }
+
+    #[test]
+    fn gc_orphans_keeps_dirs_with_unreadable_metadata() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let platform = Platform::linux_amd64();
+        let corrupt = store
+            .image_dir("sha256:def456", &platform)
+            .expect("cache path");
+        std::fs::create_dir_all(&corrupt).expect("create cache dir");
+        std::fs::write(corrupt.join(ROOTFS_FILE_NAME), b"disk").expect("write rootfs");
+        std::fs::write(corrupt.join("metadata.json"), b"not json").expect("write metadata");
+
+        let removed = store.gc_orphans().expect("gc orphans");
+
+        assert!(removed.is_empty());
+        assert!(corrupt.join("metadata.json").exists());
+        assert!(store.disk_usage().expect("disk usage").orphans.is_empty());
+    }
+
+    #[test]
+    fn get_cached_resolves_only_images_cached_for_the_platform() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let platform = Platform::linux_amd64();
+        write_cached_image(
+            &store,
+            "sha256:abc123",
+            "docker.io/library/alpine:latest",
+            &platform,
+        );
+
+        let image = store
+            .get_cached("alpine", RootfsOptions::new(platform.clone()))
+            .expect("get cached")
+            .expect("alpine should be cached");
+
+        assert_eq!(image.image_id, "sha256:abc123");
+        assert_eq!(
+            image.path,
+            store
+                .image_dir("sha256:abc123", &platform)
+                .expect("cache path")
+                .join(ROOTFS_FILE_NAME)
+        );
+        assert!(store
+            .get_cached("debian", RootfsOptions::new(platform))
+            .expect("get cached")
+            .is_none());
+        assert!(store
+            .get_cached("alpine", RootfsOptions::new(Platform::linux_arm64()))
+            .expect("get cached")
+            .is_none());
+    }
+
+    #[test]
+    fn overlay_template_is_an_empty_ext4_disk_outside_the_image_dirs() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+
+        let template = store.overlay_template().expect("overlay template");
+
+        assert_eq!(
+            store.overlay_template().expect("cached overlay template"),
+            template
+        );
+        Reader::new(&template).expect("overlay template should be ext4");
+        assert!(store.list().expect("list images").is_empty());
+        assert!(store.gc_orphans().expect("gc orphans").is_empty());
+        assert!(template.is_file());
+    }
+
+    #[test]
+    fn disk_usage_reports_images_and_orphans_without_removing_them() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let platform = Platform::linux_amd64();
+        write_cached_image(
+            &store,
+            "sha256:abc123",
+            "docker.io/library/alpine:latest",
+            &platform,
+        );
+        let stray = store
+            .image_dir("sha256:def456", &platform)
+            .expect("cache path");
+        std::fs::create_dir_all(&stray).expect("create stray dir");
+        std::fs::write(stray.join(ROOTFS_FILE_NAME), b"partial").expect("write rootfs");
+
+        let usage = store.disk_usage().expect("disk usage");
+
+        assert_eq!(usage.images.len(), 1);
+        assert_eq!(usage.images[0].image_id, "sha256:abc123");
+        assert_eq!(usage.index_bytes, 0);
+        assert_eq!(
+            usage
+                .orphans
+                .iter()
+                .map(|orphan| &orphan.path)
+                .collect::<Vec<_>>(),
+            vec![&stray]
+        );
+        assert!(usage.total_bytes() >= usage.images[0].bytes + usage.orphans[0].bytes);
+        assert!(stray.exists());
+    }
+
+    #[test]
+    fn disk_usage_counts_a_shared_rootfs_once() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let platform = Platform::linux_amd64();
+        let first = write_cached_image(
+            &store,
+            "sha256:abc123",
+            "docker.io/library/alpine:latest",
+            &platform,
+        );
+        std::fs::write(first.join(ROOTFS_FILE_NAME), vec![1u8; 64 * 1024]).expect("write rootfs");
+        let second = write_cached_image(
+            &store,
+            "sha256:def456",
+            "docker.io/library/alpine:edge",
+            &platform,
+        );
+        std::fs::remove_file(second.join(ROOTFS_FILE_NAME)).expect("remove rootfs");
+        std::fs::hard_link(first.join(ROOTFS_FILE_NAME), second.join(ROOTFS_FILE_NAME))
+            .expect("link shared rootfs");
+
+        let usage = store.disk_usage().expect("disk usage");
+
+        assert_eq!(usage.images.len(), 2);
+        assert_eq!(usage.images[0].bytes, usage.images[1].bytes);
+        assert!(usage.images[0].bytes > 0);
+        assert_eq!(usage.images.iter().filter(|image| image.shared).count(), 1);
+        assert_eq!(
+            usage.total_bytes(),
+            usage.images[0].bytes + usage.layer_cache_bytes + usage.index_bytes
+        );
+    }
+
+    #[test]
+    fn index_without_version_is_reported_corrupt() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let index = br#"{"tags":{}}"#;
+        std::fs::write(temp.path().join("index.json"), index).expect("write index");
+
+        let err = store.list().expect_err("index without version should fail");
+
+        assert!(matches!(err, OciDiskError::CorruptCacheEntry { .. }));
+        assert_eq!(
+            std::fs::read(temp.path().join("index.json")).expect("read index"),
+            index
+        );
+    }
+
+    #[test]
+    fn newer_index_version_is_rejected() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        std::fs::write(
+            temp.path().join("index.json"),
+            br#"{"version":99,"tags":{}}"#,
+        )
+        .expect("write index");
+
+        let err = store.list().expect_err("newer index should fail");
+
+        assert!(matches!(
+            err,
+            OciDiskError::UnsupportedIndexVersion { version: 99, .. }
+        ));
+    }
+
+    #[test]
+    fn older_index_is_upgraded_step_by_step_and_written_back() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let store = ImageStore::open(temp.path()).expect("open store");
+        let index_path = temp.path().join("index.json");
+        let raw = serde_json::json!({
+            "version": 1,
+            "tags": {
+                "docker.io/library/alpine:latest|linux-amd64": {
+                    "image_ref": "docker.io/library/alpine:latest",
+                    "platform": { "os": "linux", "architecture": "amd64" },
+                    "digest": "sha256:abc123"
+                }
+            }
+        });
+        let rename_digest: IndexMigration = |document| {
+            for tag in document
+                .get_mut("tags")
+                .and_then(serde_json::Value::as_object_mut)
+                .into_iter()
+                .flat_map(|tags| tags.values_mut())
+                .filter_map(serde_json::Value::as_object_mut)
+            {
+                if let Some(digest) = tag.remove("digest") {
+                    tag.insert("manifest_digest".to_string(), digest);
+                }
+            }
+        };
+        let add_updated_at: IndexMigration = |document| {
+            for tag in document
+                .get_mut("tags")
+                .and_then(serde_json::Value::as_object_mut)
+                .into_iter()
+                .flat_map(|tags| tags.values_mut())
+                .filter_map(serde_json::Value::as_object_mut)
+            {
+                tag.entry("updated_at_unix").or_insert(0.into());
+            }
+        };
+
+        let migrated = migrate_index_to(raw, &index_path, 3, &[rename_digest, add_updated_at])
+            .expect("migrate index");
+        let index = serde_json::from_value::<StoreIndex>(migrated).expect("parse migrated index");
+        store.write_index(&index).expect("write index");
+        let rewritten: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&index_path).expect("read index"))
+                .expect("parse rewritten index");
+
+        let tag = &rewritten["tags"]["docker.io/library/alpine:latest|linux-amd64"];
+        assert_eq!(rewritten["version"], 3);
+        assert_eq!(tag["manifest_digest"], "sha256:abc123");
+        assert_eq!(tag["updated_at_unix"], 0);
+    }
+
+    #[test]
+    fn index_migration_rejects_newer_and_unbridgeable_versions() {
+        let path = std::path::Path::new("index.json");
+        let noop: IndexMigration = |_| {};
+
+        let newer = migrate_index_to(serde_json::json!({ "version": 4 }), path, 3, &[noop, noop])
+            .expect_err("newer index should fail");
+        let gap = migrate_index_to(serde_json::json!({ "version": 1 }), path, 3, &[noop])
+            .expect_err("missing step should fail");
+
+        assert!(matches!(
+            newer,
+            OciDiskError::UnsupportedIndexVersion {
+                version: 4,
+                supported: 3,
+                ..
+            }
+        ));
+        assert!(matches!(gap, OciDiskError::CorruptCacheEntry { .. }));
+    }
 }
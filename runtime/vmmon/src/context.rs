@@ -1,11 +1,13 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use protocol::prost_types::Struct;
 use tokio_util::sync::CancellationToken;
 use virt::{SerialConsole, VirtualMachine};
 use vm_spec::VmSpec;
 
+use crate::idle::IdleTracker;
 use crate::state::InstanceStore;
 
 #[derive(Debug, Clone)]
@@ -14,15 +16,23 @@ pub(crate) struct RuntimeContext {
     config: PathBuf,
     socket: PathBuf,
     serial_log: PathBuf,
+    idle_timeout: Option<Duration>,
 }
 
 impl RuntimeContext {
-    pub(crate) fn new(dir: PathBuf, config: PathBuf, socket: PathBuf, serial_log: PathBuf) -> Self {
+    pub(crate) fn new(
+        dir: PathBuf,
+        config: PathBuf,
+        socket: PathBuf,
+        serial_log: PathBuf,
+        idle_timeout: Option<Duration>,
+    ) -> Self {
         Self {
             dir,
             config,
             socket,
             serial_log,
+            idle_timeout,
         }
     }
 
@@ -41,6 +51,10 @@ impl RuntimeContext {
     pub(crate) fn serial_log(&self) -> &Path {
         &self.serial_log
     }
+
+    pub(crate) fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
 }
 
 #[derive(Clone)]
@@ -52,5 +66,6 @@ pub struct DaemonContext {
     pub(crate) machine: VirtualMachine,
     pub(crate) serial_console: Arc<SerialConsole>,
     pub(crate) store: Arc<InstanceStore>,
+    pub(crate) idle: Arc<IdleTracker>,
     pub(crate) shutdown: CancellationToken,
 }
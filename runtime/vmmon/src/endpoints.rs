@@ -447,6 +447,12 @@ async fn run_connect_broker(
                         .await;
                 match result {
                     Ok(Ok(stream)) => {
+                        tracing::debug!(
+                            endpoint = %endpoint.name,
+                            port = stream.destination_port(),
+                            source_port = ?stream.source_port(),
+                            "broker connect request opened guest stream"
+                        );
                         let fd = stream
                             .dup_fd()
                             .map_err(|err| format!("duplicate stream fd: {err}"))?;
@@ -459,7 +465,7 @@ async fn run_connect_broker(
                             .map_err(|err| format!("send connect_open_ok: {err}"))?;
                     }
                     Ok(Err(err)) => {
-                        tracing::info!(endpoint = %endpoint.name, error = %err, "broker connect request failed");
+                        tracing::info!(endpoint = %endpoint.name, port = endpoint.port, error = %err, "broker connect request failed");
                         control
                             .send_message(
                                 &ControlMessageKind::ConnectOpenErr {
@@ -473,7 +479,7 @@ async fn run_connect_broker(
                             .map_err(|err| format!("send connect_open_err: {err}"))?;
                     }
                     Err(_) => {
-                        tracing::info!(endpoint = %endpoint.name, timeout = ?CONNECT_TIMEOUT, "broker connect request timed out");
+                        tracing::info!(endpoint = %endpoint.name, port = endpoint.port, timeout = ?CONNECT_TIMEOUT, "broker connect request timed out");
                         control
                             .send_message(
                                 &ControlMessageKind::ConnectOpenErr {
@@ -1,10 +1,15 @@
-use vm_spec::VmSpec;
+use vm_spec::{ConnectRetry, VmSpec};
 
 pub(crate) trait VmSpecExt {
     fn cpus_or_default(&self) -> u8;
     fn memory_or_default(&self) -> u32;
     fn nested_virtualization_or_default(&self) -> bool;
     fn rosetta_or_default(&self) -> bool;
+    fn graphics_or_default(&self) -> bool;
+    fn keyboard_or_default(&self) -> bool;
+    fn entropy_or_default(&self) -> bool;
+    fn memory_balloon_or_default(&self) -> bool;
+    fn connect_retry_or_default(&self) -> ConnectRetry;
 }
 
 impl VmSpecExt for VmSpec {
@@ -35,4 +40,39 @@ impl VmSpecExt for VmSpec {
             .and_then(|hardware| hardware.rosetta)
             .unwrap_or(false)
     }
+
+    fn graphics_or_default(&self) -> bool {
+        self.hardware
+            .as_ref()
+            .and_then(|hardware| hardware.graphics)
+            .unwrap_or(false)
+    }
+
+    fn keyboard_or_default(&self) -> bool {
+        self.hardware
+            .as_ref()
+            .and_then(|hardware| hardware.keyboard)
+            .unwrap_or(false)
+    }
+
+    fn entropy_or_default(&self) -> bool {
+        self.hardware
+            .as_ref()
+            .and_then(|hardware| hardware.entropy)
+            .unwrap_or(true)
+    }
+
+    fn memory_balloon_or_default(&self) -> bool {
+        self.hardware
+            .as_ref()
+            .and_then(|hardware| hardware.memory_balloon)
+            .unwrap_or(true)
+    }
+
+    fn connect_retry_or_default(&self) -> ConnectRetry {
+        self.vsock
+            .as_ref()
+            .and_then(|vsock| vsock.connect_retry.clone())
+            .unwrap_or_default()
+    }
 }
@@ -53,13 +53,22 @@ impl GuestControlService for GuestControlSvc {
             .as_ref()
             .map(|system| system.arch.as_str())
             .unwrap_or("");
+        let addresses = request
+            .system_info
+            .as_ref()
+            .map(|system| system.ip_addresses.clone())
+            .unwrap_or_default();
 
         tracing::info!(
             guest_service_version = %request.guest_service_version,
             hostname,
             arch,
+            addresses = ?addresses,
             "guest service registered"
         );
+        self.store
+            .dispatch(Action::guest_network(addresses))
+            .map_err(|err| Status::internal(err.to_string()))?;
         self.store
             .dispatch(Action::guest_running())
             .map_err(|err| Status::internal(err.to_string()))?;
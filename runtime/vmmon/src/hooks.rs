@@ -0,0 +1,244 @@
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use eyre::Context;
+use protocol::{ENV_BENTO_MACHINE, ENV_BENTO_MACHINE_DIR, VMMON_START_TIMEOUT};
+use vm_spec::{Hook, VmSpec};
+
+/// How long a single hook may run before it is killed.
+const HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+/// Start time left over for booting the VM once every pre-start hook has run.
+const VM_START_ALLOWANCE: Duration = Duration::from_secs(30);
+/// Combined time all pre-start hooks may take.
+///
+/// Pre-start hooks run inside vmmon startup, so they share the launcher's start
+/// timeout. Keeping them below it reports a slow hook as a hook failure instead of
+/// a vmmon start timeout.
+const PRE_START_HOOKS_TIMEOUT: Duration = VMMON_START_TIMEOUT.saturating_sub(VM_START_ALLOWANCE);
+
+#[derive(Debug, Clone, Copy)]
+enum HookPhase {
+    PreStart,
+    PostStop,
+}
+
+impl HookPhase {
+    fn label(self) -> &'static str {
+        match self {
+            Self::PreStart => "pre-start",
+            Self::PostStop => "post-stop",
+        }
+    }
+
+    fn hooks(self, spec: &VmSpec) -> &[Hook] {
+        match (self, spec.hooks.as_ref()) {
+            (Self::PreStart, Some(hooks)) => &hooks.pre_start,
+            (Self::PostStop, Some(hooks)) => &hooks.post_stop,
+            (_, None) => &[],
+        }
+    }
+}
+
+/// Runs the pre-start hooks in order, stopping at the first failure.
+pub(crate) async fn run_pre_start(spec: &VmSpec, name: &str, data_dir: &Path) -> eyre::Result<()> {
+    run_pre_start_within(spec, name, data_dir, PRE_START_HOOKS_TIMEOUT).await
+}
+
+async fn run_pre_start_within(
+    spec: &VmSpec,
+    name: &str,
+    data_dir: &Path,
+    budget: Duration,
+) -> eyre::Result<()> {
+    let deadline = Instant::now() + budget;
+    for hook in HookPhase::PreStart.hooks(spec) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            eyre::bail!(
+                "pre-start hooks did not finish within {budget:?}; {} was not run",
+                hook.command.display()
+            );
+        }
+        run_hook(
+            HookPhase::PreStart,
+            hook,
+            name,
+            data_dir,
+            remaining.min(HOOK_TIMEOUT),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Runs every post-stop hook, logging failures instead of returning them.
+pub(crate) async fn run_post_stop(spec: &VmSpec, name: &str, data_dir: &Path) {
+    for hook in HookPhase::PostStop.hooks(spec) {
+        if let Err(err) = run_hook(HookPhase::PostStop, hook, name, data_dir, HOOK_TIMEOUT).await {
+            tracing::warn!(
+                error = %err,
+                command = %hook.command.display(),
+                "post-stop hook failed"
+            );
+        }
+    }
+}
+
+async fn run_hook(
+    phase: HookPhase,
+    hook: &Hook,
+    name: &str,
+    data_dir: &Path,
+    timeout: Duration,
+) -> eyre::Result<()> {
+    let started = Instant::now();
+    tracing::info!(
+        phase = phase.label(),
+        command = %hook.command.display(),
+        args = ?hook.args,
+        "running lifecycle hook"
+    );
+
+    let mut command = tokio::process::Command::new(&hook.command);
+    command
+        .args(&hook.args)
+        .env(ENV_BENTO_MACHINE, name)
+        .env(ENV_BENTO_MACHINE_DIR, data_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let child = command
+        .spawn()
+        .wrap_err_with(|| format!("spawn {} hook {}", phase.label(), hook.command.display()))?;
+    let output = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(output) => output.wrap_err_with(|| {
+            format!("wait for {} hook {}", phase.label(), hook.command.display())
+        })?,
+        Err(_) => eyre::bail!(
+            "{} hook {} timed out after {:?}",
+            phase.label(),
+            hook.command.display(),
+            timeout
+        ),
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    tracing::info!(
+        phase = phase.label(),
+        command = %hook.command.display(),
+        status = %output.status,
+        elapsed = ?started.elapsed(),
+        stdout = %stdout.trim_end(),
+        stderr = %stderr.trim_end(),
+        "lifecycle hook finished"
+    );
+
+    if !output.status.success() {
+        eyre::bail!(
+            "{} hook {} exited with {}",
+            phase.label(),
+            hook.command.display(),
+            output.status
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use vm_spec::{Hook, Hooks, VmSpec};
+
+    use crate::hooks::{run_post_stop, run_pre_start, run_pre_start_within};
+
+    fn shell_hook(script: &str) -> Hook {
+        Hook {
+            command: PathBuf::from("/bin/sh"),
+            args: vec!["-c".to_string(), script.to_string()],
+        }
+    }
+
+    fn spec_with_hooks(hooks: Hooks) -> VmSpec {
+        VmSpec {
+            hooks: Some(hooks),
+            ..VmSpec::current()
+        }
+    }
+
+    #[tokio::test]
+    async fn pre_start_hooks_receive_machine_env() {
+        let spec = spec_with_hooks(Hooks {
+            pre_start: vec![shell_hook(
+                r#"test "$BENTO_MACHINE" = devbox && test "$BENTO_MACHINE_DIR" = /tmp/devbox"#,
+            )],
+            post_stop: Vec::new(),
+        });
+
+        run_pre_start(&spec, "devbox", Path::new("/tmp/devbox"))
+            .await
+            .expect("pre-start hook should succeed");
+    }
+
+    #[tokio::test]
+    async fn failing_pre_start_hook_stops_later_hooks() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let marker = dir.path().join("ran");
+        let spec = spec_with_hooks(Hooks {
+            pre_start: vec![
+                shell_hook("exit 3"),
+                shell_hook(&format!("touch {}", marker.display())),
+            ],
+            post_stop: Vec::new(),
+        });
+
+        let err = run_pre_start(&spec, "devbox", dir.path())
+            .await
+            .expect_err("failing hook should abort");
+
+        assert!(err.to_string().contains("pre-start hook"));
+        assert!(!marker.exists());
+    }
+
+    #[tokio::test]
+    async fn pre_start_hooks_share_one_time_budget() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let marker = dir.path().join("ran");
+        let spec = spec_with_hooks(Hooks {
+            pre_start: vec![
+                shell_hook("sleep 0.3"),
+                shell_hook(&format!("touch {}", marker.display())),
+            ],
+            post_stop: Vec::new(),
+        });
+
+        let err = run_pre_start_within(&spec, "devbox", dir.path(), Duration::from_millis(100))
+            .await
+            .expect_err("hooks over budget should abort");
+
+        assert!(err.to_string().contains("timed out"));
+        assert!(!marker.exists());
+    }
+
+    #[tokio::test]
+    async fn post_stop_hooks_continue_after_failure() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let marker = dir.path().join("ran");
+        let spec = spec_with_hooks(Hooks {
+            pre_start: Vec::new(),
+            post_stop: vec![
+                shell_hook("exit 1"),
+                shell_hook(&format!("touch {}", marker.display())),
+            ],
+        });
+
+        run_post_stop(&spec, "devbox", dir.path()).await;
+
+        assert!(marker.exists());
+    }
+}
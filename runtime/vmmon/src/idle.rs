@@ -0,0 +1,119 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+#[derive(Debug, Clone, Copy)]
+struct Activity {
+    connections: usize,
+    last_seen: Instant,
+}
+
+/// Tracks control socket clients so vmmon can stop a machine nobody is using.
+///
+/// The machine counts as idle once no client has been connected for the
+/// configured timeout. An open shell or serial session keeps it busy.
+#[derive(Debug)]
+pub(crate) struct IdleTracker {
+    timeout: Option<Duration>,
+    activity: watch::Sender<Activity>,
+}
+
+/// Keeps the machine busy until dropped.
+#[derive(Debug)]
+pub(crate) struct ConnectionGuard {
+    tracker: Arc<IdleTracker>,
+}
+
+impl IdleTracker {
+    pub(crate) fn new(timeout: Option<Duration>) -> Self {
+        let (activity, _) = watch::channel(Activity {
+            connections: 0,
+            last_seen: Instant::now(),
+        });
+        Self { timeout, activity }
+    }
+
+    pub(crate) fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub(crate) fn connect(self: &Arc<Self>) -> ConnectionGuard {
+        self.activity.send_modify(|activity| {
+            activity.connections += 1;
+            activity.last_seen = Instant::now();
+        });
+        ConnectionGuard {
+            tracker: self.clone(),
+        }
+    }
+
+    /// Resolves once the idle timeout has elapsed with no client connected.
+    ///
+    /// Never resolves when no timeout is configured.
+    pub(crate) async fn wait_idle(&self) {
+        let Some(timeout) = self.timeout else {
+            return std::future::pending().await;
+        };
+
+        let mut activity = self.activity.subscribe();
+        loop {
+            let current = *activity.borrow_and_update();
+            if current.connections > 0 {
+                if activity.changed().await.is_err() {
+                    return std::future::pending().await;
+                }
+                continue;
+            }
+
+            tokio::select! {
+                () = tokio::time::sleep_until(current.last_seen + timeout) => return,
+                changed = activity.changed() => {
+                    if changed.is_err() {
+                        return std::future::pending().await;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.tracker.activity.send_modify(|activity| {
+            activity.connections = activity.connections.saturating_sub(1);
+            activity.last_seen = Instant::now();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::idle::IdleTracker;
+
+    #[tokio::test]
+    async fn open_connection_keeps_machine_busy_until_closed() {
+        let tracker = Arc::new(IdleTracker::new(Some(Duration::from_millis(20))));
+        let connection = tracker.connect();
+
+        let busy = tokio::time::timeout(Duration::from_millis(100), tracker.wait_idle()).await;
+        drop(connection);
+        let idle = tokio::time::timeout(Duration::from_secs(5), tracker.wait_idle()).await;
+
+        assert!(busy.is_err());
+        assert!(idle.is_ok());
+    }
+
+    #[tokio::test]
+    async fn no_timeout_never_reports_idle() {
+        let tracker = IdleTracker::new(None);
+
+        let idle = tokio::time::timeout(Duration::from_millis(50), tracker.wait_idle()).await;
+
+        assert!(idle.is_err());
+    }
+}
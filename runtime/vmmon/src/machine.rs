@@ -1,3 +1,4 @@
+use std::os::unix::fs::FileTypeExt;
 use std::path::{Path, PathBuf};
 
 use agent_spec::SSH_VSOCK_PORT;
@@ -8,7 +9,7 @@ use virt::{
     DiskImage, MachineIdentifier, SharedDirectory, VirtError, VmConfig, VmConfigBuilder, VsockPort,
     VsockPortMode,
 };
-use vm_spec::{VmSpec, VsockEndpointMode};
+use vm_spec::{Disk, VmSpec, VsockEndpointMode};
 
 use crate::ext::VmSpecExt;
 use crate::guest::GUEST_CONTROL_PORT;
@@ -25,6 +26,21 @@ pub enum MachineSpecError {
 
     #[error("invalid mount tag for {mount_source}: mount tags must be non-empty")]
     InvalidMountTag { mount_source: String },
+
+    #[error("{role} disk {} cannot be read", path.display())]
+    DiskUnavailable {
+        role: &'static str,
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("{role} disk {} is a {kind}, not a regular file", path.display())]
+    DiskNotRegularFile {
+        role: &'static str,
+        path: PathBuf,
+        kind: &'static str,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -63,7 +79,11 @@ pub(crate) fn vm_spec_machine_config(
             inputs.guest_services_enabled,
         ))
         .nested_virtualization(inputs.spec.nested_virtualization_or_default())
-        .rosetta(inputs.spec.rosetta_or_default());
+        .rosetta(inputs.spec.rosetta_or_default())
+        .graphics(inputs.spec.graphics_or_default())
+        .keyboard(inputs.spec.keyboard_or_default())
+        .entropy(inputs.spec.entropy_or_default())
+        .memory_balloon(inputs.spec.memory_balloon_or_default());
 
     builder = apply_runtime_network(builder, inputs.network)?;
 
@@ -81,9 +101,11 @@ pub(crate) fn vm_spec_machine_config(
     }
 
     if let Some(storage) = inputs.spec.storage.as_ref() {
-        for disk in &storage.disks {
+        for (index, disk) in storage.disks.iter().enumerate() {
+            let path = resolve_spec_path(inputs.data_dir, &disk.path);
+            validate_disk_path(disk_role(&storage.disks, index), &path)?;
             let disk_image = DiskImage {
-                path: resolve_spec_path(inputs.data_dir, &disk.path),
+                path,
                 read_only: disk.read_only,
             };
 
@@ -226,6 +248,46 @@ fn vm_spec_boot_assets(inputs: &VmSpecInputs<'_>) -> Result<BootAssets, MachineS
     Ok(BootAssets { kernel, initramfs })
 }
 
+/// Names the disk at `index` for error messages. Overlay machines attach the
+/// read-only base rootfs first and their writable root disk second.
+fn disk_role(disks: &[Disk], index: usize) -> &'static str {
+    let overlay = disks.len() > 1 && disks[0].read_only;
+    match (overlay, index) {
+        (true, 0) => "base",
+        (true, 1) | (false, 0) => "root",
+        _ => "data",
+    }
+}
+
+/// Rejects disks whose target, after following symlinks, is not a regular file.
+///
+/// Block devices are not supported as disks yet, and the backends report them
+/// with errors that do not name the offending disk.
+fn validate_disk_path(role: &'static str, path: &Path) -> Result<(), MachineSpecError> {
+    let metadata = std::fs::metadata(path).map_err(|source| MachineSpecError::DiskUnavailable {
+        role,
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let file_type = metadata.file_type();
+    let kind = if file_type.is_file() {
+        return Ok(());
+    } else if file_type.is_dir() {
+        "directory"
+    } else if file_type.is_block_device() {
+        "block device"
+    } else if file_type.is_char_device() {
+        "character device"
+    } else {
+        "special file"
+    };
+    Err(MachineSpecError::DiskNotRegularFile {
+        role,
+        path: path.to_path_buf(),
+        kind,
+    })
+}
+
 fn resolve_spec_path(data_dir: &Path, path: &Path) -> PathBuf {
     if path.is_absolute() {
         path.to_path_buf()
@@ -240,7 +302,9 @@ fn apply_runtime_network(
 ) -> Result<VmConfigBuilder, MachineSpecError> {
     match network {
         RuntimeNetwork::None => Ok(builder.no_network()),
-        RuntimeNetwork::VzNat { .. } => Ok(builder.vz_nat_network()),
+        RuntimeNetwork::VzNat { mac } => {
+            Ok(builder.vz_nat_network(mac.as_deref().map(parse_mac_str).transpose()?))
+        }
         RuntimeNetwork::UnixDatagram { path, mac } => {
             Ok(builder.unix_datagram_network(path.clone(), parse_mac_str(mac)?))
         }
@@ -273,7 +337,10 @@ fn load_host_machine_identifier(
 
 #[cfg(test)]
 mod tests {
-    use super::{apply_runtime_network, vm_spec_machine_config, RuntimeNetwork, VmSpecInputs};
+    use super::{
+        apply_runtime_network, vm_spec_machine_config, MachineSpecError, RuntimeNetwork,
+        VmSpecInputs,
+    };
     use agent_spec::SSH_VSOCK_PORT;
     use std::fs;
     use std::path::PathBuf;
@@ -319,8 +386,13 @@ mod tests {
             hardware: Some(Hardware {
                 cpus: Some(2),
                 memory: Some(1024),
+                min_memory: None,
                 nested_virtualization: Some(false),
                 rosetta: Some(false),
+                graphics: Some(false),
+                keyboard: Some(false),
+                entropy: Some(true),
+                memory_balloon: Some(true),
             }),
             storage: Some(Storage { disks: Vec::new() }),
             ..VmSpec::current()
@@ -354,6 +426,19 @@ mod tests {
         assert_eq!(mac, [0x02, 0, 0, 0, 0, 1]);
     }
 
+    #[test]
+    fn no_network_attaches_no_network_device() {
+        let config = apply_runtime_network(
+            VmConfig::builder("devbox").vz_nat_network(None),
+            &RuntimeNetwork::None,
+        )
+        .expect("runtime network")
+        .build();
+
+        assert_eq!(config.network, virt::NetworkMode::None);
+        assert!(config.unix_datagram_network().is_none());
+    }
+
     #[test]
     fn vznat_network_maps_to_vz_nat_mode() {
         assert_eq!(
@@ -364,7 +449,25 @@ mod tests {
             .expect("runtime network")
             .build()
             .network,
-            virt::NetworkMode::VzNat
+            virt::NetworkMode::VzNat { mac: None }
+        );
+    }
+
+    #[test]
+    fn vznat_network_forwards_configured_mac() {
+        assert_eq!(
+            apply_runtime_network(
+                VmConfig::builder("devbox"),
+                &RuntimeNetwork::VzNat {
+                    mac: Some("02:00:00:00:00:01".to_string())
+                }
+            )
+            .expect("runtime network")
+            .build()
+            .network,
+            virt::NetworkMode::VzNat {
+                mac: Some([0x02, 0, 0, 0, 0, 1])
+            }
         );
     }
 
@@ -433,10 +536,105 @@ mod tests {
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn vm_spec_machine_config_rejects_disk_symlinked_to_directory() {
+        let dir = temp_dir("directory-disk");
+        fs::create_dir_all(dir.join("not-a-disk")).expect("create disk directory");
+        fs::write(dir.join("rootfs.img"), b"root").expect("write root disk");
+        std::os::unix::fs::symlink(dir.join("not-a-disk"), dir.join(DATA_DISK))
+            .expect("link data disk");
+
+        let mut spec = sample_spec(&dir);
+        spec.storage = Some(Storage {
+            disks: vec![
+                Disk {
+                    path: PathBuf::from("rootfs.img"),
+                    read_only: false,
+                },
+                Disk {
+                    path: PathBuf::from(DATA_DISK),
+                    read_only: false,
+                },
+            ],
+        });
+
+        let err = vm_spec_machine_config(VmSpecInputs {
+            name: "devbox",
+            id: "vm790",
+            data_dir: &dir,
+            spec: &spec,
+            network: &RuntimeNetwork::None,
+            guest_services_enabled: false,
+        })
+        .expect_err("directory disk should be rejected");
+
+        assert!(matches!(
+            &err,
+            MachineSpecError::DiskNotRegularFile {
+                role: "data",
+                kind: "directory",
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "data disk {} is a directory, not a regular file",
+                dir.join(DATA_DISK).display()
+            )
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn vm_spec_machine_config_names_overlay_disks() {
+        let dir = temp_dir("overlay-disks");
+        fs::create_dir_all(&dir).expect("create temp dir");
+        fs::write(dir.join("base.img"), b"base").expect("write base disk");
+
+        let mut spec = sample_spec(&dir);
+        spec.storage = Some(Storage {
+            disks: vec![
+                Disk {
+                    path: dir.join("base.img"),
+                    read_only: true,
+                },
+                Disk {
+                    path: PathBuf::from("rootfs.img"),
+                    read_only: false,
+                },
+            ],
+        });
+
+        let err = vm_spec_machine_config(VmSpecInputs {
+            name: "devbox",
+            id: "vm791",
+            data_dir: &dir,
+            spec: &spec,
+            network: &RuntimeNetwork::None,
+            guest_services_enabled: false,
+        })
+        .expect_err("missing overlay root disk should be rejected");
+
+        assert!(matches!(
+            err,
+            MachineSpecError::DiskUnavailable { role: "root", .. }
+        ));
+        assert_eq!(
+            crate::machine::disk_role(&spec.storage.as_ref().expect("storage").disks, 0),
+            "base"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn vm_spec_machine_config_attaches_spec_disks_in_order() {
         let dir = temp_dir("declared-disks");
         fs::create_dir_all(&dir).expect("create temp dir");
+        fs::write(dir.join("rootfs.img"), b"root").expect("write root disk");
+        fs::write(dir.join(DATA_DISK), b"data").expect("write data disk");
 
         let mut spec = sample_spec(&dir);
         spec.storage = Some(Storage {
@@ -10,6 +10,8 @@ mod exit_command;
 mod exit_status;
 mod ext;
 mod guest;
+mod hooks;
+mod idle;
 mod lock;
 mod machine;
 mod net;
@@ -51,6 +53,12 @@ struct Args {
     #[arg(long = "wait-for-registration", default_value_t = 0)]
     wait_for_registration: u64,
 
+    #[arg(
+        long = "idle-timeout",
+        help = "seconds without clients before the machine is stopped"
+    )]
+    idle_timeout: Option<u64>,
+
     #[arg(long = "socket")]
     socket: PathBuf,
 
@@ -76,7 +84,26 @@ struct Args {
     foreground: bool,
 }
 
+/// Probe flag used by libvm to query host features without starting a machine.
+///
+/// Checked before clap parsing because every other vmmon argument is required.
+const HOST_CAPABILITIES_FLAG: &str = "--host-capabilities";
+
 fn main() -> eyre::Result<()> {
+    if std::env::args_os()
+        .nth(1)
+        .is_some_and(|arg| arg == HOST_CAPABILITIES_FLAG)
+    {
+        let capabilities = virt::VirtualMachine::host_capabilities();
+        println!(
+            "{}",
+            serde_json::json!({
+                "nested_virtualization": capabilities.nested_virtualization,
+            })
+        );
+        return Ok(());
+    }
+
     let args = Args::parse();
     let inherited_fds = InheritedPipeFds::from_env()?;
 
@@ -124,16 +151,23 @@ async fn run(args: Args, start_gate: StartGate, sync_reporter: SyncReporter) ->
     let mut start_gate = start_gate;
     let mut sync_reporter = sync_reporter;
     let exit_command =
-        ExitCommand::from_cli(args.exit_command.clone(), args.exit_command_args.clone())?;
+        match ExitCommand::from_cli(args.exit_command.clone(), args.exit_command_args.clone()) {
+            Ok(exit_command) => exit_command,
+            Err(err) => return Err(report_early_failure(&mut sync_reporter, err)),
+        };
     let runtime = RuntimeContext::new(
         args.data_dir.clone(),
         args.config.clone(),
         args.socket.clone(),
         args.serial_log.clone(),
+        args.idle_timeout.map(std::time::Duration::from_secs),
     );
-    let pid_guard = PidGuard::create(&args.pidfile).await?;
+    let pid_guard = match PidGuard::create(&args.pidfile).await {
+        Ok(pid_guard) => pid_guard,
+        Err(err) => return Err(report_early_failure(&mut sync_reporter, err)),
+    };
 
-    let result = match startup::init(
+    let init = startup::init(
         &runtime,
         &args.id,
         &args.name,
@@ -141,13 +175,20 @@ async fn run(args: Args, start_gate: StartGate, sync_reporter: SyncReporter) ->
         args.metadata_config.as_deref(),
         std::time::Duration::from_secs(args.wait_for_registration),
         &mut start_gate,
-    )
-    .await
-    {
-        Ok(ctx) => match services::start_services(&runtime, &ctx, &mut sync_reporter).await {
-            Ok(handles) => shutdown::run(runtime, ctx, handles).await,
-            Err(err) => Err(err),
-        },
+    );
+    let result = match sync_reporter.with_heartbeats(init).await {
+        Ok(ctx) => {
+            let cleanup = (runtime.clone(), ctx.clone());
+            let result = match services::start_services(&runtime, &ctx, &mut sync_reporter).await {
+                Ok(handles) => shutdown::run(runtime, ctx, handles).await,
+                Err(err) => Err(err),
+            };
+            if result.is_err() {
+                let (runtime, ctx) = cleanup;
+                shutdown::stop_after_error(&runtime, &ctx).await;
+            }
+            result
+        }
         Err(err) => Err(err),
     };
 
@@ -179,6 +220,17 @@ async fn run(args: Args, start_gate: StartGate, sync_reporter: SyncReporter) ->
     result
 }
 
+/// Reports a failure from before machine init over the syncpipe, so the
+/// launcher fails fast with the real error instead of an unexpected EOF.
+fn report_early_failure(sync_reporter: &mut SyncReporter, err: eyre::Report) -> eyre::Report {
+    let full_error = format_error_chain(&err);
+    tracing::error!(error = %full_error, "vmmon failed before machine init");
+    if let Err(report_err) = sync_reporter.report_failed(&full_error) {
+        tracing::debug!(error = %report_err, "report early failure on syncpipe");
+    }
+    err
+}
+
 fn format_error_chain(err: &eyre::Report) -> String {
     let mut parts = Vec::new();
     for cause in err.chain() {
@@ -213,8 +265,11 @@ fn daemonize(args: &Args, inherited_fds: InheritedPipeFds) -> eyre::Result<()> {
         cmd.arg("--metadata-config").arg(metadata_config);
     }
     cmd.arg("--wait-for-registration")
-        .arg(args.wait_for_registration.to_string())
-        .arg("--socket")
+        .arg(args.wait_for_registration.to_string());
+    if let Some(idle_timeout) = args.idle_timeout {
+        cmd.arg("--idle-timeout").arg(idle_timeout.to_string());
+    }
+    cmd.arg("--socket")
         .arg(&args.socket)
         .arg("--serial-log")
         .arg(&args.serial_log)
@@ -261,9 +316,34 @@ fn daemonize(_args: &Args, _inherited_fds: InheritedPipeFds) -> eyre::Result<()>
 mod tests {
     use std::path::PathBuf;
 
+    use std::io::Read;
+    use std::os::fd::IntoRawFd;
+
     use clap::Parser;
+    use nix::unistd::pipe;
+
+    use crate::startup::SyncReporter;
+    use crate::{report_early_failure, Args};
 
-    use crate::Args;
+    #[test]
+    fn early_failure_is_reported_on_syncpipe() {
+        let (read_fd, write_fd) = pipe().expect("create pipe");
+        let mut reporter =
+            SyncReporter::from_fd(Some(write_fd.into_raw_fd())).expect("open sync reporter");
+
+        let err = report_early_failure(
+            &mut reporter,
+            eyre::eyre!("pidfile is locked").wrap_err("create pidfile"),
+        );
+        drop(reporter);
+
+        let mut output = String::new();
+        std::fs::File::from(read_fd)
+            .read_to_string(&mut output)
+            .expect("read syncpipe");
+        assert_eq!(output, "failed\tcreate pidfile: pidfile is locked\n");
+        assert_eq!(err.to_string(), "create pidfile");
+    }
 
     #[test]
     fn parses_hidden_exit_command_as_opaque_argv() {
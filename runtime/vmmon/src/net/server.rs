@@ -1,11 +1,11 @@
 use std::future::Future;
 
 use protocol::negotiate::{RejectCode, Upgrade};
-use tokio::net::{UnixListener, UnixStream};
+use tokio::net::UnixListener;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 
-use crate::net::listener::NegotiateListener;
+use crate::net::listener::{NegotiateListener, PendingNegotiation};
 
 pub(crate) struct NegotiationRejection {
     pub(crate) code: RejectCode,
@@ -23,10 +23,12 @@ impl NegotiateServer {
         Self { listener, shutdown }
     }
 
+    /// Rejects connections the policy refuses and hands the rest to `handler`,
+    /// which accepts or rejects the upgrade itself.
     pub(crate) fn listen<P, H, Fut>(self, policy: P, handler: H) -> JoinHandle<eyre::Result<()>>
     where
         P: Fn(&Upgrade) -> Option<NegotiationRejection> + Clone + Send + Sync + 'static,
-        H: Fn(UnixStream, Upgrade) -> Fut + Clone + Send + Sync + 'static,
+        H: Fn(PendingNegotiation) -> Fut + Clone + Send + Sync + 'static,
         Fut: Future<Output = eyre::Result<()>> + Send + 'static,
     {
         tokio::spawn(async move {
@@ -42,16 +44,9 @@ impl NegotiateServer {
                     continue;
                 }
 
-                let (stream, upgrade) = match pending.accept().await {
-                    Ok(accepted) => accepted,
-                    Err(err) => {
-                        tracing::warn!(error = %err, "failed to accept negotiated connection");
-                        continue;
-                    }
-                };
                 let handler = handler.clone();
                 tokio::spawn(async move {
-                    if let Err(err) = handler(stream, upgrade).await {
+                    if let Err(err) = handler(pending).await {
                         tracing::warn!(error = %err, "shell control request failed");
                     }
                 });
@@ -91,7 +86,7 @@ mod tests {
                 }),
                 Upgrade::Serial | Upgrade::Api { .. } => None,
             },
-            move |_stream, _upgrade| {
+            move |_pending| {
                 let handled = handled_by_handler.clone();
                 async move {
                     handled.store(true, Ordering::Release);
@@ -4,16 +4,15 @@ use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::UnixStream;
 use virt::VsockStream;
 
-pub fn spawn_tunnel(stream: UnixStream, vsock_stream: VsockStream) {
-    tokio::spawn(async move {
-        if let Err(err) = proxy_streams(stream, vsock_stream).await {
-            if is_expected_disconnect(&err) {
-                tracing::debug!(error = %err, "vsock relay closed");
-            } else {
-                tracing::error!(error = %err, "vsock relay failed");
-            }
+/// Relays `stream` to `vsock_stream` until either side disconnects.
+pub async fn run_tunnel(stream: UnixStream, vsock_stream: VsockStream) {
+    if let Err(err) = proxy_streams(stream, vsock_stream).await {
+        if is_expected_disconnect(&err) {
+            tracing::debug!(error = %err, "vsock relay closed");
+        } else {
+            tracing::error!(error = %err, "vsock relay failed");
         }
-    });
+    }
 }
 
 async fn proxy_streams(
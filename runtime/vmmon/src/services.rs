@@ -1,34 +1,49 @@
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 use agent_spec::SSH_VSOCK_PORT;
 use eyre::Context;
 use futures::stream::{self, Stream, StreamExt};
 use protocol::negotiate::{RejectCode, Upgrade};
+use protocol::services::{ENDPOINT_API, ENDPOINT_SERIAL, ENDPOINT_SHELL, VM_MONITOR_API_VERSION};
+use protocol::socket::validate_socket_path;
 use protocol::v1::vm_monitor_service_server::{VmMonitorService, VmMonitorServiceServer};
 use protocol::v1::{
-    InspectRequest, InspectResponse, PingRequest, PingResponse, StatusUpdate, WatchStatusRequest,
+    InspectRequest, InspectResponse, LifecycleState, ListServicesRequest, ListServicesResponse,
+    MetricsRequest, MetricsResponse, PauseRequest, PauseResponse, PingRequest, PingResponse,
+    ResumeRequest, ResumeResponse, ServiceDescriptor, ServiceKind, SetBalloonTargetRequest,
+    SetBalloonTargetResponse, StatusUpdate, WatchStatusRequest,
 };
 use tokio::net::{UnixListener, UnixStream};
 use tokio::sync::broadcast;
 use tokio::task::JoinHandle;
 use tonic::{Request, Response, Status};
-use virt::{spawn_serial_tunnel, SerialAccess};
+use virt::{
+    run_serial_tunnel, SerialAccess, SerialBackpressure, SerialOpenOptions, VirtError,
+    VirtualMachine, VsockStream,
+};
+use vm_spec::{ConnectRetry, Hardware};
 
 use crate::context::{DaemonContext, RuntimeContext};
 use crate::endpoints::start_endpoint_supervisor;
 use crate::ext::VmSpecExt;
 use crate::guest::spawn_guest_services;
+use crate::net::listener::PendingNegotiation;
 use crate::net::server::{NegotiateServer, NegotiationRejection};
-use crate::net::tunnel::spawn_tunnel;
+use crate::net::tunnel::run_tunnel;
 use crate::startup::SyncReporter;
 use crate::state::{
     guest_shell_ready as state_guest_shell_ready, select_current_events, select_current_inspect,
-    select_current_ping, Action, InstanceStore, StoreError,
+    select_current_ping, vm_state, Action, InstanceStore, StoreError,
 };
 
 type WatchStatusStream = Pin<Box<dyn Stream<Item = Result<StatusUpdate, Status>> + Send>>;
 const SHELL_RETRY_AFTER_MS: u32 = 1_000;
+/// How long one connection attempt to the guest sshd may take.
+const SHELL_CONNECT_ATTEMPT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long serial output waits for a stalled interactive client before detaching it.
+const SERIAL_CLIENT_STALL_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct ServiceHandles {
     pub(crate) control_socket: JoinHandle<eyre::Result<()>>,
@@ -40,6 +55,8 @@ pub struct ServiceHandles {
 #[derive(Clone)]
 struct VmMonitorSvc {
     store: Arc<InstanceStore>,
+    machine: VirtualMachine,
+    hardware: Option<Hardware>,
 }
 
 #[tonic::async_trait]
@@ -72,45 +89,171 @@ impl VmMonitorService for VmMonitorSvc {
         &self,
         _request: Request<WatchStatusRequest>,
     ) -> Result<Response<Self::WatchStatusStream>, Status> {
+        let stream = status_update_stream(Arc::clone(&self.store)).map_err(store_status)?;
+        Ok(Response::new(stream))
+    }
+
+    async fn pause(
+        &self,
+        _request: Request<PauseRequest>,
+    ) -> Result<Response<PauseResponse>, Status> {
+        let snapshot = self.store.snapshot().map_err(store_status)?;
+        match vm_state(&snapshot) {
+            LifecycleState::Paused => return Ok(Response::new(PauseResponse {})),
+            LifecycleState::Running => {}
+            state => {
+                return Err(Status::failed_precondition(format!(
+                    "vm is {}, only a running vm can be paused",
+                    lifecycle_label(state)
+                )))
+            }
+        }
+
+        self.machine.pause().await.map_err(virt_status)?;
+        self.store
+            .dispatch(Action::vm_paused())
+            .map_err(store_status)?;
+        tracing::info!(service = "vm_monitor.pause", "vm paused");
+        Ok(Response::new(PauseResponse {}))
+    }
+
+    async fn resume(
+        &self,
+        _request: Request<ResumeRequest>,
+    ) -> Result<Response<ResumeResponse>, Status> {
         let snapshot = self.store.snapshot().map_err(store_status)?;
-        let snapshots = select_current_events(&snapshot);
-        let rx = self.store.subscribe();
-
-        let snapshot_stream = stream::iter(snapshots.into_iter().map(Ok));
-        let update_stream = stream::unfold(rx, |mut rx| async move {
-            match rx.recv().await {
-                Ok(update) => Some((Ok(update), rx)),
-                Err(broadcast::error::RecvError::Lagged(skipped)) => Some((
-                    Err(Status::resource_exhausted(format!(
-                        "status stream lagged, skipped {skipped} updates"
-                    ))),
-                    rx,
-                )),
-                Err(broadcast::error::RecvError::Closed) => None,
+        match vm_state(&snapshot) {
+            LifecycleState::Running => return Ok(Response::new(ResumeResponse {})),
+            LifecycleState::Paused => {}
+            state => {
+                return Err(Status::failed_precondition(format!(
+                    "vm is {}, only a paused vm can be resumed",
+                    lifecycle_label(state)
+                )))
             }
-        });
+        }
+
+        self.machine.resume().await.map_err(virt_status)?;
+        self.store
+            .dispatch(Action::vm_resumed())
+            .map_err(store_status)?;
+        tracing::info!(service = "vm_monitor.resume", "vm resumed");
+        Ok(Response::new(ResumeResponse {}))
+    }
+
+    async fn metrics(
+        &self,
+        _request: Request<MetricsRequest>,
+    ) -> Result<Response<MetricsResponse>, Status> {
+        let metrics = self.machine.metrics().await.map_err(virt_status)?;
+        Ok(Response::new(MetricsResponse {
+            memory_mib: metrics.memory_mib,
+            balloon_target_mib: metrics.balloon_target_mib,
+            cpu_time_ms: metrics
+                .cpu_time
+                .map(|cpu_time| u64::try_from(cpu_time.as_millis()).unwrap_or(u64::MAX)),
+        }))
+    }
+
+    async fn set_balloon_target(
+        &self,
+        request: Request<SetBalloonTargetRequest>,
+    ) -> Result<Response<SetBalloonTargetResponse>, Status> {
+        let snapshot = self.store.snapshot().map_err(store_status)?;
+        let state = vm_state(&snapshot);
+        if state != LifecycleState::Running {
+            return Err(Status::failed_precondition(format!(
+                "vm is {}, only a running vm can change its balloon target",
+                lifecycle_label(state)
+            )));
+        }
+
+        let requested = request.into_inner().target_mib;
+        let target_mib = clamp_balloon_target(self.hardware.as_ref(), requested);
+        self.machine
+            .set_balloon_target(target_mib)
+            .await
+            .map_err(virt_status)?;
+        tracing::info!(
+            service = "vm_monitor.set_balloon_target",
+            requested_mib = requested,
+            target_mib,
+            "balloon target set"
+        );
+        Ok(Response::new(SetBalloonTargetResponse { target_mib }))
+    }
+
+    async fn list_services(
+        &self,
+        _request: Request<ListServicesRequest>,
+    ) -> Result<Response<ListServicesResponse>, Status> {
+        Ok(Response::new(ListServicesResponse {
+            services: service_descriptors(&self.store),
+        }))
+    }
+}
 
-        Ok(Response::new(Box::pin(
-            snapshot_stream.chain(update_stream),
-        )))
+/// Clamps a requested balloon target to the machine's `[min_memory, memory]` range.
+fn clamp_balloon_target(hardware: Option<&Hardware>, target_mib: u64) -> u64 {
+    match hardware {
+        Some(hardware) => {
+            u64::from(hardware.clamp_balloon_target(u32::try_from(target_mib).unwrap_or(u32::MAX)))
+        }
+        None => target_mib,
     }
 }
 
+/// Reports every upgrade the control socket serves and whether it would be accepted now.
+fn service_descriptors(store: &InstanceStore) -> Vec<ServiceDescriptor> {
+    [
+        (
+            ENDPOINT_API,
+            Upgrade::Api {
+                api_version: VM_MONITOR_API_VERSION,
+            },
+            ServiceKind::Api,
+            None,
+        ),
+        (ENDPOINT_SERIAL, Upgrade::Serial, ServiceKind::Serial, None),
+        (
+            ENDPOINT_SHELL,
+            Upgrade::Shell,
+            ServiceKind::Vsock,
+            Some(SSH_VSOCK_PORT),
+        ),
+    ]
+    .into_iter()
+    .map(|(name, upgrade, kind, vsock_port)| {
+        let rejection = upgrade_rejection(&upgrade, store);
+        ServiceDescriptor {
+            name: name.to_string(),
+            available: rejection.is_none(),
+            message: rejection
+                .map(|rejection| rejection.message)
+                .unwrap_or_default(),
+            kind: kind.into(),
+            vsock_port,
+        }
+    })
+    .collect()
+}
+
 pub async fn start_services(
     runtime: &RuntimeContext,
     ctx: &DaemonContext,
     sync_reporter: &mut SyncReporter,
 ) -> eyre::Result<ServiceHandles> {
     let path = runtime.socket().to_path_buf();
+    validate_socket_path(&path)?;
     let listener = UnixListener::bind(&path).context(format!("bind socket {}", path.display()))?;
     let server = NegotiateServer::new(listener, ctx.shutdown.clone());
     let policy_store = ctx.store.clone();
     let handler_ctx = ctx.clone();
     let control_socket = server.listen(
         move |upgrade| upgrade_rejection(upgrade, &policy_store),
-        move |stream, upgrade| {
+        move |pending| {
             let ctx = handler_ctx.clone();
-            async move { handle_connection(stream, upgrade, ctx).await }
+            async move { handle_connection(pending, ctx).await }
         },
     );
 
@@ -161,50 +304,177 @@ pub async fn start_services(
     })
 }
 
-pub(crate) async fn serve(stream: UnixStream, store: Arc<InstanceStore>) -> eyre::Result<()> {
+pub(crate) async fn serve(
+    stream: UnixStream,
+    store: Arc<InstanceStore>,
+    machine: VirtualMachine,
+    hardware: Option<Hardware>,
+) -> eyre::Result<()> {
     let incoming = stream::once(async move { Ok::<_, std::io::Error>(stream) });
     tonic::transport::Server::builder()
-        .add_service(VmMonitorServiceServer::new(VmMonitorSvc { store }))
+        .add_service(VmMonitorServiceServer::new(VmMonitorSvc {
+            store,
+            machine,
+            hardware,
+        }))
         .serve_with_incoming(incoming)
         .await?;
     Ok(())
 }
 
-async fn handle_connection(
-    stream: UnixStream,
-    upgrade: Upgrade,
-    ctx: DaemonContext,
-) -> eyre::Result<()> {
-    match upgrade {
+async fn handle_connection(pending: PendingNegotiation, ctx: DaemonContext) -> eyre::Result<()> {
+    let _connection = ctx.idle.connect();
+    match pending.upgrade() {
         Upgrade::Serial => {
+            let (stream, _) = pending.accept().await?;
             let serial_stream = ctx
                 .serial_console
-                .open_stream(SerialAccess::Interactive)
+                .open_stream_with(
+                    SerialOpenOptions::new(SerialAccess::Interactive).backpressure(
+                        SerialBackpressure::Block {
+                            timeout: SERIAL_CLIENT_STALL_TIMEOUT,
+                        },
+                    ),
+                )
                 .await?;
-            spawn_serial_tunnel(stream, serial_stream);
+            run_serial_tunnel(stream, serial_stream).await;
             Ok(())
         }
         Upgrade::Shell => {
             if !guest_shell_ready(&ctx.store)? {
-                tracing::warn!("shell requested before guest shell was ready, closing connection");
-                return Ok(());
+                tracing::warn!("shell requested before guest shell was ready, rejecting");
+                return pending
+                    .reject(
+                        RejectCode::ServiceStarting,
+                        "guest shell is not ready",
+                        Some(SHELL_RETRY_AFTER_MS),
+                    )
+                    .await;
             }
 
-            match ctx.machine.connect_vsock(SSH_VSOCK_PORT).await {
+            // Connect before accepting so a failure reaches the client as a
+            // reject code instead of a closed stream.
+            match connect_shell_backend(&ctx).await {
                 Ok(vsock_stream) => {
-                    spawn_tunnel(stream, vsock_stream);
+                    tracing::debug!(
+                        port = vsock_stream.destination_port(),
+                        source_port = ?vsock_stream.source_port(),
+                        "shell backend connected"
+                    );
+                    let (stream, _) = pending.accept().await?;
+                    run_tunnel(stream, vsock_stream).await;
                     Ok(())
                 }
                 Err(err) => {
-                    tracing::warn!(error = %err, "failed to connect shell backend, closing connection");
-                    Ok(())
+                    tracing::warn!(port = SSH_VSOCK_PORT, error = %err, "failed to connect shell backend, rejecting");
+                    pending
+                        .reject(
+                            err.reject_code(),
+                            err.to_string(),
+                            Some(SHELL_RETRY_AFTER_MS),
+                        )
+                        .await
                 }
             }
         }
-        Upgrade::Api { .. } => serve(stream, ctx.store).await,
+        Upgrade::Api { .. } => {
+            let (stream, _) = pending.accept().await?;
+            serve(stream, ctx.store, ctx.machine, ctx.spec.hardware).await
+        }
     }
 }
 
+/// Why the guest sshd could not be reached.
+#[derive(Debug, thiserror::Error)]
+enum ShellBackendError {
+    #[error("guest sshd did not answer within {timeout:?} on any of {attempts} attempts")]
+    TimedOut { attempts: u32, timeout: Duration },
+
+    #[error("guest sshd refused the connection: {0}")]
+    Failed(VirtError),
+
+    #[error("vmmon is shutting down")]
+    ShuttingDown,
+}
+
+impl ShellBackendError {
+    fn reject_code(&self) -> RejectCode {
+        match self {
+            Self::TimedOut { .. } => RejectCode::Timeout,
+            Self::Failed(_) | Self::ShuttingDown => RejectCode::ServiceUnavailable,
+        }
+    }
+}
+
+/// Connects to the guest sshd, retrying while it comes up.
+///
+/// Reports a timeout only when every attempt timed out, so a guest that never
+/// answers is told apart from one whose sshd refuses connections.
+async fn connect_shell_backend(ctx: &DaemonContext) -> Result<VsockStream, ShellBackendError> {
+    let retry = ctx.spec.connect_retry_or_default();
+    let mut delays = connect_retry_delays(&retry).into_iter();
+    let attempts = retry.attempts.max(1);
+    let mut last_failure = None;
+
+    for attempt in 1..=attempts {
+        match tokio::time::timeout(
+            SHELL_CONNECT_ATTEMPT_TIMEOUT,
+            ctx.machine.connect_vsock(SSH_VSOCK_PORT),
+        )
+        .await
+        {
+            Ok(Ok(stream)) => return Ok(stream),
+            Ok(Err(err)) => {
+                tracing::debug!(
+                    port = SSH_VSOCK_PORT,
+                    attempt,
+                    attempts,
+                    error = %err,
+                    "shell backend not reachable yet"
+                );
+                last_failure = Some(err);
+            }
+            Err(_) => {
+                tracing::debug!(
+                    port = SSH_VSOCK_PORT,
+                    attempt,
+                    attempts,
+                    timeout = ?SHELL_CONNECT_ATTEMPT_TIMEOUT,
+                    "shell backend connect timed out"
+                );
+            }
+        }
+
+        let Some(delay) = delays.next() else {
+            break;
+        };
+        tokio::select! {
+            () = tokio::time::sleep(delay) => {}
+            () = ctx.shutdown.cancelled() => return Err(ShellBackendError::ShuttingDown),
+        }
+    }
+
+    Err(match last_failure {
+        Some(err) => ShellBackendError::Failed(err),
+        None => ShellBackendError::TimedOut {
+            attempts,
+            timeout: SHELL_CONNECT_ATTEMPT_TIMEOUT,
+        },
+    })
+}
+
+/// Delays slept between connection attempts, one fewer than the attempt count.
+fn connect_retry_delays(retry: &ConnectRetry) -> Vec<Duration> {
+    let max = Duration::from_millis(retry.delay_ms.max);
+    let mut delay = Duration::from_millis(retry.delay_ms.initial).min(max);
+    let mut delays = Vec::new();
+    for _ in 1..retry.attempts.max(1) {
+        delays.push(delay);
+        delay = delay.saturating_mul(2).min(max);
+    }
+    delays
+}
+
 fn upgrade_rejection(upgrade: &Upgrade, store: &InstanceStore) -> Option<NegotiationRejection> {
     match upgrade {
         Upgrade::Shell => match guest_shell_ready(store) {
@@ -220,6 +490,15 @@ fn upgrade_rejection(upgrade: &Upgrade, store: &InstanceStore) -> Option<Negotia
                 retry_after_ms: None,
             }),
         },
+        Upgrade::Api { api_version } if *api_version != VM_MONITOR_API_VERSION => {
+            Some(NegotiationRejection {
+                code: RejectCode::UnsupportedApiVersion,
+                message: format!(
+                    "client speaks vm monitor api version {api_version}, this monitor speaks {VM_MONITOR_API_VERSION}; restart the machine to upgrade its monitor"
+                ),
+                retry_after_ms: None,
+            })
+        }
         Upgrade::Serial | Upgrade::Api { .. } => None,
     }
 }
@@ -229,17 +508,96 @@ fn guest_shell_ready(store: &InstanceStore) -> Result<bool, StoreError> {
     Ok(state_guest_shell_ready(&snapshot))
 }
 
+/// Streams the current status events followed by live updates.
+///
+/// A subscriber that falls behind the bus skips the backlog and gets the
+/// current state replayed, so slow clients see the latest state rather than
+/// a queue of stale transitions.
+fn status_update_stream(store: Arc<InstanceStore>) -> Result<WatchStatusStream, StoreError> {
+    let rx = store.subscribe();
+    let snapshot = store.snapshot()?;
+    let snapshot_stream = stream::iter(select_current_events(&snapshot).into_iter().map(Ok));
+    let update_stream = stream::unfold((store, rx), |(store, mut rx)| async move {
+        let batch = match rx.recv().await {
+            Ok(update) => vec![Ok(update)],
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                tracing::debug!(skipped, "status subscriber lagged, replaying current state");
+                rx = rx.resubscribe();
+                match store.snapshot() {
+                    Ok(snapshot) => select_current_events(&snapshot)
+                        .into_iter()
+                        .map(Ok)
+                        .collect(),
+                    Err(err) => vec![Err(store_status(err))],
+                }
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        };
+        Some((stream::iter(batch), (store, rx)))
+    })
+    .flatten();
+
+    Ok(Box::pin(snapshot_stream.chain(update_stream)))
+}
+
 fn store_status(err: StoreError) -> Status {
     Status::internal(err.to_string())
 }
 
+fn virt_status(err: VirtError) -> Status {
+    match err {
+        VirtError::Unimplemented { .. } => Status::unimplemented(err.to_string()),
+        err => Status::internal(err.to_string()),
+    }
+}
+
+fn lifecycle_label(state: LifecycleState) -> &'static str {
+    match state {
+        LifecycleState::Unspecified => "unknown",
+        LifecycleState::Starting => "starting",
+        LifecycleState::Running => "running",
+        LifecycleState::Stopping => "stopping",
+        LifecycleState::Stopped => "stopped",
+        LifecycleState::Error => "in an error state",
+        LifecycleState::Paused => "paused",
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use protocol::negotiate::{RejectCode, Upgrade};
+    use protocol::services::VM_MONITOR_API_VERSION;
+    use protocol::v1::ServiceKind;
+    use vm_spec::{Backoff, ConnectRetry, Hardware};
 
     use crate::state::{new_instance_store, Action};
 
-    use super::upgrade_rejection;
+    use super::{
+        clamp_balloon_target, connect_retry_delays, service_descriptors, status_update_stream,
+        upgrade_rejection, ShellBackendError,
+    };
+
+    #[test]
+    fn balloon_target_is_clamped_to_the_machine_memory_range() {
+        let hardware = Hardware {
+            cpus: None,
+            memory: Some(4096),
+            min_memory: Some(1024),
+            nested_virtualization: None,
+            rosetta: None,
+            graphics: None,
+            keyboard: None,
+            entropy: None,
+            memory_balloon: None,
+        };
+
+        assert_eq!(clamp_balloon_target(Some(&hardware), 512), 1024);
+        assert_eq!(clamp_balloon_target(Some(&hardware), 2048), 2048);
+        assert_eq!(clamp_balloon_target(Some(&hardware), 8192), 4096);
+        assert_eq!(clamp_balloon_target(None, 512), 512);
+    }
 
     #[test]
     fn shell_upgrade_is_rejected_until_guest_is_ready() {
@@ -258,4 +616,131 @@ mod tests {
 
         assert!(upgrade_rejection(&Upgrade::Shell, &store).is_none());
     }
+
+    #[test]
+    fn api_upgrade_is_rejected_on_api_version_mismatch() {
+        let store = new_instance_store();
+
+        assert!(upgrade_rejection(
+            &Upgrade::Api {
+                api_version: VM_MONITOR_API_VERSION,
+            },
+            &store,
+        )
+        .is_none());
+
+        let rejection = upgrade_rejection(
+            &Upgrade::Api {
+                api_version: VM_MONITOR_API_VERSION + 1,
+            },
+            &store,
+        )
+        .expect("api version rejection");
+        assert_eq!(rejection.code, RejectCode::UnsupportedApiVersion);
+        assert!(
+            rejection.message.contains("restart the machine"),
+            "{}",
+            rejection.message
+        );
+        assert_eq!(rejection.retry_after_ms, None);
+    }
+
+    #[test]
+    fn service_descriptors_report_shell_unavailable_until_guest_is_ready() {
+        let store = new_instance_store();
+
+        let starting = service_descriptors(&store);
+        store.dispatch(Action::guest_running()).unwrap();
+        let ready = service_descriptors(&store);
+
+        let names = starting
+            .iter()
+            .map(|service| service.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["api", "serial", "shell"]);
+        assert_eq!(starting[2].kind(), ServiceKind::Vsock);
+        assert_eq!(starting[2].vsock_port, Some(22));
+        assert_eq!(starting[1].vsock_port, None);
+        assert!(!starting[2].available);
+        assert_eq!(starting[2].message, "guest shell is not ready");
+        assert!(ready.iter().all(|service| service.available));
+    }
+
+    #[test]
+    fn default_connect_retry_waits_a_fixed_delay_between_five_attempts() {
+        let delays = connect_retry_delays(&ConnectRetry::default());
+
+        assert_eq!(delays, vec![Duration::from_secs(2); 4]);
+    }
+
+    #[test]
+    fn connect_retry_backs_off_exponentially_up_to_the_cap() {
+        let retry = ConnectRetry {
+            attempts: 5,
+            delay_ms: Backoff {
+                initial: 500,
+                max: 1_500,
+            },
+        };
+
+        let delays = connect_retry_delays(&retry);
+
+        assert_eq!(
+            delays,
+            [500, 1_000, 1_500, 1_500]
+                .map(Duration::from_millis)
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn zero_connect_attempts_still_tries_once() {
+        let retry = ConnectRetry {
+            attempts: 0,
+            ..ConnectRetry::default()
+        };
+
+        assert!(connect_retry_delays(&retry).is_empty());
+    }
+
+    #[test]
+    fn shell_backend_timeout_is_told_apart_from_refusal() {
+        let timed_out = ShellBackendError::TimedOut {
+            attempts: 5,
+            timeout: Duration::from_secs(5),
+        };
+        let refused = ShellBackendError::Failed(virt::VirtError::Io(std::io::Error::from(
+            std::io::ErrorKind::ConnectionRefused,
+        )));
+
+        assert_eq!(timed_out.reject_code(), RejectCode::Timeout);
+        assert_eq!(refused.reject_code(), RejectCode::ServiceUnavailable);
+        assert_eq!(
+            ShellBackendError::ShuttingDown.reject_code(),
+            RejectCode::ServiceUnavailable
+        );
+    }
+
+    #[tokio::test]
+    async fn lagging_status_subscriber_gets_latest_state() {
+        use futures::{FutureExt, StreamExt};
+        use protocol::v1::{LifecycleState, StatusSource};
+
+        let store = std::sync::Arc::new(new_instance_store());
+        let mut updates = status_update_stream(std::sync::Arc::clone(&store)).expect("stream");
+        for _ in 0..300 {
+            store.dispatch(Action::vm_starting()).expect("dispatch");
+        }
+        store.dispatch(Action::vm_running()).expect("dispatch");
+
+        let update = updates
+            .next()
+            .await
+            .expect("update")
+            .expect("status update");
+
+        assert_eq!(update.source, StatusSource::Vm as i32);
+        assert_eq!(update.state, LifecycleState::Running as i32);
+        assert!(updates.next().now_or_never().is_none());
+    }
 }
@@ -5,6 +5,7 @@ use tokio::signal;
 use virt::VmExit;
 
 use crate::context::{DaemonContext, RuntimeContext};
+use crate::hooks;
 use crate::services::ServiceHandles;
 use crate::state::{select_current_inspect, Action};
 
@@ -25,6 +26,16 @@ pub async fn run(
             ctx.shutdown.cancel();
             graceful_stop(&ctx).await?
         }
+        () = ctx.idle.wait_idle() => {
+            let timeout = ctx.idle.timeout().unwrap_or_default();
+            tracing::info!(instance = %ctx.machine.name(), ?timeout, "idle timeout reached");
+            ctx.store.dispatch(Action::VmTransition {
+                state: LifecycleState::Stopping,
+                message: format!("idle for {}s, shutting down", timeout.as_secs()),
+            })?;
+            ctx.shutdown.cancel();
+            graceful_stop(&ctx).await?
+        }
         result = wait_for_machine_stop(&ctx.machine) => {
             let stop_info = result?;
             tracing::info!(instance = %ctx.machine.name(), message = %stop_info.message, "machine exited");
@@ -117,15 +128,47 @@ async fn wait_for_machine_stop(machine: &virt::VirtualMachine) -> Result<VmStopI
     Ok(VmStopInfo { message })
 }
 
-async fn cleanup(_runtime: &RuntimeContext, ctx: &DaemonContext) -> eyre::Result<()> {
+async fn cleanup(runtime: &RuntimeContext, ctx: &DaemonContext) -> eyre::Result<()> {
     let snapshot = ctx.store.snapshot()?;
     let inspect = select_current_inspect(&snapshot);
     tracing::debug!(summary = %inspect.summary, "final vmmon status snapshot");
 
     tracing::info!(instance = %ctx.machine.name(), "instance stopped");
+    hooks::run_post_stop(&ctx.spec, ctx.machine.name(), runtime.dir()).await;
     Ok(())
 }
 
+/// Stops the VM after vmmon failed on its way to, or during, a normal shutdown.
+///
+/// Failures are only logged because the original error is what vmmon reports.
+pub(crate) async fn stop_after_error(runtime: &RuntimeContext, ctx: &DaemonContext) {
+    let already_exited = match ctx.machine.try_wait().await {
+        Ok(exit) => exit.is_some(),
+        Err(err) => {
+            tracing::debug!(error = %err, "check vm exit before error stop");
+            false
+        }
+    };
+    if !already_exited {
+        match tokio::time::timeout(VM_STOP_TIMEOUT, ctx.machine.stop()).await {
+            Ok(Ok(())) => {
+                tracing::info!(instance = %ctx.machine.name(), "vm stopped after vmmon error");
+            }
+            Ok(Err(err)) => {
+                tracing::warn!(instance = %ctx.machine.name(), error = %err, "stop vm after vmmon error");
+            }
+            Err(_) => {
+                tracing::warn!(
+                    instance = %ctx.machine.name(),
+                    timeout = ?VM_STOP_TIMEOUT,
+                    "timed out stopping vm after vmmon error"
+                );
+            }
+        }
+    }
+    hooks::run_post_stop(&ctx.spec, ctx.machine.name(), runtime.dir()).await;
+}
+
 async fn wait_for_signal() {
     let ctrl_c = async {
         signal::ctrl_c().await.expect("install Ctrl+C handler");
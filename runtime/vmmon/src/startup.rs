@@ -1,4 +1,5 @@
 use std::fs::File;
+use std::future::Future;
 use std::io::{self, Read, Write};
 use std::os::fd::{BorrowedFd, FromRawFd, RawFd};
 use std::path::{Path, PathBuf};
@@ -13,6 +14,8 @@ use virt::VirtualMachine;
 use vm_spec::VmSpec;
 
 use crate::context::{DaemonContext, RuntimeContext};
+use crate::hooks;
+use crate::idle::IdleTracker;
 use crate::machine::{
     machine_identifier_path_from_dir, vm_spec_machine_config, RuntimeNetwork, VmSpecInputs,
 };
@@ -20,6 +23,8 @@ use crate::state::{new_instance_store, Action};
 
 pub const ENV_STARTPIPE: &str = "_VM_STARTPIPE";
 pub const ENV_SYNCPIPE: &str = "_VM_SYNCPIPE";
+/// How often vmmon tells the launcher it is still starting.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(2);
 
 #[derive(Clone, Copy, Debug)]
 pub struct InheritedPipeFds {
@@ -87,6 +92,7 @@ impl StartGate {
 
 pub struct SyncReporter {
     file: Option<File>,
+    heartbeats: bool,
 }
 
 impl SyncReporter {
@@ -100,14 +106,20 @@ impl SyncReporter {
     fn from_sync_fd(fd: RawFd) -> io::Result<Self> {
         set_cloexec(fd, true)?;
         let file = unsafe { File::from_raw_fd(fd) };
-        Ok(Self { file: Some(file) })
+        Ok(Self {
+            file: Some(file),
+            heartbeats: true,
+        })
     }
 
     fn from_stdout() -> io::Result<Self> {
         let borrowed = unsafe { BorrowedFd::borrow_raw(libc::STDOUT_FILENO) };
         let duplicated = nix::unistd::dup(borrowed).map_err(io::Error::other)?;
         let file = File::from(duplicated);
-        Ok(Self { file: Some(file) })
+        Ok(Self {
+            file: Some(file),
+            heartbeats: false,
+        })
     }
 
     pub fn report_started(&mut self) -> io::Result<()> {
@@ -118,6 +130,37 @@ impl SyncReporter {
         self.write_message(&format!("failed\t{message}\n"))
     }
 
+    /// Runs `work`, reporting a heartbeat every [`HEARTBEAT_INTERVAL`] until it completes.
+    ///
+    /// Heartbeats let the launcher tell a slow boot from a hung vmmon. They are
+    /// only sent over a syncpipe, never to stdout in foreground mode.
+    pub async fn with_heartbeats<F: Future>(&mut self, work: F) -> F::Output {
+        tokio::pin!(work);
+        let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                output = &mut work => return output,
+                _ = interval.tick() => {
+                    if let Err(err) = self.report_heartbeat() {
+                        tracing::debug!(error = %err, "syncpipe heartbeat failed");
+                    }
+                }
+            }
+        }
+    }
+
+    fn report_heartbeat(&mut self) -> io::Result<()> {
+        if !self.heartbeats {
+            return Ok(());
+        }
+        let Some(file) = self.file.as_mut() else {
+            return Ok(());
+        };
+        file.write_all(b"heartbeat\n")?;
+        file.flush()
+    }
+
     fn write_message(&mut self, message: &str) -> io::Result<()> {
         let Some(mut file) = self.file.take() else {
             return Ok(());
@@ -166,8 +209,18 @@ pub async fn init(
 
     store.dispatch(Action::vm_starting())?;
     start_gate.wait_for_release().await?;
-    machine.start().await?;
-    store.dispatch(Action::vm_running())?;
+    hooks::run_pre_start(&spec, name, runtime.dir()).await?;
+    if let Err(err) = machine.start().await {
+        hooks::run_post_stop(&spec, name, runtime.dir()).await;
+        return Err(err.into());
+    }
+    if let Err(err) = store.dispatch(Action::vm_running()) {
+        if let Err(stop_err) = machine.stop().await {
+            tracing::warn!(error = %stop_err, "stop vm after failed startup");
+        }
+        hooks::run_post_stop(&spec, name, runtime.dir()).await;
+        return Err(err.into());
+    }
 
     Ok(DaemonContext {
         spec,
@@ -177,6 +230,7 @@ pub async fn init(
         machine,
         serial_console,
         store,
+        idle: Arc::new(IdleTracker::new(runtime.idle_timeout())),
         shutdown: CancellationToken::new(),
     })
 }
@@ -184,7 +238,7 @@ pub async fn init(
 fn load_spec(runtime: &RuntimeContext) -> eyre::Result<VmSpec> {
     let raw = std::fs::read_to_string(runtime.config())
         .wrap_err_with(|| format!("read vm spec at {}", runtime.config().display()))?;
-    serde_json::from_str(&raw)
+    VmSpec::from_json_str(&raw)
         .map_err(|err| eyre::eyre!("parse vm spec at {}: {}", runtime.config().display(), err))
 }
 
@@ -342,6 +396,27 @@ mod tests {
         assert_eq!(message, "failed\tvz failed\n");
     }
 
+    #[tokio::test]
+    async fn sync_reporter_sends_heartbeats_until_work_completes() {
+        let (read_fd, write_fd) = pipe().expect("create pipe");
+        let mut reporter =
+            SyncReporter::from_fd(Some(write_fd.into_raw_fd())).expect("open sync reporter");
+
+        let value = reporter
+            .with_heartbeats(async {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                7
+            })
+            .await;
+        reporter.report_started().expect("report started");
+
+        let mut file = std::fs::File::from(read_fd);
+        let mut message = String::new();
+        file.read_to_string(&mut message).expect("read message");
+        assert_eq!(value, 7);
+        assert_eq!(message, "heartbeat\nstarted\n");
+    }
+
     #[test]
     fn network_parser_rejects_unsupported_runtime_attachments() {
         assert!(parse_network_arg("unixstream,/tmp/net.sock,mac=02:00:00:00:00:01").is_err());
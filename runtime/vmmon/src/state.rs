@@ -98,6 +98,7 @@ pub(crate) struct InstanceState {
     vm: LifecycleState,
     guest: LifecycleState,
     guest_message: String,
+    guest_addresses: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -110,6 +111,9 @@ pub(crate) enum Action {
         state: LifecycleState,
         message: String,
     },
+    GuestNetwork {
+        addresses: Vec<String>,
+    },
 }
 
 impl Action {
@@ -127,6 +131,20 @@ impl Action {
         }
     }
 
+    pub(crate) fn vm_paused() -> Self {
+        Self::VmTransition {
+            state: LifecycleState::Paused,
+            message: String::from("vm paused"),
+        }
+    }
+
+    pub(crate) fn vm_resumed() -> Self {
+        Self::VmTransition {
+            state: LifecycleState::Running,
+            message: String::from("vm resumed"),
+        }
+    }
+
     pub(crate) fn guest_starting() -> Self {
         Self::GuestTransition {
             state: LifecycleState::Starting,
@@ -141,6 +159,10 @@ impl Action {
         }
     }
 
+    pub(crate) fn guest_network(addresses: Vec<String>) -> Self {
+        Self::GuestNetwork { addresses }
+    }
+
     pub(crate) fn guest_error(message: impl Into<String>) -> Self {
         Self::GuestTransition {
             state: LifecycleState::Error,
@@ -174,6 +196,7 @@ pub(crate) fn select_current_inspect(state: &InstanceState) -> InspectResponse {
         guest_state: state.guest as i32,
         ready: state.vm == LifecycleState::Running && state.guest == LifecycleState::Running,
         summary: status_summary(state),
+        guest_addresses: state.guest_addresses.clone(),
     }
 }
 
@@ -195,6 +218,10 @@ pub(crate) fn select_current_events(state: &InstanceState) -> Vec<StatusUpdate>
     events
 }
 
+pub(crate) fn vm_state(state: &InstanceState) -> LifecycleState {
+    state.vm
+}
+
 pub(crate) fn guest_shell_ready(state: &InstanceState) -> bool {
     state.guest == LifecycleState::Running
 }
@@ -210,6 +237,9 @@ fn reduce_instance_state(current: &InstanceState, action: &Action) -> InstanceSt
             next.guest = *state;
             next.guest_message = message.clone();
         }
+        Action::GuestNetwork { addresses } => {
+            next.guest_addresses = addresses.clone();
+        }
     }
 
     next
@@ -225,6 +255,7 @@ fn project_status_update(action: &Action) -> Option<StatusUpdate> {
             *state,
             message.clone(),
         )),
+        Action::GuestNetwork { .. } => None,
     }
 }
 
@@ -246,7 +277,12 @@ mod tests {
 
     use tokio::sync::broadcast::error::TryRecvError;
 
-    use crate::state::{new_instance_store, Action, StoreError};
+    use protocol::v1::LifecycleState;
+
+    use crate::state::{
+        new_instance_store, select_current_inspect, select_current_ping, vm_state, Action,
+        StoreError,
+    };
 
     #[test]
     fn dispatch_updates_state_before_publishing() {
@@ -260,6 +296,37 @@ mod tests {
         assert!(rx.try_recv().is_ok());
     }
 
+    #[test]
+    fn guest_addresses_are_inspectable_without_a_status_event() {
+        let store = new_instance_store();
+        let mut rx = store.subscribe();
+
+        store
+            .dispatch(Action::guest_network(vec!["192.168.64.5".to_string()]))
+            .unwrap();
+
+        let inspect = select_current_inspect(&store.snapshot().unwrap());
+        assert_eq!(inspect.guest_addresses, vec!["192.168.64.5".to_string()]);
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[test]
+    fn paused_vm_is_not_ready_until_resumed() {
+        let store = new_instance_store();
+        store.dispatch(Action::vm_running()).unwrap();
+        store.dispatch(Action::guest_running()).unwrap();
+
+        store.dispatch(Action::vm_paused()).unwrap();
+        let paused = store.snapshot().unwrap();
+        store.dispatch(Action::vm_resumed()).unwrap();
+        let resumed = store.snapshot().unwrap();
+
+        assert_eq!(vm_state(&paused), LifecycleState::Paused);
+        assert!(!select_current_ping(&paused).ok);
+        assert_eq!(vm_state(&resumed), LifecycleState::Running);
+        assert!(select_current_ping(&resumed).ok);
+    }
+
     #[test]
     fn poisoned_store_does_not_publish_events() {
         let store = Arc::new(new_instance_store());
@@ -1,5 +1,6 @@
 pub mod negotiate;
 pub mod services;
+pub mod socket;
 
 pub use prost_types;
 
@@ -37,6 +38,13 @@ pub mod v1 {
     }
 }
 
+/// Machine name exported to vmmon and every process it spawns.
+pub const ENV_BENTO_MACHINE: &str = "BENTO_MACHINE";
+/// Machine data directory exported to vmmon and every process it spawns.
+pub const ENV_BENTO_MACHINE_DIR: &str = "BENTO_MACHINE_DIR";
+/// Upper bound on how long vmmon may take to report startup, even while heartbeating.
+pub const VMMON_START_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
 pub const DEFAULT_GUEST_CONTROL_PORT: u32 = 1027;
 pub const KERNEL_PARAM_GUEST_PORT: &str = "bento.guest.port";
 
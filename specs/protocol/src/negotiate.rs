@@ -42,6 +42,8 @@ pub enum RejectCode {
     PermissionDenied,
     AuthFailed,
     Internal,
+    Timeout,
+    UnsupportedApiVersion,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -113,6 +115,16 @@ impl Negotiate {
     pub async fn client_upgrade_stream_v1(
         stream: tokio::net::UnixStream,
         upgrade: Upgrade,
+    ) -> Result<tokio::net::UnixStream, ClientUpgradeStreamError> {
+        Self::client_upgrade_stream_v1_with_timeout(stream, upgrade, NEGOTIATE_STREAM_TIMEOUT).await
+    }
+
+    /// Like `client_upgrade_stream_v1`, for upgrades the server may take longer
+    /// to answer, such as a shell that waits for the guest service.
+    pub async fn client_upgrade_stream_v1_with_timeout(
+        stream: tokio::net::UnixStream,
+        upgrade: Upgrade,
+        timeout: Duration,
     ) -> Result<tokio::net::UnixStream, ClientUpgradeStreamError> {
         let mut stream = stream;
         let negotiate = async {
@@ -130,7 +142,7 @@ impl Negotiate {
             }
         };
 
-        match tokio::time::timeout(NEGOTIATE_STREAM_TIMEOUT, negotiate).await {
+        match tokio::time::timeout(timeout, negotiate).await {
             Ok(Ok(())) => Ok(stream),
             Ok(Err(err)) => Err(err),
             Err(_) => Err(ClientUpgradeStreamError::Io(io::Error::new(
@@ -372,6 +384,116 @@ mod tests {
         });
     }
 
+    #[test]
+    fn frames_reassemble_from_single_byte_reads() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("build tokio runtime");
+
+        rt.block_on(async {
+            // A one byte duplex buffer hands the reader every frame a byte at a time.
+            let (mut writer, mut reader) = tokio::io::duplex(1);
+            let mut request = Negotiate::new(11, Upgrade::Api { api_version: 1 });
+            request.auth_token = Some("token".to_string());
+            let response = Response::Reject(Reject {
+                request_id: 11,
+                code: RejectCode::ServiceStarting,
+                message: "starting".to_string(),
+                retry_after_ms: Some(250),
+            });
+
+            let write_task = tokio::spawn({
+                let request = request.clone();
+                let response = response.clone();
+                async move {
+                    request
+                        .write_to(&mut writer)
+                        .await
+                        .expect("write negotiate request");
+                    response
+                        .write_to(&mut writer)
+                        .await
+                        .expect("write reject response");
+                }
+            });
+
+            let decoded_request = Negotiate::read_from(&mut reader)
+                .await
+                .expect("read negotiate request");
+            let decoded_response = Response::read_from(&mut reader)
+                .await
+                .expect("read response frame");
+
+            write_task.await.expect("writer task join");
+            assert_eq!(decoded_request, request);
+            assert_eq!(decoded_response, response);
+        });
+    }
+
+    #[test]
+    fn back_to_back_frames_read_independently() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("build tokio runtime");
+
+        rt.block_on(async {
+            let first = Response::Accept(Accept {
+                request_id: 1,
+                message: None,
+            });
+            let second = Response::Accept(Accept {
+                request_id: 2,
+                message: Some("again".to_string()),
+            });
+            let mut bytes = Vec::new();
+            first.write_to(&mut bytes).await.expect("write first frame");
+            second
+                .write_to(&mut bytes)
+                .await
+                .expect("write second frame");
+
+            let mut reader = bytes.as_slice();
+            let decoded_first = Response::read_from(&mut reader)
+                .await
+                .expect("read first frame");
+            let decoded_second = Response::read_from(&mut reader)
+                .await
+                .expect("read second frame");
+
+            assert_eq!(decoded_first, first);
+            assert_eq!(decoded_second, second);
+            assert!(reader.is_empty());
+        });
+    }
+
+    #[test]
+    fn truncated_frame_reports_unexpected_eof() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("build tokio runtime");
+
+        rt.block_on(async {
+            let mut bytes = Vec::new();
+            Response::Accept(Accept {
+                request_id: 3,
+                message: Some("cut short".to_string()),
+            })
+            .write_to(&mut bytes)
+            .await
+            .expect("write frame");
+            bytes.truncate(bytes.len() - 2);
+
+            let mut reader = bytes.as_slice();
+            let err = Response::read_from(&mut reader)
+                .await
+                .expect_err("truncated frame should fail");
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        });
+    }
+
     #[test]
     fn client_upgrade_stream_accepts_response() {
         let rt = tokio::runtime::Builder::new_current_thread()
@@ -449,4 +571,42 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn client_upgrade_stream_with_timeout_gives_up_on_silent_server() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("build tokio runtime");
+
+        rt.block_on(async {
+            let (client, mut server) =
+                tokio::net::UnixStream::pair().expect("create unix stream pair");
+
+            let server_task = tokio::spawn(async move {
+                Negotiate::read_from(&mut server)
+                    .await
+                    .expect("read negotiate request");
+                server
+            });
+
+            let result = Negotiate::client_upgrade_stream_v1_with_timeout(
+                client,
+                Upgrade::Shell,
+                Duration::from_millis(50),
+            )
+            .await;
+
+            let _server = server_task.await.expect("server task join");
+            match result {
+                Err(ClientUpgradeStreamError::Io(err)) => {
+                    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+                }
+                Ok(_) => panic!("expected timeout"),
+                Err(ClientUpgradeStreamError::Reject(reject)) => {
+                    panic!("unexpected reject: {}", reject.message);
+                }
+            }
+        });
+    }
 }
@@ -2,3 +2,9 @@
 pub const ENDPOINT_SHELL: &str = "shell";
 pub const ENDPOINT_SERIAL: &str = "serial";
 pub const ENDPOINT_API: &str = "api";
+
+/// vm monitor gRPC API version negotiated on every control connection.
+///
+/// Bump this whenever an RPC is added or changed, so a client never talks to a
+/// monitor that was started by a different release.
+pub const VM_MONITOR_API_VERSION: u32 = 2;
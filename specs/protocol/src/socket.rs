@@ -0,0 +1,63 @@
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+/// Bytes `sockaddr_un.sun_path` can hold, excluding the trailing NUL.
+#[cfg(target_os = "macos")]
+pub const MAX_SOCKET_PATH_BYTES: usize = 103;
+#[cfg(not(target_os = "macos"))]
+pub const MAX_SOCKET_PATH_BYTES: usize = 107;
+
+/// A Unix socket path that does not fit in `sockaddr_un.sun_path`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SocketPathTooLong {
+    pub path: PathBuf,
+    pub len: usize,
+    pub max: usize,
+}
+
+impl std::fmt::Display for SocketPathTooLong {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "socket path {} is {} bytes, over the {} byte limit for Unix sockets; point XDG_RUNTIME_DIR at a shorter directory",
+            self.path.display(),
+            self.len,
+            self.max
+        )
+    }
+}
+
+impl std::error::Error for SocketPathTooLong {}
+
+/// Rejects socket paths the kernel would refuse to bind or connect to.
+pub fn validate_socket_path(path: &Path) -> Result<(), SocketPathTooLong> {
+    let len = path.as_os_str().as_bytes().len();
+    if len > MAX_SOCKET_PATH_BYTES {
+        return Err(SocketPathTooLong {
+            path: path.to_path_buf(),
+            len,
+            max: MAX_SOCKET_PATH_BYTES,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::socket::{validate_socket_path, SocketPathTooLong, MAX_SOCKET_PATH_BYTES};
+
+    #[test]
+    fn socket_paths_over_the_sun_path_limit_are_rejected() {
+        let fits = PathBuf::from(format!("/{}", "a".repeat(MAX_SOCKET_PATH_BYTES - 1)));
+        let too_long = PathBuf::from(format!("/{}", "a".repeat(MAX_SOCKET_PATH_BYTES)));
+
+        assert!(validate_socket_path(&fits).is_ok());
+        assert!(matches!(
+            validate_socket_path(&too_long),
+            Err(SocketPathTooLong { len, max, .. })
+                if len == MAX_SOCKET_PATH_BYTES + 1 && max == MAX_SOCKET_PATH_BYTES
+        ));
+    }
+}
@@ -2,8 +2,56 @@ use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use semver::Version;
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+/// Spec format version written by this crate.
+pub const CURRENT_SPEC_VERSION: Version = Version::new(0, 1, 0);
+
+const SPEC_VERSION_FIELD: &str = "specVersion";
+
+/// A single upgrade step applied to a raw spec document.
+struct Migration {
+    from: Version,
+    to: Version,
+    apply: fn(&mut Map<String, Value>),
+}
+
+/// Ordered upgrade steps. Each step moves a document from `from` to `to`,
+/// renaming or defaulting fields before the typed deserialize runs.
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: CURRENT_SPEC_VERSION,
+    to: CURRENT_SPEC_VERSION,
+    apply: |_| {},
+}];
+
+/// Errors raised while loading a persisted spec document.
+#[derive(Debug, thiserror::Error)]
+pub enum VmSpecError {
+    #[error("vm spec document must be a JSON object")]
+    NotAnObject,
+
+    #[error("vm spec document is missing `specVersion`")]
+    MissingVersion,
+
+    #[error("vm spec version `{value}` is not a valid semantic version: {source}")]
+    InvalidVersion {
+        value: String,
+        #[source]
+        source: semver::Error,
+    },
+
+    #[error(
+        "vm spec version {found} is newer than the supported version {supported}; upgrade bento to read it"
+    )]
+    UnsupportedVersion { found: Version, supported: Version },
+
+    #[error("no migration path from vm spec version {found} to {supported}")]
+    NoMigrationPath { found: Version, supported: Version },
+
+    #[error("decode vm spec: {0}")]
+    Decode(#[from] serde_json::Error),
+}
 
 /// Top-level Bento virtual machine specification.
 ///
@@ -32,6 +80,9 @@ pub struct VmSpec {
     /// Vsock endpoints supervised alongside the VM.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub vsock: Option<Vsock>,
+    /// Host commands run around the VM lifecycle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<Hooks>,
     /// Free-form metadata for callers that need non-standard annotations.
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub annotations: BTreeMap<String, String>,
@@ -41,16 +92,85 @@ impl VmSpec {
     /// Create a minimal spec at the current schema version.
     pub fn current() -> Self {
         Self {
-            spec_version: Version::new(0, 1, 0),
+            spec_version: CURRENT_SPEC_VERSION,
             guest: None,
             boot: None,
             hardware: None,
             storage: None,
             mounts: Vec::new(),
             vsock: None,
+            hooks: None,
             annotations: BTreeMap::new(),
         }
     }
+
+    /// Parse a JSON spec document, upgrading it to the current version first.
+    pub fn from_json_str(raw: &str) -> Result<Self, VmSpecError> {
+        Self::migrate(serde_json::from_str(raw)?)
+    }
+
+    /// Upgrade a raw spec document to [`CURRENT_SPEC_VERSION`] and decode it.
+    ///
+    /// Documents written by a newer release are rejected rather than decoded
+    /// with fields silently dropped.
+    ///
+    /// The upgraded document is not written back. Specs are read on paths that
+    /// hold no machine lock, such as list and inspect, so a write there could race
+    /// an update. The next config update or rebuild saves the spec at the current
+    /// version, and until then every load applies the same migrations again.
+    pub fn migrate(raw: Value) -> Result<Self, VmSpecError> {
+        let Value::Object(mut document) = raw else {
+            return Err(VmSpecError::NotAnObject);
+        };
+
+        let mut version = document_version(&document)?;
+        if version > CURRENT_SPEC_VERSION {
+            return Err(VmSpecError::UnsupportedVersion {
+                found: version,
+                supported: CURRENT_SPEC_VERSION,
+            });
+        }
+
+        loop {
+            let Some(step) = MIGRATIONS.iter().find(|step| step.from == version) else {
+                return Err(VmSpecError::NoMigrationPath {
+                    found: version,
+                    supported: CURRENT_SPEC_VERSION,
+                });
+            };
+            (step.apply)(&mut document);
+            if step.to == version {
+                break;
+            }
+            version = step.to.clone();
+        }
+
+        document.insert(
+            SPEC_VERSION_FIELD.to_string(),
+            Value::String(CURRENT_SPEC_VERSION.to_string()),
+        );
+        Ok(serde_json::from_value(Value::Object(document))?)
+    }
+}
+
+/// Serde adapter that routes a nested spec through [`VmSpec::migrate`].
+pub fn deserialize_migrated<'de, D>(deserializer: D) -> Result<VmSpec, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = Value::deserialize(deserializer)?;
+    VmSpec::migrate(raw).map_err(serde::de::Error::custom)
+}
+
+fn document_version(document: &Map<String, Value>) -> Result<Version, VmSpecError> {
+    let value = match document.get(SPEC_VERSION_FIELD) {
+        Some(Value::String(value)) => value,
+        Some(_) | None => return Err(VmSpecError::MissingVersion),
+    };
+    Version::parse(value).map_err(|source| VmSpecError::InvalidVersion {
+        value: value.clone(),
+        source,
+    })
 }
 
 /// Guest operating system configuration.
@@ -107,12 +227,38 @@ pub struct Hardware {
     /// Guest memory size in MiB, using binary mebibytes.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub memory: Option<u32>,
+    /// Floor in MiB below which the memory balloon never reclaims guest memory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_memory: Option<u32>,
     /// Enables nested virtualization when supported by the backend.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub nested_virtualization: Option<bool>,
     /// Enables Rosetta integration for supported guests and hosts.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub rosetta: Option<bool>,
+    /// Attaches a graphics device and pointing device for graphical guests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graphics: Option<bool>,
+    /// Attaches a USB keyboard.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyboard: Option<bool>,
+    /// Attaches a virtio entropy device. Defaults to on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entropy: Option<bool>,
+    /// Attaches a virtio memory balloon device. Defaults to on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_balloon: Option<bool>,
+}
+
+impl Hardware {
+    /// Clamps a balloon target in MiB to `[min_memory, memory]`.
+    ///
+    /// An unset bound leaves that side of the range open.
+    pub fn clamp_balloon_target(&self, target_mib: u32) -> u32 {
+        let floor = self.min_memory.unwrap_or(0);
+        let ceiling = self.memory.unwrap_or(u32::MAX).max(floor);
+        target_mib.clamp(floor, ceiling)
+    }
 }
 
 /// Ordered disk attachments.
@@ -155,6 +301,60 @@ pub struct Vsock {
     /// Endpoints supervised for this VM.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub endpoints: Vec<VsockEndpoint>,
+    /// Retry policy for host connections to guest services such as the shell.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_retry: Option<ConnectRetry>,
+}
+
+/// Host commands run by the VM monitor around the VM lifecycle.
+///
+/// Hooks run in order with `BENTO_MACHINE` and `BENTO_MACHINE_DIR` set. A failing
+/// pre-start hook aborts the start; post-stop failures are only logged.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hooks {
+    /// Commands run before the VM starts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_start: Vec<Hook>,
+    /// Commands run after the VM has stopped.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_stop: Vec<Hook>,
+}
+
+/// Host command run as a lifecycle hook.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hook {
+    /// Executable path or name resolved through `PATH`.
+    pub command: PathBuf,
+    /// Arguments passed to the command.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+}
+
+/// Retry policy for opening a connection to a guest vsock service.
+///
+/// The delay doubles after each failed attempt until it reaches `max`. Set
+/// `initial` and `max` to the same value for a fixed delay.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectRetry {
+    /// Total connection attempts, including the first one.
+    pub attempts: u32,
+    /// Delay between attempts in milliseconds.
+    pub delay_ms: Backoff,
+}
+
+impl Default for ConnectRetry {
+    fn default() -> Self {
+        Self {
+            attempts: 5,
+            delay_ms: Backoff {
+                initial: 2_000,
+                max: 2_000,
+            },
+        }
+    }
 }
 
 /// A host-side service bound to a guest vsock port.
@@ -271,8 +471,9 @@ mod tests {
     use serde_json::json;
 
     use crate::{
-        Backoff, Boot, Disk, Guest, GuestOs, Hardware, Kernel, Lifecycle, Mount, Plugin,
-        RestartPolicy, Storage, VmSpec, Vsock, VsockEndpoint, VsockEndpointMode,
+        Backoff, Boot, ConnectRetry, Disk, Guest, GuestOs, Hardware, Kernel, Lifecycle, Mount,
+        Plugin, RestartPolicy, Storage, VmSpec, VmSpecError, Vsock, VsockEndpoint,
+        VsockEndpointMode, CURRENT_SPEC_VERSION,
     };
 
     #[test]
@@ -311,8 +512,13 @@ mod tests {
             hardware: Some(Hardware {
                 cpus: Some(4),
                 memory: Some(4096),
+                min_memory: None,
                 nested_virtualization: Some(false),
                 rosetta: Some(true),
+                graphics: None,
+                keyboard: None,
+                entropy: None,
+                memory_balloon: None,
             }),
             storage: Some(Storage {
                 disks: vec![Disk {
@@ -347,6 +553,13 @@ mod tests {
                         },
                     },
                 }],
+                connect_retry: Some(ConnectRetry {
+                    attempts: 10,
+                    delay_ms: Backoff {
+                        initial: 500,
+                        max: 4_000,
+                    },
+                }),
             }),
             annotations: BTreeMap::from([("io.bentobox.demo".to_string(), "true".to_string())]),
             ..VmSpec::current()
@@ -400,7 +613,11 @@ mod tests {
                                 "backoffMs": { "initial": 200, "max": 5000 }
                             }
                         }
-                    ]
+                    ],
+                    "connectRetry": {
+                        "attempts": 10,
+                        "delayMs": { "initial": 500, "max": 4000 }
+                    }
                 },
                 "annotations": { "io.bentobox.demo": "true" }
             })
@@ -437,4 +654,66 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn migrate_accepts_current_version() {
+        let spec = VmSpec::migrate(json!({
+            "specVersion": CURRENT_SPEC_VERSION.to_string(),
+            "hardware": { "cpus": 2 }
+        }))
+        .expect("migrate current spec");
+
+        assert_eq!(spec.spec_version, CURRENT_SPEC_VERSION);
+        assert_eq!(spec.hardware.and_then(|hardware| hardware.cpus), Some(2));
+    }
+
+    #[test]
+    fn migrate_rejects_future_version() {
+        let err = VmSpec::migrate(json!({ "specVersion": "99.0.0" }))
+            .expect_err("future spec should fail");
+
+        assert!(matches!(err, VmSpecError::UnsupportedVersion { .. }));
+        assert!(err.to_string().contains("newer than the supported version"));
+    }
+
+    #[test]
+    fn migrate_rejects_unknown_older_version() {
+        let err = VmSpec::migrate(json!({ "specVersion": "0.0.1" }))
+            .expect_err("unknown older spec should fail");
+
+        assert!(matches!(err, VmSpecError::NoMigrationPath { .. }));
+    }
+
+    #[test]
+    fn migrate_requires_version() {
+        let err = VmSpec::migrate(json!({})).expect_err("missing version should fail");
+
+        assert!(matches!(err, VmSpecError::MissingVersion));
+    }
+
+    #[test]
+    fn from_json_str_runs_migration() {
+        let spec = VmSpec::from_json_str(r#"{"specVersion":"0.1.0"}"#).expect("parse spec");
+
+        assert_eq!(spec, VmSpec::current());
+    }
+
+    #[test]
+    fn clamp_balloon_target_respects_min_and_max_memory() {
+        let hardware = Hardware {
+            cpus: None,
+            memory: Some(4096),
+            min_memory: Some(1024),
+            nested_virtualization: None,
+            rosetta: None,
+            graphics: None,
+            keyboard: None,
+            entropy: None,
+            memory_balloon: None,
+        };
+
+        assert_eq!(hardware.clamp_balloon_target(512), 1024);
+        assert_eq!(hardware.clamp_balloon_target(2048), 2048);
+        assert_eq!(hardware.clamp_balloon_target(8192), 4096);
+    }
 }
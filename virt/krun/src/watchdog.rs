@@ -71,13 +71,13 @@ fn wait_for_parent_death(fd: BorrowedFd<'_>) {
     let mut poll_fd = [PollFd::new(fd, PollFlags::POLLHUP)];
     loop {
         match poll(&mut poll_fd, PollTimeout::NONE) {
-            Ok(count) if count > 0 => {
-                if poll_fd[0]
-                    .revents()
-                    .is_some_and(|events| events.contains(PollFlags::POLLHUP))
-                {
-                    return;
-                }
+            Ok(count)
+                if count > 0
+                    && poll_fd[0]
+                        .revents()
+                        .is_some_and(|events| events.contains(PollFlags::POLLHUP)) =>
+            {
+                return;
             }
             Err(nix::errno::Errno::EINTR) => {}
             Err(err) => {
@@ -9,13 +9,14 @@ use krun::{
     Disk as KrunDisk, KrunBackendError, Mount as KrunMount, NetUnixgram as KrunNetUnixgram,
     VirtualMachine, VirtualMachineBuilder, VsockPort as KrunVsockPort,
 };
+use nix::unistd::{sysconf, SysconfVar};
 use tokio::net::{UnixListener, UnixStream};
 use tokio::sync::Mutex as AsyncMutex;
 use tokio::time::{sleep, timeout};
 
 use crate::stream::{MachineSerialStream, VsockListener, VsockStream};
 use crate::types::{
-    DiskImage, NetworkMode, SharedDirectory, VirtError, VmConfig, VmExit, VsockPortMode,
+    DiskImage, NetworkMode, SharedDirectory, VirtError, VmConfig, VmExit, VmMetrics, VsockPortMode,
 };
 
 const KRUN_BINARY_ENV: &str = "KRUN_BIN";
@@ -86,13 +87,25 @@ fn validate(config: &VmConfig) -> Result<(), VirtError> {
             "nested virtualization is not implemented for the krun backend yet",
         );
     }
+    if !config.entropy || !config.memory_balloon {
+        return invalid_config(
+            config,
+            "libkrun always attaches entropy and memory balloon devices",
+        );
+    }
+    if config.graphics || config.keyboard {
+        return invalid_config(
+            config,
+            "graphics and keyboard devices are only supported by the VZ backend",
+        );
+    }
 
     match &config.network {
         NetworkMode::None => {}
         NetworkMode::UnixDatagram { peer_path, .. } => {
             validate_unix_datagram_network(config, peer_path)?
         }
-        NetworkMode::VzNat => {
+        NetworkMode::VzNat { .. } => {
             return invalid_config(
                 config,
                 "vznat networking is only supported by the VZ backend",
@@ -208,6 +221,48 @@ impl KrunMachineBackend {
         Ok(())
     }
 
+    pub(crate) async fn pause(&self) -> Result<(), VirtError> {
+        Err(VirtError::Unimplemented {
+            kind: "krun",
+            operation: "pause",
+        })
+    }
+
+    pub(crate) async fn resume(&self) -> Result<(), VirtError> {
+        Err(VirtError::Unimplemented {
+            kind: "krun",
+            operation: "resume",
+        })
+    }
+
+    pub(crate) async fn set_balloon_target(&self, _target_mib: u64) -> Result<(), VirtError> {
+        Err(VirtError::Unimplemented {
+            kind: "krun",
+            operation: "set_balloon_target",
+        })
+    }
+
+    pub(crate) async fn metrics(&self) -> Result<VmMetrics, VirtError> {
+        let vm = {
+            let runtime = self.runtime.lock().await;
+            let Some(running) = runtime.as_ref() else {
+                return Err(VirtError::Backend(format!(
+                    "cannot read metrics for machine {:?} because it is not running",
+                    self.config.name.as_str()
+                )));
+            };
+            running.vm.clone()
+        };
+        let pid = vm.lock().await.id();
+
+        Ok(VmMetrics {
+            memory_mib: self.config.memory_mib.unwrap_or_default(),
+            // libkrun does not report the balloon target back to the host.
+            balloon_target_mib: None,
+            cpu_time: process_cpu_time(pid),
+        })
+    }
+
     pub(crate) async fn connect_vsock(&self, port: u32) -> Result<VsockStream, VirtError> {
         {
             let runtime = self.runtime.lock().await;
@@ -231,7 +286,7 @@ impl KrunMachineBackend {
         }
 
         let stream = UnixStream::connect(vsock_path(&self.config, port, mode)).await?;
-        Ok(VsockStream::from_unix_stream(stream))
+        Ok(VsockStream::from_unix_stream_on_port(stream, port))
     }
 
     pub(crate) async fn listen_vsock(&self, port: u32) -> Result<VsockListener, VirtError> {
@@ -523,6 +578,31 @@ fn vsock_dir_for(config: &VmConfig) -> PathBuf {
     runtime_dir_for(config).join(VSOCK_DIR_NAME)
 }
 
+/// Reads user plus system CPU time for `pid` from `/proc/<pid>/stat`.
+fn process_cpu_time(pid: u32) -> Option<Duration> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    let ticks_per_second = sysconf(SysconfVar::CLK_TCK).ok()??;
+    let ticks = stat_cpu_ticks(&stat)?;
+    let ticks_per_second = u64::try_from(ticks_per_second)
+        .ok()
+        .filter(|ticks| *ticks > 0)?;
+    Some(Duration::from_millis(
+        ticks.saturating_mul(1000) / ticks_per_second,
+    ))
+}
+
+/// Sums `utime` and `stime`, fields 14 and 15 of `/proc/<pid>/stat`.
+///
+/// The command name in field 2 may contain spaces, so fields are counted from
+/// its closing parenthesis.
+fn stat_cpu_ticks(stat: &str) -> Option<u64> {
+    let (_, rest) = stat.rsplit_once(')')?;
+    let mut fields = rest.split_whitespace().skip(11);
+    let utime = fields.next()?.parse::<u64>().ok()?;
+    let stime = fields.next()?.parse::<u64>().ok()?;
+    Some(utime.saturating_add(stime))
+}
+
 fn locate_krun_binary() -> Result<PathBuf, VirtError> {
     if let Some(path) = env::var_os(KRUN_BINARY_ENV) {
         let path = PathBuf::from(path);
@@ -600,3 +680,48 @@ fn invalid_config<T>(config: &VmConfig, reason: &str) -> Result<T, VirtError> {
         reason: reason.to_string(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::krun::backend::stat_cpu_ticks;
+
+    #[test]
+    fn stat_cpu_ticks_sums_utime_and_stime() {
+        let stat = "4242 (krun) S 1 4242 4242 0 -1 4194560 1200 0 3 0 250 75 0 0 20 0 4 0";
+
+        assert_eq!(stat_cpu_ticks(stat), Some(325));
+    }
+
+    #[test]
+    fn stat_cpu_ticks_counts_fields_after_the_last_paren() {
+        let stat = "4242 (krun worker) (x) S 1 4242 4242 0 -1 4194560 1200 0 3 0 10 5 0 0 20 0";
+
+        assert_eq!(stat_cpu_ticks(stat), Some(15));
+    }
+
+    #[test]
+    fn stat_cpu_ticks_rejects_truncated_lines() {
+        assert_eq!(stat_cpu_ticks(""), None);
+        assert_eq!(stat_cpu_ticks("4242 (krun"), None);
+        assert_eq!(
+            stat_cpu_ticks("4242 (krun) S 1 4242 4242 0 -1 4194560 1200 0 3 0"),
+            None
+        );
+        assert_eq!(
+            stat_cpu_ticks("4242 (krun) S 1 4242 4242 0 -1 4194560 1200 0 3 0 250"),
+            None
+        );
+    }
+
+    #[test]
+    fn stat_cpu_ticks_rejects_non_numeric_times() {
+        assert_eq!(
+            stat_cpu_ticks("4242 (krun) S 1 4242 4242 0 -1 4194560 1200 0 3 0 abc 75 0"),
+            None
+        );
+        assert_eq!(
+            stat_cpu_ticks("4242 (krun) S 1 4242 4242 0 -1 4194560 1200 0 3 0 250 -1 0"),
+            None
+        );
+    }
+}
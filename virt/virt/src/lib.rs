@@ -9,9 +9,12 @@ mod types;
 mod vz;
 
 pub use crate::machine::VirtualMachine;
-pub use crate::serial::{spawn_serial_tunnel, SerialAccess, SerialConsole, SerialStream};
+pub use crate::serial::{
+    run_serial_tunnel, spawn_serial_tunnel, SerialAccess, SerialBackpressure, SerialConsole,
+    SerialOpenOptions, SerialStream,
+};
 pub use crate::stream::{VsockListener, VsockStream};
 pub use crate::types::{
-    DiskImage, MachineIdentifier, NetworkMode, SharedDirectory, VirtError, VmConfig,
-    VmConfigBuilder, VmExit, VsockPort, VsockPortMode,
+    DiskImage, HostCapabilities, MachineIdentifier, NetworkMode, SharedDirectory, VirtError,
+    VmConfig, VmConfigBuilder, VmExit, VmMetrics, VsockPort, VsockPortMode,
 };
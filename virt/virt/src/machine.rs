@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
-use crate::platform::{create_backend, VmBackend};
+use crate::platform::{create_backend, host_capabilities, VmBackend};
 use crate::serial::SerialConsole;
-use crate::types::{VirtError, VmConfig, VmExit};
+use crate::types::{HostCapabilities, VirtError, VmConfig, VmExit, VmMetrics};
 use crate::{VsockListener, VsockStream};
 
 #[derive(Clone)]
@@ -33,6 +33,11 @@ impl VirtualMachine {
         })
     }
 
+    /// Reports which optional features this host's backend can enable.
+    pub fn host_capabilities() -> HostCapabilities {
+        host_capabilities()
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -45,6 +50,26 @@ impl VirtualMachine {
         self.backend.stop().await
     }
 
+    /// Suspends the guest vCPUs without stopping the machine.
+    pub async fn pause(&self) -> Result<(), VirtError> {
+        self.backend.pause().await
+    }
+
+    /// Resumes a paused machine.
+    pub async fn resume(&self) -> Result<(), VirtError> {
+        self.backend.resume().await
+    }
+
+    /// Reports current resource usage of a running machine.
+    pub async fn metrics(&self) -> Result<VmMetrics, VirtError> {
+        self.backend.metrics().await
+    }
+
+    /// Asks the memory balloon to leave the guest `target_mib` MiB of memory.
+    pub async fn set_balloon_target(&self, target_mib: u64) -> Result<(), VirtError> {
+        self.backend.set_balloon_target(target_mib).await
+    }
+
     pub async fn restart(&self) -> Result<(), VirtError> {
         self.stop().await?;
         self.start().await
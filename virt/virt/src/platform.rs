@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::types::{VirtError, VmConfig};
+use crate::types::{HostCapabilities, VirtError, VmConfig};
 
 #[cfg(target_os = "linux")]
 pub(crate) type VmBackend = crate::krun::KrunMachineBackend;
@@ -30,3 +30,15 @@ pub(crate) fn create_backend(config: VmConfig) -> Result<Arc<VmBackend>, VirtErr
         })
     }
 }
+
+pub(crate) fn host_capabilities() -> HostCapabilities {
+    #[cfg(target_os = "macos")]
+    {
+        crate::vz::host_capabilities()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        HostCapabilities::default()
+    }
+}
@@ -1,10 +1,12 @@
 use std::io;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf};
 use tokio::net::UnixStream;
-use tokio::sync::{broadcast, Mutex};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, Mutex};
 
 use crate::platform::VmBackend;
 use crate::stream::MachineSerialStream;
@@ -15,10 +17,89 @@ pub enum SerialAccess {
     Watch,
 }
 
+/// Output chunks buffered per serial client before its backpressure policy applies.
+const SUBSCRIBER_BUFFER: usize = 256;
+
+/// What happens to serial output when a client's buffer is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SerialBackpressure {
+    /// Skip output the client has no room for and keep it attached.
+    #[default]
+    Drop,
+    /// Wait up to `timeout` for the client to make room, then detach its output.
+    ///
+    /// While waiting, the serial reader is paused for every client and file sink.
+    Block { timeout: Duration },
+}
+
+/// Options for opening a serial stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerialOpenOptions {
+    access: SerialAccess,
+    backpressure: SerialBackpressure,
+}
+
+impl SerialOpenOptions {
+    /// Creates options for `access` that drop output a slow client cannot keep up with.
+    pub fn new(access: SerialAccess) -> Self {
+        Self {
+            access,
+            backpressure: SerialBackpressure::default(),
+        }
+    }
+
+    /// Sets how output is delivered when the client falls behind.
+    pub fn backpressure(mut self, backpressure: SerialBackpressure) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SerialSubscriber {
+    id: u64,
+    output_tx: mpsc::Sender<Vec<u8>>,
+    backpressure: SerialBackpressure,
+}
+
+impl SerialSubscriber {
+    /// Returns `false` once the client should no longer receive output.
+    async fn deliver(&self, chunk: &[u8]) -> bool {
+        match self.backpressure {
+            SerialBackpressure::Drop => match self.output_tx.try_send(chunk.to_vec()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    tracing::debug!(
+                        client_id = self.id,
+                        bytes = chunk.len(),
+                        "serial client buffer full, output dropped"
+                    );
+                    true
+                }
+                Err(TrySendError::Closed(_)) => false,
+            },
+            SerialBackpressure::Block { timeout } => {
+                match tokio::time::timeout(timeout, self.output_tx.send(chunk.to_vec())).await {
+                    Ok(result) => result.is_ok(),
+                    Err(_) => {
+                        tracing::warn!(
+                            client_id = self.id,
+                            ?timeout,
+                            "serial client stalled, detaching output"
+                        );
+                        false
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 struct SerialHub {
     next_id: u64,
     interactive_owner: Option<u64>,
+    subscribers: Vec<SerialSubscriber>,
 }
 
 impl SerialHub {
@@ -26,10 +107,15 @@ impl SerialHub {
         Self {
             next_id: 1,
             interactive_owner: None,
+            subscribers: Vec::new(),
         }
     }
 
-    fn attach(&mut self, access: SerialAccess) -> Result<u64, crate::types::VirtError> {
+    fn attach(
+        &mut self,
+        options: SerialOpenOptions,
+    ) -> Result<(u64, mpsc::Receiver<Vec<u8>>), crate::types::VirtError> {
+        let access = options.access;
         if access == SerialAccess::Interactive && self.interactive_owner.is_some() {
             return Err(crate::types::VirtError::Backend(
                 "interactive serial client is already attached".to_string(),
@@ -43,13 +129,25 @@ impl SerialHub {
             self.interactive_owner = Some(id);
         }
 
-        Ok(id)
+        let (output_tx, output_rx) = mpsc::channel(SUBSCRIBER_BUFFER);
+        self.subscribers.push(SerialSubscriber {
+            id,
+            output_tx,
+            backpressure: options.backpressure,
+        });
+
+        Ok((id, output_rx))
     }
 
     fn detach(&mut self, id: u64) {
         if self.interactive_owner == Some(id) {
             self.interactive_owner = None;
         }
+        self.unsubscribe(id);
+    }
+
+    fn unsubscribe(&mut self, id: u64) {
+        self.subscribers.retain(|subscriber| subscriber.id != id);
     }
 
     fn can_write_input(&self, id: u64) -> bool {
@@ -69,7 +167,6 @@ pub struct SerialConsole {
     hub: Arc<Mutex<SerialHub>>,
     attachment: Arc<Mutex<Option<SerialAttachment>>>,
     file_sinks: Arc<Mutex<Vec<tokio::fs::File>>>,
-    output_tx: broadcast::Sender<Vec<u8>>,
     attach_lock: Arc<Mutex<()>>,
 }
 
@@ -78,18 +175,16 @@ pub struct SerialStream {
     console: Arc<SerialConsole>,
     client_id: u64,
     access: SerialAccess,
-    output_rx: broadcast::Receiver<Vec<u8>>,
+    output_rx: Option<mpsc::Receiver<Vec<u8>>>,
 }
 
 impl SerialConsole {
     pub(crate) fn new(backend: Arc<VmBackend>) -> Self {
-        let (output_tx, _) = broadcast::channel(256);
         Self {
             backend,
             hub: Arc::new(Mutex::new(SerialHub::new())),
             attachment: Arc::new(Mutex::new(None)),
             file_sinks: Arc::new(Mutex::new(Vec::new())),
-            output_tx,
             attach_lock: Arc::new(Mutex::new(())),
         }
     }
@@ -111,20 +206,32 @@ impl SerialConsole {
     pub async fn open_stream(
         self: &Arc<Self>,
         access: SerialAccess,
+    ) -> Result<SerialStream, crate::types::VirtError> {
+        self.open_stream_with(SerialOpenOptions::new(access)).await
+    }
+
+    pub async fn open_stream_with(
+        self: &Arc<Self>,
+        options: SerialOpenOptions,
     ) -> Result<SerialStream, crate::types::VirtError> {
         self.ensure_attached().await?;
 
-        let client_id = {
+        let (client_id, output_rx) = {
             let mut hub = self.hub.lock().await;
-            hub.attach(access)?
+            hub.attach(options)?
         };
-        tracing::info!(client_id, access = ?access, "serial client attached");
+        tracing::info!(
+            client_id,
+            access = ?options.access,
+            backpressure = ?options.backpressure,
+            "serial client attached"
+        );
 
         Ok(SerialStream {
             console: self.clone(),
             client_id,
-            access,
-            output_rx: self.output_tx.subscribe(),
+            access: options.access,
+            output_rx: Some(output_rx),
         })
     }
 
@@ -141,10 +248,10 @@ impl SerialConsole {
         let stream = self.open_serial_device().await?;
         tracing::info!("serial backend stream opened");
         let (guest_output, guest_input) = tokio::io::split(stream);
-        let output_tx = self.output_tx.clone();
+        let hub = self.hub.clone();
         let file_sinks = self.file_sinks.clone();
         let reader_task = tokio::spawn(async move {
-            run_serial_reader(guest_output, file_sinks, output_tx).await;
+            run_serial_reader(guest_output, file_sinks, hub).await;
         });
 
         let attachment = SerialAttachment {
@@ -218,7 +325,7 @@ impl Drop for SerialConsole {
 async fn run_serial_reader(
     mut guest_output: ReadHalf<MachineSerialStream>,
     file_sinks: Arc<Mutex<Vec<tokio::fs::File>>>,
-    output_tx: broadcast::Sender<Vec<u8>>,
+    hub: Arc<Mutex<SerialHub>>,
 ) {
     let mut buf = [0u8; 8192];
     let mut saw_output = false;
@@ -275,20 +382,44 @@ async fn run_serial_reader(
             }
         }
 
-        let _ = output_tx.send(chunk);
+        broadcast_output(&hub, &chunk).await;
+    }
+}
+
+/// Delivers `chunk` to every serial client according to its backpressure policy.
+///
+/// Senders are cloned out of the hub so a stalled client does not hold the hub
+/// lock while the interactive client is writing input.
+async fn broadcast_output(hub: &Mutex<SerialHub>, chunk: &[u8]) {
+    let subscribers = hub.lock().await.subscribers.clone();
+    let mut detached = Vec::new();
+    for subscriber in &subscribers {
+        if !subscriber.deliver(chunk).await {
+            detached.push(subscriber.id);
+        }
+    }
+
+    if !detached.is_empty() {
+        let mut hub = hub.lock().await;
+        for id in detached {
+            hub.detach(id);
+        }
     }
 }
 
 pub fn spawn_serial_tunnel(stream: UnixStream, serial_stream: SerialStream) {
-    tokio::spawn(async move {
-        if let Err(err) = proxy_serial_stream(stream, serial_stream).await {
-            if is_expected_disconnect(&err) {
-                tracing::debug!(error = %err, "serial relay closed");
-            } else {
-                tracing::error!(error = %err, "serial relay failed");
-            }
+    tokio::spawn(run_serial_tunnel(stream, serial_stream));
+}
+
+/// Relays `serial_stream` over `stream` until either side disconnects.
+pub async fn run_serial_tunnel(stream: UnixStream, serial_stream: SerialStream) {
+    if let Err(err) = proxy_serial_stream(stream, serial_stream).await {
+        if is_expected_disconnect(&err) {
+            tracing::debug!(error = %err, "serial relay closed");
+        } else {
+            tracing::error!(error = %err, "serial relay failed");
         }
-    });
+    }
 }
 
 async fn proxy_serial_stream(
@@ -296,34 +427,43 @@ async fn proxy_serial_stream(
     mut serial_stream: SerialStream,
 ) -> io::Result<()> {
     let access = serial_stream.access;
-    let (mut client_read, mut client_write) = client_stream.into_split();
-    let mut output_rx = std::mem::replace(
-        &mut serial_stream.output_rx,
-        serial_stream.console.output_tx.subscribe(),
-    );
-
-    let output_task: tokio::task::JoinHandle<io::Result<()>> = tokio::spawn(async move {
-        loop {
-            match output_rx.recv().await {
-                Ok(chunk) => {
-                    client_write.write_all(&chunk).await?;
-                    client_write.flush().await?;
-                }
-                Err(broadcast::error::RecvError::Lagged(_)) => continue,
-                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+    let (mut client_read, client_write) = client_stream.into_split();
+    let Some(output_rx) = serial_stream.output_rx.take() else {
+        return Err(io::Error::other("serial stream output is already relayed"));
+    };
+
+    let input = async {
+        match access {
+            SerialAccess::Interactive => {
+                relay_client_input(&mut serial_stream, &mut client_read).await
             }
+            SerialAccess::Watch => wait_for_client_disconnect(&mut client_read).await,
         }
-    });
-
-    let relay_result = match access {
-        SerialAccess::Interactive => relay_client_input(&mut serial_stream, &mut client_read).await,
-        SerialAccess::Watch => wait_for_client_disconnect(&mut client_read).await,
     };
+    relay_until_closed(output_rx, client_write, input).await
+}
 
-    output_task.abort();
-    let _ = output_task.await;
+/// Writes serial output to the client while `input` runs, stopping when either
+/// side finishes. The hub closes `output_rx` when it detaches a stalled client,
+/// so that also ends the client's input relay and closes its stream.
+async fn relay_until_closed(
+    mut output_rx: mpsc::Receiver<Vec<u8>>,
+    mut client_write: tokio::net::unix::OwnedWriteHalf,
+    input: impl std::future::Future<Output = io::Result<()>>,
+) -> io::Result<()> {
+    let output = async {
+        while let Some(chunk) = output_rx.recv().await {
+            client_write.write_all(&chunk).await?;
+            client_write.flush().await?;
+        }
+        tracing::debug!("serial output closed, disconnecting client");
+        Ok(())
+    };
 
-    relay_result
+    tokio::select! {
+        result = input => result,
+        result = output => result,
+    }
 }
 
 async fn relay_client_input(
@@ -365,3 +505,139 @@ fn is_expected_disconnect(err: &io::Error) -> bool {
             | io::ErrorKind::Interrupted
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::io::AsyncReadExt;
+    use tokio::net::UnixStream;
+    use tokio::sync::{mpsc, Mutex};
+
+    use crate::serial::{
+        broadcast_output, relay_until_closed, wait_for_client_disconnect, SerialAccess,
+        SerialBackpressure, SerialHub, SerialOpenOptions, SUBSCRIBER_BUFFER,
+    };
+
+    async fn fill_buffers(hub: &Mutex<SerialHub>) {
+        for index in 0..SUBSCRIBER_BUFFER {
+            broadcast_output(hub, &[index as u8]).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn blocking_subscriber_survives_a_transient_stall() {
+        let hub = Mutex::new(SerialHub::new());
+        let (_, mut dropping_rx) = hub
+            .lock()
+            .await
+            .attach(SerialOpenOptions::new(SerialAccess::Watch))
+            .expect("attach dropping client");
+        let (_, mut blocking_rx) = hub
+            .lock()
+            .await
+            .attach(
+                SerialOpenOptions::new(SerialAccess::Interactive).backpressure(
+                    SerialBackpressure::Block {
+                        timeout: Duration::from_secs(5),
+                    },
+                ),
+            )
+            .expect("attach blocking client");
+        fill_buffers(&hub).await;
+
+        let ((), first) = tokio::join!(broadcast_output(&hub, b"tail"), async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            blocking_rx.recv().await
+        });
+
+        assert_eq!(first, Some(vec![0]));
+        assert_eq!(hub.lock().await.subscribers.len(), 2);
+        let mut last = None;
+        while let Ok(chunk) = blocking_rx.try_recv() {
+            last = Some(chunk);
+        }
+        assert_eq!(last, Some(b"tail".to_vec()));
+        let mut dropped_last = None;
+        while let Ok(chunk) = dropping_rx.try_recv() {
+            dropped_last = Some(chunk);
+        }
+        assert_eq!(dropped_last, Some(vec![(SUBSCRIBER_BUFFER - 1) as u8]));
+    }
+
+    #[tokio::test]
+    async fn blocking_subscriber_is_detached_after_timeout() {
+        let hub = Mutex::new(SerialHub::new());
+        let (_, _blocking_rx) = hub
+            .lock()
+            .await
+            .attach(SerialOpenOptions::new(SerialAccess::Watch).backpressure(
+                SerialBackpressure::Block {
+                    timeout: Duration::from_millis(10),
+                },
+            ))
+            .expect("attach blocking client");
+        fill_buffers(&hub).await;
+
+        broadcast_output(&hub, b"tail").await;
+
+        assert!(hub.lock().await.subscribers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn stalled_interactive_client_loses_input_ownership() {
+        let hub = Mutex::new(SerialHub::new());
+        let (id, _blocking_rx) = hub
+            .lock()
+            .await
+            .attach(
+                SerialOpenOptions::new(SerialAccess::Interactive).backpressure(
+                    SerialBackpressure::Block {
+                        timeout: Duration::from_millis(10),
+                    },
+                ),
+            )
+            .expect("attach blocking client");
+        fill_buffers(&hub).await;
+
+        broadcast_output(&hub, b"tail").await;
+
+        assert!(!hub.lock().await.can_write_input(id));
+        hub.lock()
+            .await
+            .attach(SerialOpenOptions::new(SerialAccess::Interactive))
+            .expect("another interactive client should attach");
+    }
+
+    #[tokio::test]
+    async fn closed_output_stops_input_relay_and_closes_client() {
+        let (mut client, relay) = UnixStream::pair().expect("socket pair");
+        let (mut relay_read, relay_write) = relay.into_split();
+        let (output_tx, output_rx) = mpsc::channel(1);
+        output_tx
+            .send(b"last".to_vec())
+            .await
+            .expect("queue output");
+        drop(output_tx);
+
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            relay_until_closed(
+                output_rx,
+                relay_write,
+                wait_for_client_disconnect(&mut relay_read),
+            ),
+        )
+        .await
+        .expect("relay should stop once output closes")
+        .expect("relay should succeed");
+        drop(relay_read);
+
+        let mut received = Vec::new();
+        client
+            .read_to_end(&mut received)
+            .await
+            .expect("read until the relay closes");
+        assert_eq!(received, b"last");
+    }
+}
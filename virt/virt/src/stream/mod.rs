@@ -15,7 +15,8 @@ mod vz;
 compile_error!("virt stream support requires a Unix host");
 
 enum VsockStreamInner {
-    Unix(UnixStream),
+    /// A relayed stream and the guest port it was opened for, or 0 if unknown.
+    Unix(UnixStream, u32),
     #[cfg(target_os = "macos")]
     Vz(vz::VzVsockConnection),
 }
@@ -67,8 +68,13 @@ impl fmt::Debug for VsockListener {
 
 impl VsockStream {
     pub fn from_unix_stream(stream: UnixStream) -> Self {
+        Self::from_unix_stream_on_port(stream, 0)
+    }
+
+    /// Wraps a relayed stream that was opened for guest `port`.
+    pub(crate) fn from_unix_stream_on_port(stream: UnixStream, port: u32) -> Self {
         Self {
-            inner: VsockStreamInner::Unix(stream),
+            inner: VsockStreamInner::Unix(stream, port),
         }
     }
 
@@ -81,7 +87,7 @@ impl VsockStream {
 
     pub fn source_port(&self) -> Option<u32> {
         match &self.inner {
-            VsockStreamInner::Unix(_) => None,
+            VsockStreamInner::Unix(..) => None,
             #[cfg(target_os = "macos")]
             VsockStreamInner::Vz(stream) => Some(stream.source_port()),
         }
@@ -89,7 +95,7 @@ impl VsockStream {
 
     pub fn destination_port(&self) -> u32 {
         match &self.inner {
-            VsockStreamInner::Unix(_) => 0,
+            VsockStreamInner::Unix(_, port) => *port,
             #[cfg(target_os = "macos")]
             VsockStreamInner::Vz(stream) => stream.destination_port(),
         }
@@ -97,7 +103,7 @@ impl VsockStream {
 
     pub fn dup_fd(&self) -> io::Result<OwnedFd> {
         match &self.inner {
-            VsockStreamInner::Unix(stream) => duplicate_nonblocking_fd(stream),
+            VsockStreamInner::Unix(stream, _) => duplicate_nonblocking_fd(stream),
             #[cfg(target_os = "macos")]
             VsockStreamInner::Vz(stream) => duplicate_nonblocking_fd(stream),
         }
@@ -177,7 +183,7 @@ impl AsyncRead for VsockStream {
         buf: &mut ReadBuf<'_>,
     ) -> Poll<io::Result<()>> {
         match &mut self.inner {
-            VsockStreamInner::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+            VsockStreamInner::Unix(stream, _) => Pin::new(stream).poll_read(cx, buf),
             #[cfg(target_os = "macos")]
             VsockStreamInner::Vz(stream) => Pin::new(stream).poll_read(cx, buf),
         }
@@ -191,7 +197,7 @@ impl AsyncWrite for VsockStream {
         buf: &[u8],
     ) -> Poll<io::Result<usize>> {
         match &mut self.inner {
-            VsockStreamInner::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+            VsockStreamInner::Unix(stream, _) => Pin::new(stream).poll_write(cx, buf),
             #[cfg(target_os = "macos")]
             VsockStreamInner::Vz(stream) => Pin::new(stream).poll_write(cx, buf),
         }
@@ -199,7 +205,7 @@ impl AsyncWrite for VsockStream {
 
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         match &mut self.inner {
-            VsockStreamInner::Unix(stream) => Pin::new(stream).poll_flush(cx),
+            VsockStreamInner::Unix(stream, _) => Pin::new(stream).poll_flush(cx),
             #[cfg(target_os = "macos")]
             VsockStreamInner::Vz(stream) => Pin::new(stream).poll_flush(cx),
         }
@@ -207,7 +213,7 @@ impl AsyncWrite for VsockStream {
 
     fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         match &mut self.inner {
-            VsockStreamInner::Unix(stream) => Pin::new(stream).poll_shutdown(cx),
+            VsockStreamInner::Unix(stream, _) => Pin::new(stream).poll_shutdown(cx),
             #[cfg(target_os = "macos")]
             VsockStreamInner::Vz(stream) => Pin::new(stream).poll_shutdown(cx),
         }
@@ -348,4 +354,14 @@ mod tests {
         assert_eq!(accepted.destination_port(), 0);
         let _ = std::fs::remove_file(path);
     }
+
+    #[tokio::test]
+    async fn relayed_stream_reports_the_port_it_was_opened_for() {
+        let (stream, _peer) = UnixStream::pair().expect("unix stream pair should be created");
+
+        let stream = VsockStream::from_unix_stream_on_port(stream, 2222);
+
+        assert_eq!(stream.destination_port(), 2222);
+        assert_eq!(stream.source_port(), None);
+    }
 }
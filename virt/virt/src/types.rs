@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use thiserror::Error;
 
@@ -66,6 +67,25 @@ impl MachineIdentifier {
     }
 }
 
+/// Optional hypervisor features the host supports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HostCapabilities {
+    pub nested_virtualization: bool,
+}
+
+/// Resource usage of a running machine.
+///
+/// Fields are `None` when the backend has no way to observe them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VmMetrics {
+    /// Memory assigned to the guest at boot.
+    pub memory_mib: u64,
+    /// Memory the balloon device currently asks the guest to keep.
+    pub balloon_target_mib: Option<u64>,
+    /// Host CPU time consumed by the process running the guest.
+    pub cpu_time: Option<Duration>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VmConfig {
     pub(crate) name: String,
@@ -78,6 +98,10 @@ pub struct VmConfig {
     pub machine_identifier: Option<MachineIdentifier>,
     pub nested_virtualization: bool,
     pub rosetta: bool,
+    pub graphics: bool,
+    pub keyboard: bool,
+    pub entropy: bool,
+    pub memory_balloon: bool,
     pub network: NetworkMode,
     pub kernel_cmdline: Vec<String>,
     pub disks: Vec<DiskImage>,
@@ -98,6 +122,10 @@ impl VmConfig {
             machine_identifier: None,
             nested_virtualization: false,
             rosetta: false,
+            graphics: false,
+            keyboard: false,
+            entropy: true,
+            memory_balloon: true,
             network: NetworkMode::None,
             kernel_cmdline: Vec::new(),
             disks: Vec::new(),
@@ -192,6 +220,27 @@ impl VmConfigBuilder {
         self
     }
 
+    /// Attaches a graphics device and a pointing device to drive it.
+    pub fn graphics(mut self, enabled: bool) -> Self {
+        self.config.graphics = enabled;
+        self
+    }
+
+    pub fn keyboard(mut self, enabled: bool) -> Self {
+        self.config.keyboard = enabled;
+        self
+    }
+
+    pub fn entropy(mut self, enabled: bool) -> Self {
+        self.config.entropy = enabled;
+        self
+    }
+
+    pub fn memory_balloon(mut self, enabled: bool) -> Self {
+        self.config.memory_balloon = enabled;
+        self
+    }
+
     pub fn network(mut self, network: NetworkMode) -> Self {
         self.config.network = network;
         self
@@ -202,8 +251,9 @@ impl VmConfigBuilder {
         self
     }
 
-    pub fn vz_nat_network(mut self) -> Self {
-        self.config.network = NetworkMode::VzNat;
+    /// Uses VZ NAT networking. Without a MAC address VZ picks a random one.
+    pub fn vz_nat_network(mut self, mac: Option<[u8; 6]>) -> Self {
+        self.config.network = NetworkMode::VzNat { mac };
         self
     }
 
@@ -284,7 +334,7 @@ pub enum VsockPortMode {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NetworkMode {
     None,
-    VzNat,
+    VzNat { mac: Option<[u8; 6]> },
     UnixDatagram { peer_path: PathBuf, mac: [u8; 6] },
     UnixStream { path: PathBuf, mac: [u8; 6] },
     Tap { name: String, mac: [u8; 6] },
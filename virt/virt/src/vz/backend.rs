@@ -5,10 +5,11 @@ use std::time::Duration;
 
 use tokio::sync::{Mutex as AsyncMutex, Notify};
 use vz::device::{
-    EntropyDeviceConfiguration, LinuxRosettaDirectoryShare, MemoryBalloonDeviceConfiguration,
-    NetworkDeviceConfiguration, SerialPortConfiguration, SharedDirectory, SingleDirectoryShare,
-    SocketDevice, SocketDeviceConfiguration, StorageDeviceConfiguration,
-    VirtioFileSystemDeviceConfiguration,
+    EntropyDeviceConfiguration, KeyboardConfiguration, LinuxRosettaDirectoryShare,
+    MemoryBalloonDeviceConfiguration, NetworkDeviceConfiguration, PointingDeviceConfiguration,
+    SerialPortConfiguration, SharedDirectory, SingleDirectoryShare, SocketDevice,
+    SocketDeviceConfiguration, StorageDeviceConfiguration, VirtioFileSystemDeviceConfiguration,
+    VirtioGraphicsDeviceConfiguration,
 };
 use vz::{
     GenericMachineIdentifier, GenericPlatform, LinuxBootLoader, RosettaAvailability,
@@ -16,11 +17,18 @@ use vz::{
 };
 
 use crate::stream::{MachineSerialStream, VsockListener, VsockStream};
-use crate::types::{MachineIdentifier, NetworkMode, VirtError, VmConfig, VmExit};
+use crate::types::{
+    HostCapabilities, MachineIdentifier, NetworkMode, VirtError, VmConfig, VmExit, VmMetrics,
+};
 
 const STARTUP_TIMEOUT: Duration = Duration::from_secs(60 * 5);
 const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+const PAUSE_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_MEMORY_MIB: u64 = 2048;
+const BYTES_PER_MIB: u64 = 1024 * 1024;
 const BENTO_ROSETTA_TAG: &str = "bento-rosetta";
+const GRAPHICS_WIDTH_PIXELS: u32 = 1920;
+const GRAPHICS_HEIGHT_PIXELS: u32 = 1080;
 
 #[derive(Debug)]
 pub(crate) struct VzMachineBackend {
@@ -158,6 +166,99 @@ impl VzMachineBackend {
         Ok(())
     }
 
+    pub(crate) async fn pause(&self) -> Result<(), VirtError> {
+        let state = self.inner.lock().await;
+        let vm = state.vm.as_ref().ok_or_else(|| {
+            VirtError::Backend(format!(
+                "cannot pause machine {:?} because it is not running",
+                self.config.name.as_str()
+            ))
+        })?;
+        if vm.state() == VirtualMachineState::Paused {
+            return Ok(());
+        }
+        if !vm.can_pause() {
+            return Err(VirtError::Backend(format!(
+                "machine {:?} cannot be paused in state {}",
+                self.config.name.as_str(),
+                vm.state()
+            )));
+        }
+
+        let mut state_events = vm.subscribe_state();
+        vm.pause().await.map_err(vz_error)?;
+        wait_for_state(
+            &mut state_events,
+            vm,
+            VirtualMachineState::Paused,
+            PAUSE_TIMEOUT,
+        )
+        .await
+    }
+
+    pub(crate) async fn resume(&self) -> Result<(), VirtError> {
+        let state = self.inner.lock().await;
+        let vm = state.vm.as_ref().ok_or_else(|| {
+            VirtError::Backend(format!(
+                "cannot resume machine {:?} because it is not running",
+                self.config.name.as_str()
+            ))
+        })?;
+        if vm.state() == VirtualMachineState::Running {
+            return Ok(());
+        }
+        if !vm.can_resume() {
+            return Err(VirtError::Backend(format!(
+                "machine {:?} cannot be resumed in state {}",
+                self.config.name.as_str(),
+                vm.state()
+            )));
+        }
+
+        let mut state_events = vm.subscribe_state();
+        vm.resume().await.map_err(vz_error)?;
+        wait_for_state(
+            &mut state_events,
+            vm,
+            VirtualMachineState::Running,
+            PAUSE_TIMEOUT,
+        )
+        .await
+    }
+
+    pub(crate) async fn metrics(&self) -> Result<VmMetrics, VirtError> {
+        let state = self.inner.lock().await;
+        let vm = state.vm.as_ref().ok_or_else(|| {
+            VirtError::Backend(format!(
+                "cannot read metrics for machine {:?} because it is not running",
+                self.config.name.as_str()
+            ))
+        })?;
+
+        // Virtualization.framework runs the guest in a separate XPC service and
+        // does not expose its CPU time.
+        Ok(VmMetrics {
+            memory_mib: self.config.memory_mib.unwrap_or(DEFAULT_MEMORY_MIB),
+            balloon_target_mib: vm
+                .memory_balloon_target()
+                .map(|target_bytes| target_bytes / BYTES_PER_MIB),
+            cpu_time: None,
+        })
+    }
+
+    pub(crate) async fn set_balloon_target(&self, target_mib: u64) -> Result<(), VirtError> {
+        let state = self.inner.lock().await;
+        let vm = state.vm.as_ref().ok_or_else(|| {
+            VirtError::Backend(format!(
+                "cannot set the balloon target for machine {:?} because it is not running",
+                self.config.name.as_str()
+            ))
+        })?;
+
+        vm.set_memory_balloon_target(target_mib.saturating_mul(BYTES_PER_MIB))
+            .map_err(vz_error)
+    }
+
     pub(crate) async fn connect_vsock(&self, port: u32) -> Result<VsockStream, VirtError> {
         let vm = {
             let state = self.inner.lock().await;
@@ -282,6 +383,31 @@ impl VzMachineBackend {
     }
 }
 
+impl Drop for VzMachineBackend {
+    /// Asks a still-running guest to shut down when the backend is dropped
+    /// without `stop`. `Drop` cannot wait for the async hard stop, so this is
+    /// best effort and process exit tears down whatever remains.
+    fn drop(&mut self) {
+        let Some(vm) = self.inner.get_mut().vm.as_ref() else {
+            return;
+        };
+        if vm.state() == VirtualMachineState::Stopped || !vm.can_request_stop() {
+            return;
+        }
+        tracing::warn!(
+            machine_id = self.config.name.as_str(),
+            "VZ backend dropped while running, requesting guest shutdown"
+        );
+        if let Err(err) = vm.request_stop() {
+            tracing::warn!(
+                machine_id = self.config.name.as_str(),
+                error = %err,
+                "request VZ shutdown on drop"
+            );
+        }
+    }
+}
+
 #[derive(Clone)]
 struct ExitDelegate {
     exit: Arc<Mutex<Option<VmExit>>>,
@@ -324,19 +450,30 @@ fn build_vm(spec: &VmConfig) -> Result<(VirtualMachine, SerialPortConfiguration)
     let mut builder = VirtualMachine::builder()
         .map_err(vz_error)?
         .set_cpu_count(spec.cpus.unwrap_or(2))
-        .set_memory_size(spec.memory_mib.unwrap_or(2048) * 1024 * 1024)
+        .set_memory_size(spec.memory_mib.unwrap_or(DEFAULT_MEMORY_MIB) * 1024 * 1024)
         .set_platform(build_platform(spec)?)
         .set_boot_loader(build_boot_loader(spec)?)
-        .add_entropy_device(EntropyDeviceConfiguration::new())
-        .add_memory_balloon_device(MemoryBalloonDeviceConfiguration::new())
         .add_serial_port(serial_port.clone())
         .add_socket_device(SocketDeviceConfiguration::new());
 
+    if spec.entropy {
+        builder = builder.add_entropy_device(EntropyDeviceConfiguration::new());
+    }
+
+    if spec.memory_balloon {
+        builder = builder.add_memory_balloon_device(MemoryBalloonDeviceConfiguration::new());
+    }
+
     match &spec.network {
         NetworkMode::None => {}
-        NetworkMode::VzNat => {
+        NetworkMode::VzNat { mac: None } => {
             builder = builder.add_network_device(NetworkDeviceConfiguration::nat());
         }
+        NetworkMode::VzNat { mac: Some(mac) } => {
+            builder = builder.add_network_device(
+                NetworkDeviceConfiguration::nat_with_mac(*mac).map_err(vz_error)?,
+            );
+        }
         NetworkMode::UnixDatagram { peer_path, mac } => {
             builder = builder.add_network_device(
                 NetworkDeviceConfiguration::unix_datagram(peer_path, &spec.vm_id, *mac)
@@ -366,6 +503,19 @@ fn build_vm(spec: &VmConfig) -> Result<(VirtualMachine, SerialPortConfiguration)
         builder = builder.add_directory_share(rosetta_config);
     }
 
+    if spec.graphics {
+        builder = builder
+            .add_graphics_device(VirtioGraphicsDeviceConfiguration::new(
+                GRAPHICS_WIDTH_PIXELS,
+                GRAPHICS_HEIGHT_PIXELS,
+            ))
+            .add_pointing_device(PointingDeviceConfiguration::new());
+    }
+
+    if spec.keyboard {
+        builder = builder.add_keyboard(KeyboardConfiguration::new());
+    }
+
     let vm = builder.build().map_err(vz_error)?;
     Ok((vm, serial_port))
 }
@@ -437,9 +587,10 @@ fn validate_machine_config(spec: &VmConfig) -> Result<(), VirtError> {
 
     validate_nested_virtualization(spec)?;
     validate_rosetta(spec)?;
+    validate_graphics(spec)?;
 
     match &spec.network {
-        NetworkMode::None | NetworkMode::VzNat => {}
+        NetworkMode::None | NetworkMode::VzNat { .. } => {}
         NetworkMode::UnixDatagram { peer_path, .. } => {
             validate_unix_datagram_network(spec, peer_path)?
         }
@@ -491,6 +642,12 @@ fn validate_unix_datagram_network(spec: &VmConfig, peer_path: &Path) -> Result<(
     })
 }
 
+pub(crate) fn host_capabilities() -> HostCapabilities {
+    HostCapabilities {
+        nested_virtualization: GenericPlatform::is_nested_virtualization_supported(),
+    }
+}
+
 fn validate_nested_virtualization(spec: &VmConfig) -> Result<(), VirtError> {
     if !spec.nested_virtualization {
         return Ok(());
@@ -525,6 +682,22 @@ fn validate_rosetta(spec: &VmConfig) -> Result<(), VirtError> {
     }
 }
 
+fn validate_graphics(spec: &VmConfig) -> Result<(), VirtError> {
+    if !spec.graphics {
+        return Ok(());
+    }
+
+    // The generic platform only boots Linux guests, which drive a virtio GPU.
+    if !VirtioGraphicsDeviceConfiguration::is_supported() {
+        return Err(VirtError::InvalidConfig {
+            name: spec.name.clone(),
+            reason: "virtio graphics requires macOS 13 or newer".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
 fn validate_machine_identifier(
     name: &str,
     machine_identifier: &MachineIdentifier,
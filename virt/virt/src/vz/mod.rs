@@ -1,3 +1,3 @@
 mod backend;
 
-pub(crate) use self::backend::VzMachineBackend;
+pub(crate) use self::backend::{host_capabilities, VzMachineBackend};
@@ -1,16 +1,18 @@
 use objc2::rc::Retained;
 use objc2_virtualization::{
     VZDirectorySharingDeviceConfiguration, VZEntropyDeviceConfiguration,
-    VZMemoryBalloonDeviceConfiguration, VZNetworkDeviceConfiguration, VZSerialPortConfiguration,
+    VZGraphicsDeviceConfiguration, VZKeyboardConfiguration, VZMemoryBalloonDeviceConfiguration,
+    VZNetworkDeviceConfiguration, VZPointingDeviceConfiguration, VZSerialPortConfiguration,
     VZSocketDeviceConfiguration, VZStorageDeviceConfiguration, VZVirtualMachineConfiguration,
 };
 
 use crate::configuration::boot_loader::BootLoader;
 use crate::configuration::{GenericPlatform, LinuxBootLoader};
 use crate::device::{
-    EntropyDeviceConfiguration, MemoryBalloonDeviceConfiguration, NetworkDeviceConfiguration,
-    SerialPortConfiguration, SocketDeviceConfiguration, StorageDeviceConfiguration,
-    VirtioFileSystemDeviceConfiguration,
+    EntropyDeviceConfiguration, KeyboardConfiguration, MemoryBalloonDeviceConfiguration,
+    NetworkDeviceConfiguration, PointingDeviceConfiguration, SerialPortConfiguration,
+    SocketDeviceConfiguration, StorageDeviceConfiguration, VirtioFileSystemDeviceConfiguration,
+    VirtioGraphicsDeviceConfiguration,
 };
 use crate::error::VzError;
 use crate::utils::{is_os_version_at_least, vz_virtual_machine_is_supported};
@@ -25,6 +27,9 @@ pub struct VirtualMachineConfiguration {
     entropy_devices: Vec<EntropyDeviceConfiguration>,
     directory_sharing_devices: Vec<VirtioFileSystemDeviceConfiguration>,
     memory_balloon_devices: Vec<MemoryBalloonDeviceConfiguration>,
+    graphics_devices: Vec<VirtioGraphicsDeviceConfiguration>,
+    keyboards: Vec<KeyboardConfiguration>,
+    pointing_devices: Vec<PointingDeviceConfiguration>,
 }
 
 impl VirtualMachineConfiguration {
@@ -39,6 +44,9 @@ impl VirtualMachineConfiguration {
             entropy_devices: Vec::new(),
             directory_sharing_devices: Vec::new(),
             memory_balloon_devices: Vec::new(),
+            graphics_devices: Vec::new(),
+            keyboards: Vec::new(),
+            pointing_devices: Vec::new(),
         })
     }
 
@@ -94,6 +102,18 @@ impl VirtualMachineConfiguration {
         self.directory_sharing_devices.push(device);
     }
 
+    pub(crate) fn add_graphics_device(&mut self, device: VirtioGraphicsDeviceConfiguration) {
+        self.graphics_devices.push(device);
+    }
+
+    pub(crate) fn add_keyboard(&mut self, device: KeyboardConfiguration) {
+        self.keyboards.push(device);
+    }
+
+    pub(crate) fn add_pointing_device(&mut self, device: PointingDeviceConfiguration) {
+        self.pointing_devices.push(device);
+    }
+
     pub(crate) fn build(mut self) -> Result<Retained<VZVirtualMachineConfiguration>, VzError> {
         self.validate()?;
         self.apply_devices();
@@ -188,6 +208,36 @@ impl VirtualMachineConfiguration {
                 self.inner
                     .setMemoryBalloonDevices(&objc2_foundation::NSArray::from_slice(&refs));
             }
+
+            if !self.graphics_devices.is_empty() {
+                let refs: Vec<&VZGraphicsDeviceConfiguration> = self
+                    .graphics_devices
+                    .iter()
+                    .map(|device| device.as_inner())
+                    .collect();
+                self.inner
+                    .setGraphicsDevices(&objc2_foundation::NSArray::from_slice(&refs));
+            }
+
+            if !self.keyboards.is_empty() {
+                let refs: Vec<&VZKeyboardConfiguration> = self
+                    .keyboards
+                    .iter()
+                    .map(|device| device.as_inner())
+                    .collect();
+                self.inner
+                    .setKeyboards(&objc2_foundation::NSArray::from_slice(&refs));
+            }
+
+            if !self.pointing_devices.is_empty() {
+                let refs: Vec<&VZPointingDeviceConfiguration> = self
+                    .pointing_devices
+                    .iter()
+                    .map(|device| device.as_inner())
+                    .collect();
+                self.inner
+                    .setPointingDevices(&objc2_foundation::NSArray::from_slice(&refs));
+            }
         }
     }
 }
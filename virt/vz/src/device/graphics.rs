@@ -0,0 +1,39 @@
+use objc2::{rc::Retained, AllocAnyThread, ClassType};
+use objc2_foundation::NSArray;
+use objc2_virtualization::{
+    VZGraphicsDeviceConfiguration, VZVirtioGraphicsDeviceConfiguration,
+    VZVirtioGraphicsScanoutConfiguration,
+};
+
+use crate::utils::is_os_version_at_least;
+
+/// Virtio GPU with a single scanout, used by Linux guests for a framebuffer.
+#[derive(Debug, Clone)]
+pub struct VirtioGraphicsDeviceConfiguration {
+    inner: Retained<VZVirtioGraphicsDeviceConfiguration>,
+}
+
+impl VirtioGraphicsDeviceConfiguration {
+    pub fn new(width_in_pixels: u32, height_in_pixels: u32) -> Self {
+        unsafe {
+            let scanout =
+                VZVirtioGraphicsScanoutConfiguration::initWithWidthInPixels_heightInPixels(
+                    VZVirtioGraphicsScanoutConfiguration::alloc(),
+                    width_in_pixels as isize,
+                    height_in_pixels as isize,
+                );
+            let inner = VZVirtioGraphicsDeviceConfiguration::new();
+            inner.setScanouts(&NSArray::from_retained_slice(&[scanout]));
+            Self { inner }
+        }
+    }
+
+    /// Virtio graphics requires macOS 13 or newer.
+    pub fn is_supported() -> bool {
+        is_os_version_at_least(13, 0, 0)
+    }
+
+    pub(crate) fn as_inner(&self) -> &VZGraphicsDeviceConfiguration {
+        self.inner.as_super()
+    }
+}
@@ -0,0 +1,52 @@
+use objc2::{rc::Retained, ClassType};
+use objc2_virtualization::{
+    VZKeyboardConfiguration, VZPointingDeviceConfiguration, VZUSBKeyboardConfiguration,
+    VZUSBScreenCoordinatePointingDeviceConfiguration,
+};
+
+#[derive(Debug, Clone)]
+pub struct KeyboardConfiguration {
+    inner: Retained<VZUSBKeyboardConfiguration>,
+}
+
+impl KeyboardConfiguration {
+    pub fn new() -> Self {
+        Self {
+            inner: unsafe { VZUSBKeyboardConfiguration::new() },
+        }
+    }
+
+    pub(crate) fn as_inner(&self) -> &VZKeyboardConfiguration {
+        self.inner.as_super()
+    }
+}
+
+impl Default for KeyboardConfiguration {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// USB pointing device that reports absolute screen coordinates.
+#[derive(Debug, Clone)]
+pub struct PointingDeviceConfiguration {
+    inner: Retained<VZUSBScreenCoordinatePointingDeviceConfiguration>,
+}
+
+impl PointingDeviceConfiguration {
+    pub fn new() -> Self {
+        Self {
+            inner: unsafe { VZUSBScreenCoordinatePointingDeviceConfiguration::new() },
+        }
+    }
+
+    pub(crate) fn as_inner(&self) -> &VZPointingDeviceConfiguration {
+        self.inner.as_super()
+    }
+}
+
+impl Default for PointingDeviceConfiguration {
+    fn default() -> Self {
+        Self::new()
+    }
+}
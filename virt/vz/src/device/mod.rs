@@ -1,6 +1,8 @@
 mod balloon;
 mod entropy;
 mod filesystem;
+mod graphics;
+mod input;
 mod network;
 mod serial;
 mod socket;
@@ -12,6 +14,8 @@ pub use filesystem::{
     LinuxRosettaDirectoryShare, SharedDirectory, SingleDirectoryShare,
     VirtioFileSystemDeviceConfiguration,
 };
+pub use graphics::VirtioGraphicsDeviceConfiguration;
+pub use input::{KeyboardConfiguration, PointingDeviceConfiguration};
 pub use network::NetworkDeviceConfiguration;
 pub use serial::{SerialPortConfiguration, SerialPortStream};
 pub use socket::{
@@ -36,6 +36,12 @@ impl NetworkDeviceConfiguration {
         }
     }
 
+    pub fn nat_with_mac(mac: [u8; 6]) -> Result<Self, VzError> {
+        let config = Self::nat();
+        unsafe { set_mac_address(&config.inner, mac)? };
+        Ok(config)
+    }
+
     pub fn unix_datagram_file_handle(
         socket: impl IntoRawFd,
         mac: [u8; 6],
@@ -1,4 +1,4 @@
-use block2::StackBlock;
+use block2::{Block, StackBlock};
 use objc2::{
     define_class, msg_send,
     rc::Retained,
@@ -10,7 +10,8 @@ use objc2_foundation::{
     NSObject, NSObjectNSKeyValueObserverRegistration, NSObjectProtocol, NSString,
 };
 use objc2_virtualization::{
-    VZNetworkDevice, VZVirtualMachine, VZVirtualMachineConfiguration, VZVirtualMachineDelegate,
+    VZMemoryBalloonDevice, VZNetworkDevice, VZVirtioTraditionalMemoryBalloonDevice,
+    VZVirtualMachine, VZVirtualMachineConfiguration, VZVirtualMachineDelegate,
     VZVirtualMachineState,
 };
 use std::collections::{HashMap, HashSet};
@@ -21,9 +22,10 @@ use tokio::sync::{oneshot, watch};
 
 use crate::configuration::VirtualMachineConfiguration;
 use crate::device::{
-    EntropyDeviceConfiguration, MemoryBalloonDeviceConfiguration, NetworkDeviceConfiguration,
-    SerialPortConfiguration, SocketDeviceConfiguration, StorageDeviceConfiguration,
-    VirtioFileSystemDeviceConfiguration, VirtioSocketDevice,
+    EntropyDeviceConfiguration, KeyboardConfiguration, MemoryBalloonDeviceConfiguration,
+    NetworkDeviceConfiguration, PointingDeviceConfiguration, SerialPortConfiguration,
+    SocketDeviceConfiguration, StorageDeviceConfiguration, VirtioFileSystemDeviceConfiguration,
+    VirtioGraphicsDeviceConfiguration, VirtioSocketDevice,
 };
 use crate::dispatch::{serial_queue, DispatchQueueExt, Queue};
 use crate::error::VzError;
@@ -186,77 +188,79 @@ impl VirtualMachine {
     }
 
     pub async fn start(&self) -> Result<(), VzError> {
-        let machine = self.machine.clone();
-        let (sender, receiver) = oneshot::channel();
-        let shared_sender = Arc::new(Mutex::new(Some(sender)));
-        let completion_sender = shared_sender.clone();
+        self.complete_with("start", |machine, handler| unsafe {
+            machine.startWithCompletionHandler(handler)
+        })
+        .await
+    }
 
-        self.queue
-            .exec_block_async(&StackBlock::new(move || unsafe {
-                let completion_sender = completion_sender.clone();
-                let completion_handler = StackBlock::new(move |err: *mut NSError| {
-                    let err = err.as_ref();
-                    let result = match err {
-                        Some(error) => {
-                            Err(VzError::Backend(error.localizedDescription().to_string()))
-                        }
-                        None => Ok(()),
-                    };
-                    if let Some(sender) = completion_sender
-                        .lock()
-                        .ok()
-                        .and_then(|mut guard| guard.take())
-                    {
-                        let _ = sender.send(result);
-                    }
-                });
-
-                machine.startWithCompletionHandler(&completion_handler);
-            }));
+    pub async fn stop(&self) -> Result<(), VzError> {
+        self.complete_with("stop", |machine, handler| unsafe {
+            machine.stopWithCompletionHandler(handler)
+        })
+        .await
+    }
 
-        receiver.await.map_err(|_| {
-            VzError::Backend(
-                "start completion channel closed before result was delivered".to_string(),
-            )
-        })?
+    pub async fn pause(&self) -> Result<(), VzError> {
+        self.complete_with("pause", |machine, handler| unsafe {
+            machine.pauseWithCompletionHandler(handler)
+        })
+        .await
     }
 
-    pub async fn stop(&self) -> Result<(), VzError> {
+    pub async fn resume(&self) -> Result<(), VzError> {
+        self.complete_with("resume", |machine, handler| unsafe {
+            machine.resumeWithCompletionHandler(handler)
+        })
+        .await
+    }
+
+    /// Submits a machine operation that reports through a completion handler on
+    /// the VM queue, and waits for the handler to deliver its result.
+    async fn complete_with<F>(&self, operation: &'static str, submit: F) -> Result<(), VzError>
+    where
+        F: Fn(&VZVirtualMachine, &Block<dyn Fn(*mut NSError)>) + Clone + 'static,
+    {
         let machine = self.machine.clone();
         let (sender, receiver) = oneshot::channel();
-        let shared_sender = Arc::new(Mutex::new(Some(sender)));
-        let completion_sender = shared_sender.clone();
-
-        self.queue
-            .exec_block_async(&StackBlock::new(move || unsafe {
-                let completion_sender = completion_sender.clone();
-                let completion_handler = StackBlock::new(move |err: *mut NSError| {
-                    let err = err.as_ref();
-                    let result = match err {
-                        Some(error) => {
-                            Err(VzError::Backend(error.localizedDescription().to_string()))
-                        }
-                        None => Ok(()),
-                    };
-                    if let Some(sender) = completion_sender
-                        .lock()
-                        .ok()
-                        .and_then(|mut guard| guard.take())
-                    {
-                        let _ = sender.send(result);
-                    }
-                });
-
-                machine.stopWithCompletionHandler(&completion_handler);
-            }));
+        let completion_sender = Arc::new(Mutex::new(Some(sender)));
+
+        self.queue.exec_block_async(&StackBlock::new(move || {
+            let completion_sender = completion_sender.clone();
+            let completion_handler = StackBlock::new(move |err: *mut NSError| {
+                let result = match unsafe { err.as_ref() } {
+                    Some(error) => Err(VzError::Backend(error.localizedDescription().to_string())),
+                    None => Ok(()),
+                };
+                if let Some(sender) = completion_sender
+                    .lock()
+                    .ok()
+                    .and_then(|mut guard| guard.take())
+                {
+                    let _ = sender.send(result);
+                }
+            });
+
+            submit(&machine, &completion_handler);
+        }));
 
         receiver.await.map_err(|_| {
-            VzError::Backend(
-                "stop completion channel closed before result was delivered".to_string(),
-            )
+            VzError::Backend(format!(
+                "{operation} completion channel closed before result was delivered"
+            ))
         })?
     }
 
+    pub fn can_pause(&self) -> bool {
+        self.queue
+            .exec_sync_with_result(move || unsafe { self.machine.canPause() })
+    }
+
+    pub fn can_resume(&self) -> bool {
+        self.queue
+            .exec_sync_with_result(move || unsafe { self.machine.canResume() })
+    }
+
     pub fn can_request_stop(&self) -> bool {
         self.queue
             .exec_sync_with_result(move || unsafe { self.machine.canRequestStop() })
@@ -304,6 +308,51 @@ impl VirtualMachine {
         self.state_tx.subscribe()
     }
 
+    /// Returns the memory in bytes the balloon device currently leaves the guest.
+    ///
+    /// Returns `None` when the machine has no memory balloon device.
+    pub fn memory_balloon_target(&self) -> Option<u64> {
+        self.queue.exec_sync_with_result(move || unsafe {
+            let devices = self.machine.memoryBalloonDevices();
+            if devices.count() == 0 {
+                return None;
+            }
+            let device: Retained<VZMemoryBalloonDevice> = devices.objectAtIndex(0);
+            device
+                .downcast_ref::<VZVirtioTraditionalMemoryBalloonDevice>()
+                .map(|balloon| balloon.targetVirtualMachineMemorySize())
+        })
+    }
+
+    /// Asks the balloon device to leave the guest `size_bytes` of memory.
+    ///
+    /// Virtualization.framework rounds the target down to whole MiB and clamps it to the
+    /// configured memory size.
+    pub fn set_memory_balloon_target(&self, size_bytes: u64) -> Result<(), VzError> {
+        let applied = self.queue.exec_sync_with_result(move || unsafe {
+            let devices = self.machine.memoryBalloonDevices();
+            if devices.count() == 0 {
+                return false;
+            }
+            let device: Retained<VZMemoryBalloonDevice> = devices.objectAtIndex(0);
+            match device.downcast_ref::<VZVirtioTraditionalMemoryBalloonDevice>() {
+                Some(balloon) => {
+                    balloon.setTargetVirtualMachineMemorySize(size_bytes);
+                    true
+                }
+                None => false,
+            }
+        });
+
+        if applied {
+            Ok(())
+        } else {
+            Err(VzError::Backend(
+                "machine has no memory balloon device".to_string(),
+            ))
+        }
+    }
+
     pub fn open_devices(&self) -> Vec<VirtioSocketDevice> {
         let count = self
             .queue
@@ -378,6 +427,21 @@ impl VirtualMachineBuilder {
         self
     }
 
+    pub fn add_graphics_device(mut self, device: VirtioGraphicsDeviceConfiguration) -> Self {
+        self.config.add_graphics_device(device);
+        self
+    }
+
+    pub fn add_keyboard(mut self, device: KeyboardConfiguration) -> Self {
+        self.config.add_keyboard(device);
+        self
+    }
+
+    pub fn add_pointing_device(mut self, device: PointingDeviceConfiguration) -> Self {
+        self.config.add_pointing_device(device);
+        self
+    }
+
     pub fn build(self) -> Result<VirtualMachine, VzError> {
         let machine_config = self.config.build()?;
 